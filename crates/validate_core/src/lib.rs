@@ -0,0 +1,169 @@
+//! `validate_macro::Validate` derive が生成するコードから参照される実行時の型。
+//!
+//! proc-macro クレートは trait や型を公開できないため、このクレートに分離している。
+
+use std::fmt;
+
+pub mod rules;
+
+/// フィールド単位の検証を行う型。通常は `#[derive(validate_macro::Validate)]` で実装する。
+pub trait Validate {
+    /// `path` を起点に検証し、違反をすべて `errors` に追加する。
+    fn validate_into(&self, path: &str, errors: &mut ValidationErrors);
+
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::default();
+        self.validate_into("", &mut errors);
+        errors.into_result()
+    }
+}
+
+impl<T: Validate> Validate for Option<T> {
+    fn validate_into(&self, path: &str, errors: &mut ValidationErrors) {
+        if let Some(value) = self {
+            value.validate_into(path, errors);
+        }
+    }
+}
+
+impl<T: Validate> Validate for Vec<T> {
+    fn validate_into(&self, path: &str, errors: &mut ValidationErrors) {
+        for (index, value) in self.iter().enumerate() {
+            value.validate_into(&index_path(path, index), errors);
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ViolationKind {
+    MaxChars { len: usize, max: usize },
+    MaxLen { len: usize, max: usize },
+    Empty,
+}
+
+impl fmt::Display for ViolationKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ViolationKind::MaxChars { len, max } => {
+                write!(f, "must be at most {max} chars (got {len})")
+            }
+            ViolationKind::MaxLen { len, max } => {
+                write!(f, "must have at most {max} items (got {len})")
+            }
+            ViolationKind::Empty => write!(f, "must not be empty"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldViolation {
+    /// `embeds[0].fields[2].value` 形式のフィールドパス
+    pub path: String,
+    pub kind: ViolationKind,
+}
+
+impl fmt::Display for FieldViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.path, self.kind)
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ValidationErrors {
+    violations: Vec<FieldViolation>,
+}
+
+impl ValidationErrors {
+    pub fn push(&mut self, path: impl Into<String>, kind: ViolationKind) {
+        self.violations.push(FieldViolation {
+            path: path.into(),
+            kind,
+        });
+    }
+
+    pub fn violations(&self) -> &[FieldViolation] {
+        &self.violations
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.violations.is_empty()
+    }
+
+    pub fn into_result(self) -> Result<(), Self> {
+        if self.is_empty() {
+            Ok(())
+        } else {
+            Err(self)
+        }
+    }
+}
+
+impl fmt::Display for ValidationErrors {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (index, violation) in self.violations.iter().enumerate() {
+            if index > 0 {
+                write!(f, "; ")?;
+            }
+            write!(f, "{violation}")?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ValidationErrors {}
+
+pub fn field_path(parent: &str, field: &str) -> String {
+    if parent.is_empty() {
+        field.to_string()
+    } else {
+        format!("{parent}.{field}")
+    }
+}
+
+pub fn index_path(parent: &str, index: usize) -> String {
+    format!("{parent}[{index}]")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Leaf(String);
+
+    impl Validate for Leaf {
+        fn validate_into(&self, path: &str, errors: &mut ValidationErrors) {
+            rules::non_empty(&self.0, &field_path(path, "name"), errors);
+        }
+    }
+
+    #[test]
+    fn collects_indexed_paths_for_vec() {
+        let leaves = vec![Leaf("a".into()), Leaf(String::new()), Leaf(String::new())];
+        let mut errors = ValidationErrors::default();
+        leaves.validate_into("items", &mut errors);
+
+        let paths = errors
+            .violations()
+            .iter()
+            .map(|v| v.path.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(paths, vec!["items[1].name", "items[2].name"]);
+    }
+
+    #[test]
+    fn skips_none() {
+        let leaf: Option<Leaf> = None;
+        assert!(leaf.validate().is_ok());
+    }
+
+    #[test]
+    fn displays_all_violations() {
+        let mut errors = ValidationErrors::default();
+        errors.push("content", ViolationKind::MaxChars { len: 3, max: 2 });
+        errors.push("title", ViolationKind::Empty);
+        assert_eq!(
+            errors.to_string(),
+            "content: must be at most 2 chars (got 3); title: must not be empty"
+        );
+    }
+}
@@ -0,0 +1,150 @@
+//! derive が各フィールド属性ごとに呼び出す検証関数。
+//! `Option` のフィールドは `None` のとき検証をスキップする。
+
+use crate::{ValidationErrors, ViolationKind};
+
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` does not support `#[validate(max_chars = ..)]`",
+    label = "expected `String`, `&str` or `Option<String>`"
+)]
+pub trait CharCount {
+    fn char_count(&self) -> Option<usize>;
+}
+
+impl CharCount for String {
+    fn char_count(&self) -> Option<usize> {
+        Some(self.chars().count())
+    }
+}
+
+impl CharCount for &str {
+    fn char_count(&self) -> Option<usize> {
+        Some(self.chars().count())
+    }
+}
+
+impl<T: CharCount> CharCount for Option<T> {
+    fn char_count(&self) -> Option<usize> {
+        self.as_ref().and_then(CharCount::char_count)
+    }
+}
+
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` does not support `#[validate(max_len = ..)]`",
+    label = "expected `Vec<_>` or `Option<Vec<_>>`"
+)]
+pub trait Length {
+    fn length(&self) -> Option<usize>;
+}
+
+impl<T> Length for Vec<T> {
+    fn length(&self) -> Option<usize> {
+        Some(self.len())
+    }
+}
+
+impl<T: Length> Length for Option<T> {
+    fn length(&self) -> Option<usize> {
+        self.as_ref().and_then(Length::length)
+    }
+}
+
+#[diagnostic::on_unimplemented(
+    message = "`{Self}` does not support `#[validate(non_empty)]`",
+    label = "expected a string, a `Vec<_>` or an `Option` of them"
+)]
+pub trait Emptiness {
+    /// 空であれば `Some(true)`、値が無ければ `None` を返す。
+    fn is_empty_value(&self) -> Option<bool>;
+}
+
+impl Emptiness for String {
+    fn is_empty_value(&self) -> Option<bool> {
+        Some(self.is_empty())
+    }
+}
+
+impl Emptiness for &str {
+    fn is_empty_value(&self) -> Option<bool> {
+        Some(self.is_empty())
+    }
+}
+
+impl<T> Emptiness for Vec<T> {
+    fn is_empty_value(&self) -> Option<bool> {
+        Some(self.is_empty())
+    }
+}
+
+impl<T: Emptiness> Emptiness for Option<T> {
+    fn is_empty_value(&self) -> Option<bool> {
+        self.as_ref().and_then(Emptiness::is_empty_value)
+    }
+}
+
+pub fn max_chars<T: CharCount + ?Sized>(
+    value: &T,
+    max: usize,
+    path: &str,
+    errors: &mut ValidationErrors,
+) {
+    if let Some(len) = value.char_count() {
+        if len > max {
+            errors.push(path, ViolationKind::MaxChars { len, max });
+        }
+    }
+}
+
+pub fn max_len<T: Length + ?Sized>(
+    value: &T,
+    max: usize,
+    path: &str,
+    errors: &mut ValidationErrors,
+) {
+    if let Some(len) = value.length() {
+        if len > max {
+            errors.push(path, ViolationKind::MaxLen { len, max });
+        }
+    }
+}
+
+pub fn non_empty<T: Emptiness + ?Sized>(value: &T, path: &str, errors: &mut ValidationErrors) {
+    if value.is_empty_value() == Some(true) {
+        errors.push(path, ViolationKind::Empty);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_chars_not_bytes() {
+        let mut errors = ValidationErrors::default();
+        max_chars(&"あいう".to_string(), 3, "content", &mut errors);
+        assert!(errors.is_empty());
+    }
+
+    #[test]
+    fn ignores_none_for_every_rule() {
+        let mut errors = ValidationErrors::default();
+        let none_string: Option<String> = None;
+        let none_vec: Option<Vec<u8>> = None;
+        max_chars(&none_string, 0, "a", &mut errors);
+        max_len(&none_vec, 0, "b", &mut errors);
+        non_empty(&none_string, "c", &mut errors);
+        assert!(errors.is_empty());
+    }
+
+    #[test]
+    fn reports_empty_and_oversized_values() {
+        let mut errors = ValidationErrors::default();
+        non_empty(&String::new(), "name", &mut errors);
+        max_len(&vec![1, 2, 3], 2, "items", &mut errors);
+        assert_eq!(errors.violations()[0].kind, ViolationKind::Empty,);
+        assert_eq!(
+            errors.violations()[1].kind,
+            ViolationKind::MaxLen { len: 3, max: 2 }
+        );
+    }
+}
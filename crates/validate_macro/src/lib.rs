@@ -1,6 +1,7 @@
 use proc_macro::TokenStream;
-use quote::quote;
-use syn::{parse_macro_input, ItemFn, Path};
+use quote::{quote, quote_spanned};
+use syn::spanned::Spanned;
+use syn::{parse_macro_input, Data, DeriveInput, Expr, Fields, ItemFn, Path, Token};
 
 #[proc_macro_attribute]
 pub fn sync_validate_return(attr: TokenStream, item: TokenStream) -> TokenStream {
@@ -61,3 +62,139 @@ pub fn async_validate_return(attr: TokenStream, item: TokenStream) -> TokenStrea
     input.block = Box::new(wrapped_block);
     TokenStream::from(quote!(#input))
 }
+
+/// フィールド属性に従って `validate_core::Validate` を実装する。
+///
+/// - `#[validate(max_chars = N)]`: 文字数が N 以下（N は `usize` の定数式）
+/// - `#[validate(max_len = N)]`: 要素数が N 以下（N は `usize` の定数式）
+/// - `#[validate(non_empty)]`: 空でない
+/// - `#[validate(nested)]`: フィールド自身の `Validate` を呼び出す
+///
+/// `Option` のフィールドは `None` のとき検証をスキップする。
+#[proc_macro_derive(Validate, attributes(validate))]
+pub fn derive_validate(item: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(item as DeriveInput);
+    match expand_validate(input) {
+        Ok(tokens) => TokenStream::from(tokens),
+        Err(err) => TokenStream::from(err.to_compile_error()),
+    }
+}
+
+enum Rule {
+    MaxChars(Expr),
+    MaxLen(Expr),
+    NonEmpty,
+    Nested,
+}
+
+fn expand_validate(input: DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => {
+                return Err(syn::Error::new(
+                    input.ident.span(),
+                    "Validate can only be derived for structs with named fields",
+                ))
+            }
+        },
+        _ => {
+            return Err(syn::Error::new(
+                input.ident.span(),
+                "Validate can only be derived for structs with named fields",
+            ))
+        }
+    };
+
+    let mut checks = Vec::new();
+    for field in fields {
+        let rules = parse_rules(&field.attrs)?;
+        if rules.is_empty() {
+            continue;
+        }
+        let ident = field.ident.as_ref().expect("named field");
+        let name = ident.to_string();
+        let span = field.ty.span();
+        let calls = rules.iter().map(|rule| match rule {
+            Rule::MaxChars(max) => quote_spanned! {span=>
+                ::validate_core::rules::max_chars(&self.#ident, #max, &__path, __errors);
+            },
+            Rule::MaxLen(max) => quote_spanned! {span=>
+                ::validate_core::rules::max_len(&self.#ident, #max, &__path, __errors);
+            },
+            Rule::NonEmpty => quote_spanned! {span=>
+                ::validate_core::rules::non_empty(&self.#ident, &__path, __errors);
+            },
+            Rule::Nested => quote_spanned! {span=>
+                ::validate_core::Validate::validate_into(&self.#ident, &__path, __errors);
+            },
+        });
+        checks.push(quote! {
+            {
+                let __path = ::validate_core::field_path(__parent, #name);
+                #(#calls)*
+            }
+        });
+    }
+
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::validate_core::Validate for #ident #ty_generics #where_clause {
+            fn validate_into(
+                &self,
+                __parent: &str,
+                __errors: &mut ::validate_core::ValidationErrors,
+            ) {
+                #(#checks)*
+            }
+        }
+    })
+}
+
+fn parse_rules(attrs: &[syn::Attribute]) -> syn::Result<Vec<Rule>> {
+    let mut rules = Vec::new();
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("validate")) {
+        attr.parse_nested_meta(|meta| {
+            let limit_rule = if meta.path.is_ident("max_chars") {
+                Some(Rule::MaxChars as fn(Expr) -> Rule)
+            } else if meta.path.is_ident("max_len") {
+                Some(Rule::MaxLen as fn(Expr) -> Rule)
+            } else {
+                None
+            };
+
+            if let Some(rule) = limit_rule {
+                if !meta.input.peek(Token![=]) {
+                    return Err(meta.error(format!(
+                        "`{}` requires a limit, e.g. `{} = 100`",
+                        path_name(&meta.path),
+                        path_name(&meta.path)
+                    )));
+                }
+                let max: Expr = meta.value()?.parse()?;
+                rules.push(rule(max));
+                return Ok(());
+            }
+
+            if meta.path.is_ident("non_empty") {
+                rules.push(Rule::NonEmpty);
+                return Ok(());
+            }
+            if meta.path.is_ident("nested") {
+                rules.push(Rule::Nested);
+                return Ok(());
+            }
+
+            Err(meta.error(format!(
+                "unknown validate rule `{}`; expected `max_chars`, `max_len`, `non_empty` or `nested`",
+                path_name(&meta.path)
+            )))
+        })?;
+    }
+    Ok(rules)
+}
+
+fn path_name(path: &Path) -> String {
+    quote!(#path).to_string()
+}
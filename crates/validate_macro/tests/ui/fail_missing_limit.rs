@@ -0,0 +1,9 @@
+use validate_macro::Validate;
+
+#[derive(Validate)]
+struct Payload {
+    #[validate(max_chars)]
+    content: String,
+}
+
+fn main() {}
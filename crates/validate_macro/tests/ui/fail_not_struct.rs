@@ -0,0 +1,8 @@
+use validate_macro::Validate;
+
+#[derive(Validate)]
+enum Payload {
+    Text(String),
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use validate_macro::Validate;
+
+#[derive(Validate)]
+struct Payload {
+    #[validate(min_chars = 1)]
+    content: String,
+}
+
+fn main() {}
@@ -0,0 +1,58 @@
+use validate_core::{Validate, ViolationKind};
+use validate_macro::Validate;
+
+const MAX_CONTENT: usize = 3;
+
+#[derive(Validate)]
+struct Field {
+    #[validate(non_empty, max_chars = 5)]
+    name: String,
+}
+
+#[derive(Validate)]
+struct Payload {
+    #[validate(max_chars = MAX_CONTENT)]
+    content: Option<String>,
+    #[validate(max_len = 2, nested)]
+    fields: Vec<Field>,
+    #[validate(nested)]
+    extra: Option<Field>,
+    untouched: u32,
+}
+
+fn main() {
+    let ok = Payload {
+        content: Some("abc".into()),
+        fields: vec![Field { name: "a".into() }],
+        extra: None,
+        untouched: 0,
+    };
+    assert!(ok.validate().is_ok());
+
+    let ng = Payload {
+        content: Some("abcd".into()),
+        fields: vec![
+            Field { name: "a".into() },
+            Field { name: String::new() },
+            Field { name: "toolong".into() },
+        ],
+        extra: Some(Field { name: String::new() }),
+        untouched: 0,
+    };
+    let errors = ng.validate().expect_err("expected violations");
+    let violations = errors
+        .violations()
+        .iter()
+        .map(|v| (v.path.as_str(), v.kind.clone()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        violations,
+        vec![
+            ("content", ViolationKind::MaxChars { len: 4, max: 3 }),
+            ("fields", ViolationKind::MaxLen { len: 3, max: 2 }),
+            ("fields[1].name", ViolationKind::Empty),
+            ("fields[2].name", ViolationKind::MaxChars { len: 7, max: 5 }),
+            ("extra.name", ViolationKind::Empty),
+        ]
+    );
+}
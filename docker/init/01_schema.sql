@@ -39,3 +39,96 @@ CREATE TABLE IF NOT EXISTS mention_dones (
   done_at BIGINT NOT NULL,
   PRIMARY KEY (mention_id, user_id)
 );
+
+CREATE INDEX IF NOT EXISTS idx_mentions_guild_created_at
+  ON mentions (guild_id, created_at DESC);
+
+CREATE TABLE IF NOT EXISTS guild_settings (
+  guild_id BIGINT PRIMARY KEY,
+  report_channel_id BIGINT NULL,
+  report_name_users BOOLEAN NOT NULL DEFAULT TRUE
+);
+
+ALTER TABLE guild_settings ADD COLUMN IF NOT EXISTS all_read_notify TEXT NOT NULL DEFAULT 'off';
+
+CREATE TABLE IF NOT EXISTS user_settings (
+  user_id BIGINT PRIMARY KEY,
+  all_read_notify TEXT NULL
+);
+
+ALTER TABLE guild_settings ADD COLUMN IF NOT EXISTS escalate_author_after_hours INTEGER NULL;
+ALTER TABLE guild_settings ADD COLUMN IF NOT EXISTS escalate_reping_after_hours INTEGER NULL;
+
+CREATE TABLE IF NOT EXISTS mention_escalations (
+  mention_id BIGINT NOT NULL REFERENCES mentions(id) ON DELETE CASCADE,
+  kind TEXT NOT NULL,
+  sent_at BIGINT NOT NULL,
+  PRIMARY KEY (mention_id, kind)
+);
+
+ALTER TABLE mentions ADD COLUMN IF NOT EXISTS due_at BIGINT NULL;
+
+CREATE INDEX IF NOT EXISTS idx_mentions_due_at
+  ON mentions (due_at) WHERE due_at IS NOT NULL;
+
+CREATE TABLE IF NOT EXISTS mention_due_reminders (
+  mention_id BIGINT NOT NULL REFERENCES mentions(id) ON DELETE CASCADE,
+  user_id BIGINT NOT NULL,
+  kind TEXT NOT NULL,
+  sent_at BIGINT NOT NULL,
+  PRIMARY KEY (mention_id, user_id, kind)
+);
+
+ALTER TABLE mentions ADD COLUMN IF NOT EXISTS priority TEXT NOT NULL DEFAULT 'normal';
+
+CREATE TABLE IF NOT EXISTS channel_settings (
+  channel_id BIGINT PRIMARY KEY,
+  guild_id BIGINT NOT NULL,
+  default_priority TEXT NULL
+);
+
+CREATE TABLE IF NOT EXISTS tracking_scope_rules (
+  guild_id BIGINT NOT NULL,
+  kind TEXT NOT NULL,
+  target_id BIGINT NOT NULL,
+  mode TEXT NOT NULL,
+  PRIMARY KEY (guild_id, kind, target_id)
+);
+
+ALTER TABLE user_settings ADD COLUMN IF NOT EXISTS digest_dm_disabled BOOLEAN NOT NULL DEFAULT FALSE;
+ALTER TABLE user_settings ADD COLUMN IF NOT EXISTS tracking_opt_out BOOLEAN NOT NULL DEFAULT FALSE;
+
+CREATE TABLE IF NOT EXISTS user_mutes (
+  user_id BIGINT NOT NULL,
+  kind TEXT NOT NULL,
+  target_id BIGINT NOT NULL,
+  PRIMARY KEY (user_id, kind, target_id)
+);
+
+ALTER TABLE guild_settings ADD COLUMN IF NOT EXISTS retention_days INTEGER NULL;
+
+CREATE INDEX IF NOT EXISTS idx_mentions_created_at
+  ON mentions (created_at);
+
+ALTER TABLE guild_settings ADD COLUMN IF NOT EXISTS content_storage TEXT NOT NULL DEFAULT 'full';
+
+CREATE TABLE IF NOT EXISTS departed_guilds (
+  guild_id BIGINT PRIMARY KEY,
+  left_at BIGINT NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS mention_completion_notifications (
+  mention_id BIGINT NOT NULL REFERENCES mentions(id) ON DELETE CASCADE,
+  kind TEXT NOT NULL,
+  notified_at BIGINT NOT NULL,
+  PRIMARY KEY (mention_id, kind)
+);
+
+CREATE TABLE IF NOT EXISTS untracked_messages (
+  message_id BIGINT PRIMARY KEY,
+  guild_id BIGINT NOT NULL,
+  untracked_at BIGINT NOT NULL
+);
+
+ALTER TABLE mention_reads ADD COLUMN IF NOT EXISTS imported BOOLEAN NOT NULL DEFAULT FALSE;
+ALTER TABLE mention_dones ADD COLUMN IF NOT EXISTS imported BOOLEAN NOT NULL DEFAULT FALSE;
@@ -1,8 +1,10 @@
 pub mod message;
+pub mod tracking_scope;
 
 use serenity::model::prelude::{RoleId, UserId};
 
 pub use message::Message;
+pub use tracking_scope::{ScopeMode, ScopeRule, ScopeTarget};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum MentionType {
@@ -11,3 +13,71 @@ pub enum MentionType {
     Everyone,
     Here,
 }
+
+/// メンションの優先度。FYI は共有のみで対応（解決）を求めない。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MentionPriority {
+    #[default]
+    Normal,
+    Urgent,
+    Fyi,
+}
+
+impl MentionPriority {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            MentionPriority::Normal => "normal",
+            MentionPriority::Urgent => "urgent",
+            MentionPriority::Fyi => "fyi",
+        }
+    }
+
+    /// 未知の値は通常扱いにする。
+    pub fn from_db(value: &str) -> Self {
+        match value {
+            "urgent" => MentionPriority::Urgent,
+            "fyi" => MentionPriority::Fyi,
+            _ => MentionPriority::Normal,
+        }
+    }
+
+    /// 対応（解決）を求めるか。FYI では DONE リアクションもリマインダーも付けない。
+    pub fn requires_action(self) -> bool {
+        self != MentionPriority::Fyi
+    }
+}
+
+/// ギルドごとのメッセージ本文の保存方式。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ContentStorageMode {
+    /// 本文をそのまま保存する。
+    #[default]
+    Full,
+    /// 表示に使う先頭部分だけを保存する。
+    Snippet,
+    /// 保存せず、表示するときに Discord から取得する。
+    None,
+    /// 設定された鍵で暗号化して保存する。
+    Encrypted,
+}
+
+impl ContentStorageMode {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ContentStorageMode::Full => "full",
+            ContentStorageMode::Snippet => "snippet",
+            ContentStorageMode::None => "none",
+            ContentStorageMode::Encrypted => "encrypted",
+        }
+    }
+
+    /// 未知の値は全文保存扱いにする。
+    pub fn from_db(value: &str) -> Self {
+        match value {
+            "snippet" => ContentStorageMode::Snippet,
+            "none" => ContentStorageMode::None,
+            "encrypted" => ContentStorageMode::Encrypted,
+            _ => ContentStorageMode::Full,
+        }
+    }
+}
@@ -0,0 +1,64 @@
+/// 追跡範囲のルールの対象。カテゴリもチャンネル ID で指定する。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScopeTarget {
+    Channel(u64),
+    Role(u64),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScopeMode {
+    /// 許可リスト。同じ種類の include が1件でもあれば、含まれないものは追跡しない。
+    Include,
+    /// 拒否リスト。チャンネルなら追跡せず、ロールなら対象者に展開しない。
+    Exclude,
+    /// チャンネルでの @everyone / @here だけを追跡しない。
+    ExcludeEveryone,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ScopeRule {
+    pub target: ScopeTarget,
+    pub mode: ScopeMode,
+}
+
+impl ScopeTarget {
+    pub fn kind_str(self) -> &'static str {
+        match self {
+            ScopeTarget::Channel(_) => "channel",
+            ScopeTarget::Role(_) => "role",
+        }
+    }
+
+    pub fn id(self) -> u64 {
+        match self {
+            ScopeTarget::Channel(id) | ScopeTarget::Role(id) => id,
+        }
+    }
+
+    pub fn from_db(kind: &str, id: u64) -> Option<Self> {
+        match kind {
+            "channel" => Some(ScopeTarget::Channel(id)),
+            "role" => Some(ScopeTarget::Role(id)),
+            _ => None,
+        }
+    }
+}
+
+impl ScopeMode {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ScopeMode::Include => "include",
+            ScopeMode::Exclude => "exclude",
+            ScopeMode::ExcludeEveryone => "exclude_everyone",
+        }
+    }
+
+    pub fn from_db(value: &str) -> Option<Self> {
+        match value {
+            "include" => Some(ScopeMode::Include),
+            "exclude" => Some(ScopeMode::Exclude),
+            "exclude_everyone" => Some(ScopeMode::ExcludeEveryone),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,55 @@
+use crate::domain::model::ContentStorageMode;
+
+/// 抜粋のみ保存する場合の文字数。一覧・既読状況で表示する最大の長さに合わせる。
+pub const SNIPPET_MAX_CHARS: usize = 100;
+
+/// 保存方式に従って DB に保存する本文を返す。
+/// 暗号化は保存時にインフラ層で行うため、`Encrypted` では本文をそのまま返す。
+pub fn content_to_store(content: &str, mode: ContentStorageMode) -> String {
+    match mode {
+        ContentStorageMode::Full | ContentStorageMode::Encrypted => content.to_string(),
+        ContentStorageMode::Snippet => {
+            let mut snippet = content.chars().take(SNIPPET_MAX_CHARS).collect::<String>();
+            if content.chars().count() > SNIPPET_MAX_CHARS {
+                snippet.push('…');
+            }
+            snippet
+        }
+        ContentStorageMode::None => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_full_content() {
+        let content = "あ".repeat(300);
+        assert_eq!(
+            content_to_store(&content, ContentStorageMode::Full),
+            content
+        );
+        assert_eq!(
+            content_to_store(&content, ContentStorageMode::Encrypted),
+            content
+        );
+    }
+
+    #[test]
+    fn stores_only_snippet() {
+        let content = "あ".repeat(300);
+        let stored = content_to_store(&content, ContentStorageMode::Snippet);
+        assert_eq!(stored.chars().count(), SNIPPET_MAX_CHARS + 1);
+        assert!(stored.ends_with('…'));
+        assert_eq!(
+            content_to_store("短い本文", ContentStorageMode::Snippet),
+            "短い本文"
+        );
+    }
+
+    #[test]
+    fn stores_nothing() {
+        assert_eq!(content_to_store("秘密", ContentStorageMode::None), "");
+    }
+}
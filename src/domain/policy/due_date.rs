@@ -0,0 +1,451 @@
+use chrono::{Datelike, Duration, NaiveDate, NaiveDateTime, NaiveTime};
+
+/// 期限の直後に日付・時刻が続く語。英語は単語境界を確認する。
+const PREFIX_CUES_JA: [&str; 4] = ["締め切り", "〆切", "締切", "期限"];
+const PREFIX_CUES_EN: [&str; 4] = ["deadline", "until", "due", "by"];
+/// 直前に日付・時刻が来る語。
+const SUFFIX_CUE: &str = "まで";
+/// 「まで」の前を遡って期限表現を探す最大文字数。
+const SUFFIX_LOOKBACK_CHARS: usize = 20;
+
+const WEEKDAYS_JA: [char; 7] = ['月', '火', '水', '木', '金', '土', '日'];
+const WEEKDAYS_EN: [(&str, &str); 7] = [
+    ("monday", "mon"),
+    ("tuesday", "tue"),
+    ("wednesday", "wed"),
+    ("thursday", "thu"),
+    ("friday", "fri"),
+    ("saturday", "sat"),
+    ("sunday", "sun"),
+];
+
+/// メッセージ本文から期限を読み取る。`now` は利用者のローカル時刻（JST）。
+///
+/// 「期限: 金曜」「締切 11/1 18:00」「来週月曜までに」「by 2026-11-01」「due tomorrow 5pm」
+/// のように、期限を示す語に隣接した日付・時刻だけを対象にする。
+/// 時刻を省略した場合はその日の 23:59 を期限とする。
+pub fn parse_due_at(content: &str, now: NaiveDateTime) -> Option<NaiveDateTime> {
+    let chars = content.to_lowercase().chars().collect::<Vec<_>>();
+    let mut candidates = Vec::new();
+
+    for cue in PREFIX_CUES_JA {
+        for pos in find_all(&chars, cue) {
+            let start = skip_separators(&chars, pos + cue.chars().count());
+            candidates.push((pos, parse_expr_at(&chars, start, now).map(|(at, _)| at)));
+        }
+    }
+    for cue in PREFIX_CUES_EN {
+        for pos in find_all(&chars, cue) {
+            let end = pos + cue.len();
+            if !is_word_boundary(&chars, pos, end) {
+                continue;
+            }
+            let start = skip_word(&chars, skip_separators(&chars, end), "on");
+            candidates.push((pos, parse_expr_at(&chars, start, now).map(|(at, _)| at)));
+        }
+    }
+    for pos in find_all(&chars, SUFFIX_CUE) {
+        candidates.push((pos, parse_expr_before(&chars, pos, now)));
+    }
+
+    candidates.sort_by_key(|(pos, _)| *pos);
+    candidates.into_iter().find_map(|(_, at)| at)
+}
+
+/// `end` の直前で終わる期限表現のうち、最も長いものを返す。
+fn parse_expr_before(chars: &[char], end: usize, now: NaiveDateTime) -> Option<NaiveDateTime> {
+    let mut expr_end = end;
+    while expr_end > 0 && chars[expr_end - 1].is_whitespace() {
+        expr_end -= 1;
+    }
+    let earliest = expr_end.saturating_sub(SUFFIX_LOOKBACK_CHARS);
+    (earliest..expr_end)
+        .filter(|start| *start == 0 || !chars[start - 1].is_ascii_digit())
+        .find_map(|start| match parse_expr_at(chars, start, now) {
+            Some((at, consumed_end)) if consumed_end == expr_end => Some(at),
+            _ => None,
+        })
+}
+
+/// `start` から始まる「日付 [時刻]」または「時刻」を読み取り、期限と読み終えた位置を返す。
+fn parse_expr_at(
+    chars: &[char],
+    start: usize,
+    now: NaiveDateTime,
+) -> Option<(NaiveDateTime, usize)> {
+    let today = now.date();
+    let date = parse_date(chars, start, today);
+    let time_start = match date {
+        Some((_, end)) => skip_connectors(chars, end),
+        None => start,
+    };
+    let time = parse_time(chars, time_start);
+
+    match (date, time) {
+        (Some((date, _)), Some((time, end))) => Some((date.and_time(time), end)),
+        (Some((date, end)), None) => Some((date.and_time(end_of_day()), end)),
+        (None, Some((time, end))) => {
+            // 日付のない時刻は、今日のその時刻を過ぎていれば明日とみなす。
+            let date = if time > now.time() {
+                today
+            } else {
+                today + Duration::days(1)
+            };
+            Some((date.and_time(time), end))
+        }
+        (None, None) => None,
+    }
+}
+
+fn end_of_day() -> NaiveTime {
+    NaiveTime::from_hms_opt(23, 59, 0).expect("valid time")
+}
+
+fn parse_date(chars: &[char], start: usize, today: NaiveDate) -> Option<(NaiveDate, usize)> {
+    parse_absolute_date(chars, start, today)
+        .or_else(|| parse_relative_day(chars, start, today))
+        .or_else(|| parse_weekday_expr(chars, start, today))
+}
+
+/// 2026-11-01 / 2026/11/01 / 2026年11月1日 / 11/1 / 11月1日
+fn parse_absolute_date(
+    chars: &[char],
+    start: usize,
+    today: NaiveDate,
+) -> Option<(NaiveDate, usize)> {
+    if let Some((year, pos)) = parse_digits(chars, start, 4, 4) {
+        let (sep, month_sep_end) = match chars.get(pos) {
+            Some('-') => ('-', pos + 1),
+            Some('/') => ('/', pos + 1),
+            Some('年') => ('月', pos + 1),
+            _ => return None,
+        };
+        let (month, pos) = parse_digits(chars, month_sep_end, 1, 2)?;
+        if chars.get(pos) != Some(&sep) {
+            return None;
+        }
+        let (day, mut pos) = parse_digits(chars, pos + 1, 1, 2)?;
+        if sep == '月' {
+            if chars.get(pos) != Some(&'日') {
+                return None;
+            }
+            pos += 1;
+        }
+        let date = NaiveDate::from_ymd_opt(year as i32, month, day)?;
+        return Some((date, pos));
+    }
+
+    let (month, pos) = parse_digits(chars, start, 1, 2)?;
+    let (day, end) = match chars.get(pos) {
+        Some('/') => parse_digits(chars, pos + 1, 1, 2)?,
+        Some('月') => {
+            let (day, pos) = parse_digits(chars, pos + 1, 1, 2)?;
+            if chars.get(pos) != Some(&'日') {
+                return None;
+            }
+            (day, pos + 1)
+        }
+        _ => return None,
+    };
+    // 年を省略した日付は、今日以降で最も近い日とする。
+    let this_year = NaiveDate::from_ymd_opt(today.year(), month, day)?;
+    let date = if this_year < today {
+        NaiveDate::from_ymd_opt(today.year() + 1, month, day)?
+    } else {
+        this_year
+    };
+    Some((date, end))
+}
+
+fn parse_relative_day(
+    chars: &[char],
+    start: usize,
+    today: NaiveDate,
+) -> Option<(NaiveDate, usize)> {
+    const WORDS: [(&str, i64); 8] = [
+        ("明後日", 2),
+        ("あさって", 2),
+        ("明日", 1),
+        ("あした", 1),
+        ("今日", 0),
+        ("本日", 0),
+        ("tomorrow", 1),
+        ("today", 0),
+    ];
+    WORDS.iter().find_map(|(word, offset)| {
+        let end = match_word(chars, start, word)?;
+        if word.is_ascii() && !is_word_boundary(chars, start, end) {
+            return None;
+        }
+        Some((today + Duration::days(*offset), end))
+    })
+}
+
+/// 金曜 / 来週の金曜日 / 今週水曜 / 来週 / friday / next fri / next week
+fn parse_weekday_expr(
+    chars: &[char],
+    start: usize,
+    today: NaiveDate,
+) -> Option<(NaiveDate, usize)> {
+    let this_monday = today - Duration::days(today.weekday().num_days_from_monday() as i64);
+    let next_monday = this_monday + Duration::days(7);
+
+    let (week_start, pos) = if let Some(end) = match_word(chars, start, "来週") {
+        (Some(next_monday), skip_word(chars, end, "の"))
+    } else if let Some(end) = match_word(chars, start, "今週") {
+        (Some(this_monday), skip_word(chars, end, "の"))
+    } else if let Some(end) = match_word(chars, start, "next week") {
+        return Some((next_monday, end));
+    } else if let Some(end) = match_word(chars, start, "next ") {
+        (Some(next_monday), end)
+    } else if let Some(end) = match_word(chars, start, "this ") {
+        (Some(this_monday), end)
+    } else {
+        (None, start)
+    };
+
+    let Some((weekday, end)) = parse_weekday(chars, pos) else {
+        // 「来週」単体は来週の月曜とする。
+        return match (week_start, match_word(chars, start, "来週")) {
+            (Some(monday), Some(end)) => Some((monday, end)),
+            _ => None,
+        };
+    };
+
+    let date = match week_start {
+        Some(monday) => monday + Duration::days(weekday),
+        None => {
+            let days_ahead = (weekday - today.weekday().num_days_from_monday() as i64 + 7) % 7;
+            today + Duration::days(days_ahead)
+        }
+    };
+    Some((date, end))
+}
+
+/// 曜日を月曜=0 の番号で返す。
+fn parse_weekday(chars: &[char], start: usize) -> Option<(i64, usize)> {
+    if let Some(index) = chars
+        .get(start)
+        .and_then(|c| WEEKDAYS_JA.iter().position(|w| w == c))
+    {
+        let end = match_word(chars, start + 1, "曜")?;
+        return Some((index as i64, skip_word(chars, end, "日")));
+    }
+
+    WEEKDAYS_EN
+        .iter()
+        .enumerate()
+        .find_map(|(index, (full, short))| {
+            [full, short].iter().find_map(|word| {
+                let end = match_word(chars, start, word)?;
+                is_word_boundary(chars, start, end).then_some((index as i64, end))
+            })
+        })
+}
+
+/// 18:00 / 18時 / 18時30分 / 18時半 / 午後6時 / 6pm / 6:30 pm
+fn parse_time(chars: &[char], start: usize) -> Option<(NaiveTime, usize)> {
+    let (meridiem, pos) = if let Some(end) = match_word(chars, start, "午前") {
+        (Some(false), end)
+    } else if let Some(end) = match_word(chars, start, "午後") {
+        (Some(true), end)
+    } else {
+        (None, start)
+    };
+
+    let (hour, pos) = parse_digits(chars, pos, 1, 2)?;
+    let (minute, mut end) = match chars.get(pos) {
+        Some(':') => parse_digits(chars, pos + 1, 2, 2)?,
+        Some('時') => {
+            if let Some(end) = match_word(chars, pos + 1, "半") {
+                (30, end)
+            } else if let Some((minute, after)) = parse_digits(chars, pos + 1, 1, 2) {
+                (minute, match_word(chars, after, "分")?)
+            } else {
+                (0, pos + 1)
+            }
+        }
+        _ => (0, pos),
+    };
+    let has_clock_marker = end > pos;
+
+    let mut is_pm = meridiem;
+    if meridiem.is_none() {
+        let after_space = skip_spaces(chars, end);
+        if let Some(marker_end) = match_word(chars, after_space, "pm") {
+            is_pm = Some(true);
+            end = marker_end;
+        } else if let Some(marker_end) = match_word(chars, after_space, "am") {
+            is_pm = Some(false);
+            end = marker_end;
+        }
+    }
+    // 数字だけでは時刻とみなさない。
+    if !has_clock_marker && is_pm.is_none() {
+        return None;
+    }
+
+    let hour = match is_pm {
+        Some(true) if hour < 12 => hour + 12,
+        Some(false) if hour == 12 => 0,
+        _ => hour,
+    };
+    Some((NaiveTime::from_hms_opt(hour, minute, 0)?, end))
+}
+
+fn parse_digits(chars: &[char], start: usize, min: usize, max: usize) -> Option<(u32, usize)> {
+    let len = chars[start.min(chars.len())..]
+        .iter()
+        .take_while(|c| c.is_ascii_digit())
+        .count();
+    if len < min || len > max {
+        return None;
+    }
+    let value = chars[start..start + len]
+        .iter()
+        .collect::<String>()
+        .parse()
+        .ok()?;
+    Some((value, start + len))
+}
+
+fn match_word(chars: &[char], start: usize, word: &str) -> Option<usize> {
+    let mut pos = start;
+    for expected in word.chars() {
+        if chars.get(pos) != Some(&expected) {
+            return None;
+        }
+        pos += 1;
+    }
+    Some(pos)
+}
+
+fn find_all(chars: &[char], word: &str) -> Vec<usize> {
+    (0..chars.len())
+        .filter(|start| match_word(chars, *start, word).is_some())
+        .collect()
+}
+
+fn is_word_boundary(chars: &[char], start: usize, end: usize) -> bool {
+    let before = start == 0 || !chars[start - 1].is_ascii_alphanumeric();
+    let after = chars.get(end).is_none_or(|c| !c.is_ascii_alphanumeric());
+    before && after
+}
+
+fn skip_word(chars: &[char], start: usize, word: &str) -> usize {
+    match match_word(chars, start, word) {
+        Some(end) => skip_spaces(chars, end),
+        None => start,
+    }
+}
+
+fn skip_spaces(chars: &[char], start: usize) -> usize {
+    let mut pos = start;
+    while chars.get(pos).is_some_and(|c| c.is_whitespace()) {
+        pos += 1;
+    }
+    pos
+}
+
+fn skip_separators(chars: &[char], start: usize) -> usize {
+    let mut pos = start;
+    while chars
+        .get(pos)
+        .is_some_and(|c| c.is_whitespace() || matches!(c, ':' | '：' | 'は' | '='))
+    {
+        pos += 1;
+    }
+    pos
+}
+
+fn skip_connectors(chars: &[char], start: usize) -> usize {
+    let mut pos = start;
+    while chars
+        .get(pos)
+        .is_some_and(|c| c.is_whitespace() || matches!(c, 'の' | ',' | '、'))
+    {
+        pos += 1;
+    }
+    pos
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 2026-10-21 (水) 10:00
+    fn now() -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2026, 10, 21)
+            .unwrap()
+            .and_hms_opt(10, 0, 0)
+            .unwrap()
+    }
+
+    fn at(month: u32, day: u32, hour: u32, minute: u32) -> Option<NaiveDateTime> {
+        NaiveDate::from_ymd_opt(2026, month, day)
+            .unwrap()
+            .and_hms_opt(hour, minute, 0)
+    }
+
+    fn parse(content: &str) -> Option<NaiveDateTime> {
+        parse_due_at(content, now())
+    }
+
+    #[test]
+    fn parses_absolute_dates() {
+        assert_eq!(parse("<@1> by 2026-11-01"), at(11, 1, 23, 59));
+        assert_eq!(parse("期限: 2026/11/01 18:00"), at(11, 1, 18, 0));
+        assert_eq!(parse("2026年11月3日までに提出"), at(11, 3, 23, 59));
+        assert_eq!(parse("締切 11/5"), at(11, 5, 23, 59));
+        assert_eq!(parse("12月1日までにお願いします"), at(12, 1, 23, 59));
+    }
+
+    #[test]
+    fn rolls_past_month_day_into_next_year() {
+        assert_eq!(
+            parse("期限: 1/10"),
+            NaiveDate::from_ymd_opt(2027, 1, 10)
+                .unwrap()
+                .and_hms_opt(23, 59, 0)
+        );
+    }
+
+    #[test]
+    fn parses_weekdays() {
+        assert_eq!(parse("期限: 金曜まで"), at(10, 23, 23, 59));
+        assert_eq!(parse("水曜日までに"), at(10, 21, 23, 59));
+        assert_eq!(parse("来週の月曜までに確認"), at(10, 26, 23, 59));
+        assert_eq!(parse("来週水曜まで"), at(10, 28, 23, 59));
+        assert_eq!(parse("due friday"), at(10, 23, 23, 59));
+        assert_eq!(parse("by next tue 9am"), at(10, 27, 9, 0));
+    }
+
+    #[test]
+    fn parses_relative_days_and_times() {
+        assert_eq!(parse("明日までに"), at(10, 22, 23, 59));
+        assert_eq!(parse("締め切りは明後日の午後3時"), at(10, 23, 15, 0));
+        assert_eq!(parse("今日の18時半まで"), at(10, 21, 18, 30));
+        assert_eq!(parse("due tomorrow 5pm"), at(10, 22, 17, 0));
+        assert_eq!(parse("来週までに"), at(10, 26, 23, 59));
+        assert_eq!(parse("by next week"), at(10, 26, 23, 59));
+    }
+
+    #[test]
+    fn time_only_rolls_to_tomorrow_when_passed() {
+        assert_eq!(parse("15:00までに"), at(10, 21, 15, 0));
+        assert_eq!(parse("9時までに"), at(10, 22, 9, 0));
+    }
+
+    #[test]
+    fn ignores_dates_without_deadline_cue() {
+        assert_eq!(parse("明日の会議について"), None);
+        assert_eq!(parse("2026-11-01 のリリースノート"), None);
+        assert_eq!(parse("stand by me"), None);
+        assert_eq!(parse("期限は未定です"), None);
+    }
+
+    #[test]
+    fn uses_expression_adjacent_to_cue() {
+        assert_eq!(parse("明日の会議資料、金曜までに"), at(10, 23, 23, 59));
+    }
+}
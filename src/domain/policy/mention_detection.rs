@@ -1,4 +1,11 @@
-use crate::domain::model::{MentionType, Message};
+use serenity::model::prelude::RoleId;
+
+use crate::domain::model::{
+    MentionPriority, MentionType, Message, ScopeMode, ScopeRule, ScopeTarget,
+};
+
+const URGENT_MARKERS: [&str; 5] = ["[至急]", "【至急】", "[urgent]", "!urgent", "!至急"];
+const FYI_MARKERS: [&str; 5] = ["[fyi]", "【fyi】", "!fyi", "[共有]", "【共有】"];
 
 pub fn should_add_read_reaction(message: &Message) -> bool {
     if message.is_reply {
@@ -35,12 +42,93 @@ pub fn extract_mentions(message: &Message) -> Vec<MentionType> {
     mentions
 }
 
+/// 本文の優先度マーカー（`[至急]` `!urgent` `[FYI]` など）から優先度を判定する。
+/// マーカーが無ければチャンネルの既定、それも無ければ通常とする。
+/// 至急と FYI の両方がある場合は見落としを避けるため至急を優先する。
+pub fn classify_priority(
+    content: &str,
+    channel_default: Option<MentionPriority>,
+) -> MentionPriority {
+    let content_lower = content.to_lowercase();
+    let has_marker = |markers: &[&str]| markers.iter().any(|m| content_lower.contains(m));
+
+    if has_marker(&URGENT_MARKERS) {
+        MentionPriority::Urgent
+    } else if has_marker(&FYI_MARKERS) {
+        MentionPriority::Fyi
+    } else {
+        channel_default.unwrap_or_default()
+    }
+}
+
+/// 追跡範囲のルールを適用した結果。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScopeDecision {
+    /// `false` ならリアクションも記録もしない。
+    pub track: bool,
+    pub allow_everyone: bool,
+    /// 対象者に展開してよいロール。
+    pub allowed_roles: Vec<RoleId>,
+}
+
+/// ギルドの追跡範囲ルールを評価する。`location` はメッセージのチャンネルと、
+/// その親（スレッドの親チャンネル、カテゴリ）の ID。
+/// 拒否は許可より優先し、ルールで展開できるメンションが何も残らなければ追跡しない。
+pub fn evaluate_tracking_scope(
+    rules: &[ScopeRule],
+    location: &[u64],
+    message: &Message,
+) -> ScopeDecision {
+    let channel_rules = |mode: ScopeMode| {
+        rules
+            .iter()
+            .filter(move |rule| rule.mode == mode && matches!(rule.target, ScopeTarget::Channel(_)))
+    };
+    let matches_location = |rule: &ScopeRule| location.contains(&rule.target.id());
+
+    let channel_excluded = channel_rules(ScopeMode::Exclude).any(matches_location);
+    let has_channel_allowlist = channel_rules(ScopeMode::Include).next().is_some();
+    let channel_included = channel_rules(ScopeMode::Include).any(matches_location);
+    let channel_allowed = !channel_excluded && (!has_channel_allowlist || channel_included);
+    let allow_everyone = !channel_rules(ScopeMode::ExcludeEveryone).any(matches_location);
+
+    let role_rule = |role_id: RoleId, mode: ScopeMode| {
+        rules
+            .iter()
+            .any(|rule| rule.mode == mode && rule.target == ScopeTarget::Role(role_id.get()))
+    };
+    let has_role_allowlist = rules
+        .iter()
+        .any(|rule| rule.mode == ScopeMode::Include && matches!(rule.target, ScopeTarget::Role(_)));
+    let allowed_roles = message
+        .role_mentions
+        .iter()
+        .copied()
+        .filter(|role_id| !role_rule(*role_id, ScopeMode::Exclude))
+        .filter(|role_id| !has_role_allowlist || role_rule(*role_id, ScopeMode::Include))
+        .collect::<Vec<_>>();
+
+    let has_trackable_mention = !message.user_mentions.is_empty()
+        || !allowed_roles.is_empty()
+        || (message.mentions_everyone && allow_everyone);
+
+    ScopeDecision {
+        track: channel_allowed && has_trackable_mention,
+        allow_everyone,
+        allowed_roles,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use serenity::model::prelude::{ChannelId, MessageId, RoleId, UserId};
 
-    use super::{extract_mentions, should_add_read_reaction};
-    use crate::domain::model::{MentionType, Message};
+    use super::{
+        classify_priority, evaluate_tracking_scope, extract_mentions, should_add_read_reaction,
+    };
+    use crate::domain::model::{
+        MentionPriority, MentionType, Message, ScopeMode, ScopeRule, ScopeTarget,
+    };
 
     fn base_message() -> Message {
         Message {
@@ -110,4 +198,115 @@ mod tests {
         assert!(mentions.contains(&MentionType::Everyone));
         assert!(mentions.contains(&MentionType::Here));
     }
+
+    #[test]
+    fn classifies_priority_markers() {
+        assert_eq!(
+            classify_priority("[至急] <@10> 障害対応お願いします", None),
+            MentionPriority::Urgent
+        );
+        assert_eq!(
+            classify_priority("<@10> !URGENT deploy is broken", None),
+            MentionPriority::Urgent
+        );
+        assert_eq!(
+            classify_priority("【FYI】<@10> 議事録を共有します", None),
+            MentionPriority::Fyi
+        );
+        assert_eq!(
+            classify_priority("<@10> 確認お願いします", None),
+            MentionPriority::Normal
+        );
+    }
+
+    #[test]
+    fn marker_overrides_channel_default() {
+        assert_eq!(
+            classify_priority("<@10> 共有です", Some(MentionPriority::Fyi)),
+            MentionPriority::Fyi
+        );
+        assert_eq!(
+            classify_priority("[至急] <@10>", Some(MentionPriority::Fyi)),
+            MentionPriority::Urgent
+        );
+    }
+
+    #[test]
+    fn prefers_urgent_when_both_markers_exist() {
+        assert_eq!(
+            classify_priority("[FYI][至急] <@10>", None),
+            MentionPriority::Urgent
+        );
+    }
+
+    fn rule(target: ScopeTarget, mode: ScopeMode) -> ScopeRule {
+        ScopeRule { target, mode }
+    }
+
+    #[test]
+    fn tracks_everything_without_rules() {
+        let mut message = base_message();
+        message.role_mentions = vec![RoleId::new(20)];
+        message.mentions_everyone = true;
+
+        let decision = evaluate_tracking_scope(&[], &[1], &message);
+        assert!(decision.track);
+        assert!(decision.allow_everyone);
+        assert_eq!(decision.allowed_roles, vec![RoleId::new(20)]);
+    }
+
+    #[test]
+    fn excludes_channel_or_its_category() {
+        let mut message = base_message();
+        message.user_mentions = vec![UserId::new(10)];
+        let rules = [rule(ScopeTarget::Channel(100), ScopeMode::Exclude)];
+
+        assert!(!evaluate_tracking_scope(&rules, &[1, 100], &message).track);
+        assert!(evaluate_tracking_scope(&rules, &[1, 200], &message).track);
+    }
+
+    #[test]
+    fn allowlist_limits_tracked_channels_and_exclude_wins() {
+        let mut message = base_message();
+        message.user_mentions = vec![UserId::new(10)];
+        let rules = [
+            rule(ScopeTarget::Channel(100), ScopeMode::Include),
+            rule(ScopeTarget::Channel(2), ScopeMode::Exclude),
+        ];
+
+        assert!(evaluate_tracking_scope(&rules, &[1, 100], &message).track);
+        assert!(!evaluate_tracking_scope(&rules, &[3, 200], &message).track);
+        assert!(!evaluate_tracking_scope(&rules, &[2, 100], &message).track);
+    }
+
+    #[test]
+    fn skips_everyone_only_message_in_excluded_channel() {
+        let mut message = base_message();
+        message.mentions_everyone = true;
+        let rules = [rule(ScopeTarget::Channel(1), ScopeMode::ExcludeEveryone)];
+
+        let decision = evaluate_tracking_scope(&rules, &[1], &message);
+        assert!(!decision.track);
+        assert!(!decision.allow_everyone);
+
+        message.user_mentions = vec![UserId::new(10)];
+        let decision = evaluate_tracking_scope(&rules, &[1], &message);
+        assert!(decision.track);
+        assert!(!decision.allow_everyone);
+    }
+
+    #[test]
+    fn filters_excluded_and_non_allowlisted_roles() {
+        let mut message = base_message();
+        message.role_mentions = vec![RoleId::new(20), RoleId::new(21)];
+
+        let excluded = [rule(ScopeTarget::Role(20), ScopeMode::Exclude)];
+        let decision = evaluate_tracking_scope(&excluded, &[1], &message);
+        assert_eq!(decision.allowed_roles, vec![RoleId::new(21)]);
+
+        let allowlist = [rule(ScopeTarget::Role(22), ScopeMode::Include)];
+        let decision = evaluate_tracking_scope(&allowlist, &[1], &message);
+        assert!(decision.allowed_roles.is_empty());
+        assert!(!decision.track);
+    }
 }
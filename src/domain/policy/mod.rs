@@ -1,3 +1,5 @@
+pub mod content_storage;
+pub mod due_date;
 pub mod greeting;
 pub mod mention_detection;
 pub mod read_status_calc;
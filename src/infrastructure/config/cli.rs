@@ -0,0 +1,69 @@
+use std::path::PathBuf;
+
+use anyhow::{anyhow, bail};
+
+const USAGE: &str = "usage: kiduku [--config <path>] [--check-config]";
+
+/// コマンドライン引数。
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct CliArgs {
+    /// 設定ファイルの場所。
+    pub config_path: Option<PathBuf>,
+    /// 設定と DB への接続を確認して終了する。
+    pub check_config: bool,
+}
+
+impl CliArgs {
+    /// プログラム名を除いた引数を解釈する。
+    pub fn parse(args: impl IntoIterator<Item = String>) -> anyhow::Result<Self> {
+        let mut parsed = Self::default();
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--check-config" => parsed.check_config = true,
+                "--config" => {
+                    let path = args
+                        .next()
+                        .ok_or_else(|| anyhow!("--config requires a path\n{USAGE}"))?;
+                    parsed.config_path = Some(PathBuf::from(path));
+                }
+                _ => match arg.strip_prefix("--config=") {
+                    Some(path) => parsed.config_path = Some(PathBuf::from(path)),
+                    None => bail!("unknown argument: {arg}\n{USAGE}"),
+                },
+            }
+        }
+        Ok(parsed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> anyhow::Result<CliArgs> {
+        CliArgs::parse(args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn parses_flags() {
+        assert_eq!(parse(&[]).unwrap(), CliArgs::default());
+        assert_eq!(
+            parse(&["--config", "/etc/kiduku/config.toml", "--check-config"]).unwrap(),
+            CliArgs {
+                config_path: Some(PathBuf::from("/etc/kiduku/config.toml")),
+                check_config: true,
+            }
+        );
+        assert_eq!(
+            parse(&["--config=kiduku.toml"]).unwrap().config_path,
+            Some(PathBuf::from("kiduku.toml"))
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_or_incomplete_arguments() {
+        assert!(parse(&["--config"]).is_err());
+        assert!(parse(&["--verbose"]).is_err());
+    }
+}
@@ -0,0 +1,143 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::Context as _;
+use serde::Deserialize;
+
+/// 設定ファイル (TOML) の内容。未指定の項目は既定値か環境変数で補う。
+/// 綴り間違いに気付けるよう、知らないキーはエラーにする。
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields, default)]
+pub struct FileConfig {
+    pub discord: DiscordSection,
+    pub database: DatabaseSection,
+    pub retention: RetentionSection,
+    pub encryption: EncryptionSection,
+    pub monitoring: MonitoringSection,
+    pub log: LogSection,
+    pub scheduler: SchedulerSection,
+    pub emoji: EmojiSection,
+    pub features: FeaturesSection,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields, default)]
+pub struct DiscordSection {
+    pub token: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields, default)]
+pub struct DatabaseSection {
+    pub url: Option<String>,
+    pub pool_size: Option<usize>,
+    /// `disable` / `prefer` / `require` / `verify-ca` / `verify-full`。接続文字列の指定より優先する。
+    pub sslmode: Option<String>,
+    /// PEM 形式の CA 証明書ファイル。
+    pub ssl_root_cert: Option<PathBuf>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields, default)]
+pub struct RetentionSection {
+    pub default_days: Option<u32>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields, default)]
+pub struct EncryptionSection {
+    pub content_key: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields, default)]
+pub struct MonitoringSection {
+    pub metrics_addr: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields, default)]
+pub struct LogSection {
+    pub level: Option<String>,
+    /// `text` か `json`。
+    pub format: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields, default)]
+pub struct SchedulerSection {
+    pub weekly_hour_jst: Option<u32>,
+    pub escalation_interval_minutes: Option<u64>,
+    pub due_reminder_interval_minutes: Option<u64>,
+    pub retention_interval_hours: Option<u64>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields, default)]
+pub struct EmojiSection {
+    pub kidoku_id: Option<u64>,
+    pub kidoku_name: Option<String>,
+    pub done_id: Option<u64>,
+    pub done_name: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields, default)]
+pub struct FeaturesSection {
+    pub weekly_digest: Option<bool>,
+    pub monthly_digest: Option<bool>,
+    pub team_report: Option<bool>,
+    pub escalation: Option<bool>,
+    pub due_reminders: Option<bool>,
+    pub retention: Option<bool>,
+    pub startup_reconcile: Option<bool>,
+}
+
+impl FileConfig {
+    pub fn read(path: &Path) -> anyhow::Result<Self> {
+        let text = std::fs::read_to_string(path)
+            .with_context(|| format!("failed to read config file {}", path.display()))?;
+        Self::parse(&text).with_context(|| format!("invalid config file {}", path.display()))
+    }
+
+    pub fn parse(text: &str) -> anyhow::Result<Self> {
+        Ok(toml::from_str(text)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_partial_file() {
+        let file = FileConfig::parse(
+            r#"
+            [database]
+            pool_size = 8
+
+            [features]
+            team_report = false
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(file.database.pool_size, Some(8));
+        assert_eq!(file.database.url, None);
+        assert_eq!(file.features.team_report, Some(false));
+        assert_eq!(file.scheduler.weekly_hour_jst, None);
+    }
+
+    #[test]
+    fn rejects_unknown_keys() {
+        let err = FileConfig::parse("[database]\npool_sise = 8\n").unwrap_err();
+        assert!(format!("{err:#}").contains("pool_sise"));
+
+        assert!(FileConfig::parse("[databse]\nurl = \"x\"\n").is_err());
+    }
+
+    #[test]
+    fn rejects_wrong_types() {
+        assert!(FileConfig::parse("[database]\npool_size = \"8\"\n").is_err());
+        assert!(FileConfig::parse("[scheduler]\nweekly_hour_jst = -1\n").is_err());
+    }
+}
@@ -0,0 +1,129 @@
+use std::fmt;
+
+use anyhow::{anyhow, Context as _};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine as _;
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM, NONCE_LEN};
+use ring::rand::{SecureRandom, SystemRandom};
+
+/// 暗号化した本文の接頭辞。平文と区別し、形式を変えたときに判別できるようにする。
+const ENCRYPTED_PREFIX: &str = "enc:v1:";
+
+/// メッセージ本文を AES-256-GCM で暗号化・復号する。
+/// 保存形式は `enc:v1:` + base64(nonce || 暗号文 || タグ)。
+pub struct ContentCipher {
+    key: LessSafeKey,
+    rng: SystemRandom,
+}
+
+impl fmt::Debug for ContentCipher {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("ContentCipher(..)")
+    }
+}
+
+impl ContentCipher {
+    /// base64 でエンコードした 32 バイトの鍵から作る。
+    pub fn from_base64_key(encoded: &str) -> anyhow::Result<Self> {
+        let bytes = STANDARD
+            .decode(encoded.trim())
+            .context("CONTENT_ENCRYPTION_KEY must be base64")?;
+        let key = UnboundKey::new(&AES_256_GCM, &bytes)
+            .map_err(|_| anyhow!("CONTENT_ENCRYPTION_KEY must be 32 bytes"))?;
+        Ok(Self {
+            key: LessSafeKey::new(key),
+            rng: SystemRandom::new(),
+        })
+    }
+
+    pub fn encrypt(&self, plaintext: &str) -> anyhow::Result<String> {
+        let mut nonce_bytes = [0u8; NONCE_LEN];
+        self.rng
+            .fill(&mut nonce_bytes)
+            .map_err(|_| anyhow!("nonce の生成に失敗しました"))?;
+
+        let mut in_out = plaintext.as_bytes().to_vec();
+        self.key
+            .seal_in_place_append_tag(
+                Nonce::assume_unique_for_key(nonce_bytes),
+                Aad::empty(),
+                &mut in_out,
+            )
+            .map_err(|_| anyhow!("本文の暗号化に失敗しました"))?;
+
+        let mut payload = nonce_bytes.to_vec();
+        payload.extend_from_slice(&in_out);
+        Ok(format!("{ENCRYPTED_PREFIX}{}", STANDARD.encode(payload)))
+    }
+
+    pub fn decrypt(&self, stored: &str) -> anyhow::Result<String> {
+        let encoded = stored
+            .strip_prefix(ENCRYPTED_PREFIX)
+            .ok_or_else(|| anyhow!("暗号化された本文ではありません"))?;
+        let payload = STANDARD
+            .decode(encoded)
+            .context("暗号化された本文の形式が不正です")?;
+        if payload.len() < NONCE_LEN {
+            return Err(anyhow!("暗号化された本文の形式が不正です"));
+        }
+
+        let (nonce_bytes, ciphertext) = payload.split_at(NONCE_LEN);
+        let nonce = Nonce::try_assume_unique_for_key(nonce_bytes)
+            .map_err(|_| anyhow!("暗号化された本文の形式が不正です"))?;
+        let mut in_out = ciphertext.to_vec();
+        let plaintext = self
+            .key
+            .open_in_place(nonce, Aad::empty(), &mut in_out)
+            .map_err(|_| anyhow!("本文の復号に失敗しました。鍵が変わった可能性があります"))?;
+
+        String::from_utf8(plaintext.to_vec()).context("復号した本文が UTF-8 ではありません")
+    }
+}
+
+/// 暗号化して保存した本文かどうか。
+pub fn is_encrypted(stored: &str) -> bool {
+    stored.starts_with(ENCRYPTED_PREFIX)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cipher(seed: u8) -> ContentCipher {
+        ContentCipher::from_base64_key(&STANDARD.encode([seed; 32])).unwrap()
+    }
+
+    #[test]
+    fn round_trips_content() {
+        let cipher = cipher(1);
+        let encrypted = cipher.encrypt("<@1> 機密の件、確認お願いします").unwrap();
+
+        assert!(is_encrypted(&encrypted));
+        assert!(!encrypted.contains("機密"));
+        assert_eq!(
+            cipher.decrypt(&encrypted).unwrap(),
+            "<@1> 機密の件、確認お願いします"
+        );
+    }
+
+    #[test]
+    fn uses_fresh_nonce_each_time() {
+        let cipher = cipher(1);
+        assert_ne!(
+            cipher.encrypt("同じ本文").unwrap(),
+            cipher.encrypt("同じ本文").unwrap()
+        );
+    }
+
+    #[test]
+    fn fails_with_different_key() {
+        let encrypted = cipher(1).encrypt("本文").unwrap();
+        assert!(cipher(2).decrypt(&encrypted).is_err());
+    }
+
+    #[test]
+    fn rejects_invalid_key() {
+        assert!(ContentCipher::from_base64_key("not base64!").is_err());
+        assert!(ContentCipher::from_base64_key(&STANDARD.encode([0u8; 16])).is_err());
+    }
+}
@@ -1,68 +1,130 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
 
 use anyhow::Context as _;
 use deadpool_postgres::{Manager, ManagerConfig, Pool, RecyclingMethod};
 use tokio_postgres::{NoTls, Transaction};
 
+use crate::domain::model::{
+    ContentStorageMode, MentionPriority, ScopeMode, ScopeRule, ScopeTarget,
+};
+use crate::domain::policy::content_storage;
+use crate::infrastructure::content_cipher::{self, ContentCipher};
+use crate::infrastructure::db_tls::{DatabaseTls, SslMode};
+use crate::infrastructure::metrics::PoolUsage;
+use crate::usecase::dto::repository::{
+    AllReadNotifyMode, AuthoredMentionRecord, CompletionKind, DueReminderCandidate,
+    DueReminderKind, EscalationCandidate, EscalationKind, MentionForTarget, MentionProgress,
+    MentionStats, NewMention, PersonalData, PersonalDataDeletion, PurgePreview,
+    ReceivedMentionRecord, ReportSettings, ResponderStats, StatsFilter, StoredMention,
+    TargetProgress, UnreadCount, UnresolvedMention, UserMute, UserNotifySettings,
+};
+use crate::usecase::ports::{
+    CompletionNotifyRepository, DueReminderRepository, EscalationRepository, MentionRepository,
+    PersonalDataRepository, RetentionRepository, StatsRepository, TeamReportRepository,
+    TrackingRepository,
+};
+
 pub type DbPool = Pool;
 
 #[derive(Debug, Clone)]
 pub struct Db {
     pool: DbPool,
+    cipher: Option<Arc<ContentCipher>>,
 }
 
-#[derive(Debug, Clone)]
-pub struct NewMention {
+/// 起動時の補完で、チャンネルごとに最後に記録したメッセージ。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChannelCursor {
     pub guild_id: u64,
     pub channel_id: u64,
-    pub message_id: u64,
-    pub author_id: u64,
-    pub content: String,
-    pub mention_everyone: bool,
-    pub created_at_unix: i64,
-    pub targets: Vec<u64>,
+    pub last_message_id: u64,
 }
 
-#[derive(Debug, Clone)]
-pub struct StoredMention {
-    pub author_id: u64,
-    pub guild_id: u64,
+/// リアクションを照合する未解決メンションのメッセージ。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OpenMentionMessage {
     pub channel_id: u64,
     pub message_id: u64,
-    pub content: String,
-    pub mention_everyone: bool,
-    pub created_at_unix: i64,
-    pub target_user_ids: Vec<u64>,
-    pub read_user_ids: Vec<u64>,
-    pub done_user_ids: Vec<u64>,
 }
 
-#[derive(Debug, Clone)]
-pub struct MentionForTarget {
-    pub mention_id: i64,
-    pub guild_id: u64,
-    pub channel_id: u64,
-    pub message_id: u64,
-    pub author_id: u64,
-    pub content: String,
-    pub mention_everyone: bool,
-    pub created_at_unix: i64,
-    pub is_read: bool,
-    pub is_done: bool,
-    pub extended_until: Option<i64>,
-}
+/// 追跡を拒否した対象者を除く条件。`mt` は mention_targets のエイリアス。
+const TARGET_TRACKED_SQL: &str = "NOT EXISTS(SELECT 1 FROM user_settings us \
+                    WHERE us.user_id = mt.user_id AND us.tracking_opt_out)";
+
+/// 追跡を拒否した対象者と、チャンネル・送信者をミュートした対象者を除く条件。
+/// Bot が退出したギルドのメンションも、削除までの猶予期間中は通知しない。
+/// `m` は mentions、`mt` は mention_targets のエイリアス。
+const TARGET_NOTIFIABLE_SQL: &str = "NOT EXISTS(SELECT 1 FROM user_settings us \
+                    WHERE us.user_id = mt.user_id AND us.tracking_opt_out) \
+     AND NOT EXISTS(SELECT 1 FROM departed_guilds dg WHERE dg.guild_id = m.guild_id) \
+     AND NOT EXISTS(SELECT 1 FROM user_mutes um \
+                    WHERE um.user_id = mt.user_id \
+                      AND ((um.kind = 'channel' AND um.target_id = m.channel_id) \
+                        OR (um.kind = 'author' AND um.target_id = m.author_id)))";
+
+/// 週次・月次の DM を止めた対象者を除く条件。`mt` は mention_targets のエイリアス。
+const TARGET_DIGEST_ENABLED_SQL: &str = "NOT EXISTS(SELECT 1 FROM user_settings us \
+                    WHERE us.user_id = mt.user_id AND us.digest_dm_disabled)";
+
+/// 保持期間を過ぎ、対象者全員が解決・無視したメンションの条件。FYI は既読で解決とみなす。
+/// ギルドの `retention_days` が NULL なら既定値、0 なら削除しない。
+/// パラメータ: $1 既定の保持日数（0 で削除しない）, $2 現在時刻, $3 guild_id（NULL で全ギルド）
+const PURGEABLE_MENTIONS_SQL: &str = "FROM mentions m \
+     LEFT JOIN guild_settings gs ON gs.guild_id = m.guild_id \
+     WHERE COALESCE(gs.retention_days, $1) > 0 \
+       AND m.created_at < $2 - COALESCE(gs.retention_days, $1)::BIGINT * 86400 \
+       AND ($3::BIGINT IS NULL OR m.guild_id = $3) \
+       AND NOT EXISTS(SELECT 1 FROM mention_targets mt \
+                      WHERE mt.mention_id = m.id AND mt.ignored_at IS NULL \
+                        AND NOT EXISTS(SELECT 1 FROM mention_dones \
+                                       WHERE mention_id = m.id AND user_id = mt.user_id) \
+                        AND NOT (m.priority = 'fyi' AND EXISTS(\
+                                 SELECT 1 FROM mention_reads \
+                                 WHERE mention_id = m.id AND user_id = mt.user_id)))";
+
+/// 統計クエリ共通の対象抽出。既読は既読・解決のうち早い方を初回反応とみなす。
+/// `/管理設定 インポート` で記録した既読・解決は実際の時刻が分からないため、件数には含め、経過時間からは除く。
+/// パラメータ: $1 guild_id, $2 since, $3 until, $4 channel_id, $5 target_user_ids
+const STATS_SCOPED_CTE: &str = "WITH scoped AS (\
+     SELECT mt.user_id, \
+            (r.mention_id IS NOT NULL OR d.mention_id IS NOT NULL) AS is_read, \
+            d.mention_id IS NOT NULL AS is_done, \
+            CASE WHEN COALESCE(r.imported, FALSE) OR COALESCE(d.imported, FALSE) THEN NULL \
+                 ELSE LEAST(r.read_at, d.done_at) - m.created_at END AS read_secs, \
+            CASE WHEN d.imported THEN NULL ELSE d.done_at - m.created_at END AS done_secs \
+     FROM mentions m \
+     JOIN mention_targets mt ON mt.mention_id = m.id \
+     LEFT JOIN mention_reads r ON r.mention_id = m.id AND r.user_id = mt.user_id \
+     LEFT JOIN mention_dones d ON d.mention_id = m.id AND d.user_id = mt.user_id \
+     WHERE m.guild_id = $1 \
+       AND m.created_at >= $2 AND m.created_at < $3 \
+       AND ($4::BIGINT IS NULL OR m.channel_id = $4) \
+       AND ($5::BIGINT[] IS NULL OR mt.user_id = ANY($5)) \
+       AND mt.ignored_at IS NULL \
+       AND NOT EXISTS(SELECT 1 FROM user_settings us \
+                      WHERE us.user_id = mt.user_id AND us.tracking_opt_out)) ";
 
 impl Db {
-    pub async fn connect(database_url: &str) -> anyhow::Result<Self> {
-        let config: tokio_postgres::Config = database_url
+    /// `database_url` は `sslmode` などの TLS の指定を取り除いたもの。TLS は `tls` に従う。
+    pub async fn connect(
+        database_url: &str,
+        pool_size: usize,
+        tls: &DatabaseTls,
+    ) -> anyhow::Result<Self> {
+        let mut config: tokio_postgres::Config = database_url
             .parse()
             .context("DATABASE_URL の形式が不正です")?;
+        tls.apply(&mut config);
         let manager_config = ManagerConfig {
             recycling_method: RecyclingMethod::Fast,
         };
-        let manager = Manager::from_config(config, NoTls, manager_config);
+        let manager = match tls.mode {
+            SslMode::Disable => Manager::from_config(config, NoTls, manager_config),
+            _ => Manager::from_config(config, tls.connector()?, manager_config),
+        };
         let pool = Pool::builder(manager)
-            .max_size(16)
+            .max_size(pool_size)
             .build()
             .context("PostgreSQL のプール構築に失敗しました")?;
 
@@ -72,10 +134,81 @@ impl Db {
             .await
             .context("DB接続の疎通確認に失敗しました")?;
 
-        Ok(Self { pool })
+        Ok(Self { pool, cipher: None })
+    }
+
+    /// DB に問い合わせできるか確認する。
+    pub async fn ping(&self) -> anyhow::Result<()> {
+        let client = self
+            .pool
+            .get()
+            .await
+            .context("DB接続の取得に失敗しました")?;
+        client
+            .simple_query("SELECT 1")
+            .await
+            .context("DB接続の疎通確認に失敗しました")?;
+        Ok(())
+    }
+
+    /// プールを閉じる。停止時に呼び、以降の接続取得は失敗する。
+    pub fn close(&self) {
+        self.pool.close();
+    }
+
+    pub fn pool_usage(&self) -> PoolUsage {
+        let status = self.pool.status();
+        PoolUsage {
+            max_size: status.max_size,
+            size: status.size,
+            available: status.available,
+            waiting: status.waiting,
+        }
+    }
+
+    pub fn with_content_cipher(mut self, cipher: Option<ContentCipher>) -> Self {
+        self.cipher = cipher.map(Arc::new);
+        self
+    }
+
+    /// 本文を暗号化して保存できるか。
+    pub fn has_content_cipher(&self) -> bool {
+        self.cipher.is_some()
     }
 
-    pub async fn insert_mention(&self, mention: NewMention) -> anyhow::Result<()> {
+    /// 保存方式に従って本文を変換する。暗号化を選んでいても鍵が無ければ本文を保存しない。
+    fn prepare_content(&self, content: &str, mode: ContentStorageMode) -> anyhow::Result<String> {
+        let stored = content_storage::content_to_store(content, mode);
+        if mode != ContentStorageMode::Encrypted {
+            return Ok(stored);
+        }
+        match &self.cipher {
+            Some(cipher) => cipher.encrypt(&stored),
+            None => {
+                tracing::warn!("CONTENT_ENCRYPTION_KEY is not set; message content is not stored");
+                Ok(String::new())
+            }
+        }
+    }
+
+    /// 行の本文を読み出す。復号できない場合は空文字を返し、表示時に Discord から取得させる。
+    fn read_content(&self, row: &tokio_postgres::Row) -> String {
+        let stored = row.get::<_, String>("content");
+        if !content_cipher::is_encrypted(&stored) {
+            return stored;
+        }
+        let Some(cipher) = &self.cipher else {
+            tracing::warn!("encrypted content found but CONTENT_ENCRYPTION_KEY is not set");
+            return String::new();
+        };
+        cipher.decrypt(&stored).unwrap_or_else(|err| {
+            tracing::warn!("failed to decrypt message content: {:?}", err);
+            String::new()
+        })
+    }
+
+    pub async fn insert_mention(&self, mut mention: NewMention) -> anyhow::Result<()> {
+        mention.content = self.prepare_content(&mention.content, mention.content_storage)?;
         let mut client = self
             .pool
             .get()
@@ -95,12 +228,15 @@ impl Db {
         Ok(())
     }
 
+    /// 新たに記録した場合は `true`、記録済みまたはメンションが無い場合は `false` を返す。
+    /// `imported` は `/管理設定 インポート` で過去のリアクションから記録したことを表し、統計の経過時間から除く。
     pub async fn record_read(
         &self,
         message_id: u64,
         user_id: u64,
         read_at_unix: i64,
-    ) -> anyhow::Result<()> {
+        imported: bool,
+    ) -> anyhow::Result<bool> {
         let client = self
             .pool
             .get()
@@ -116,28 +252,31 @@ impl Db {
             .context("メンションの検索に失敗しました")?
         {
             Some(row) => row.get::<_, i64>(0),
-            None => return Ok(()),
+            None => return Ok(false),
         };
 
-        client
+        let inserted = client
             .execute(
-                "INSERT INTO mention_reads (mention_id, user_id, read_at) \
-                 VALUES ($1, $2, $3) \
+                "INSERT INTO mention_reads (mention_id, user_id, read_at, imported) \
+                 VALUES ($1, $2, $3, $4) \
                  ON CONFLICT (mention_id, user_id) DO NOTHING",
-                &[&mention_id, &(user_id as i64), &read_at_unix],
+                &[&mention_id, &(user_id as i64), &read_at_unix, &imported],
             )
             .await
             .context("既読情報の保存に失敗しました")?;
 
-        Ok(())
+        Ok(inserted > 0)
     }
 
+    /// 新たに記録した場合は `true`、記録済みまたはメンションが無い場合は `false` を返す。
+    /// `imported` は `/管理設定 インポート` で過去のリアクションから記録したことを表し、統計の経過時間から除く。
     pub async fn record_done(
         &self,
         message_id: u64,
         user_id: u64,
         done_at_unix: i64,
-    ) -> anyhow::Result<()> {
+        imported: bool,
+    ) -> anyhow::Result<bool> {
         let client = self
             .pool
             .get()
@@ -153,20 +292,20 @@ impl Db {
             .context("メンションの検索に失敗しました")?
         {
             Some(row) => row.get::<_, i64>(0),
-            None => return Ok(()),
+            None => return Ok(false),
         };
 
-        client
+        let inserted = client
             .execute(
-                "INSERT INTO mention_dones (mention_id, user_id, done_at) \
-                 VALUES ($1, $2, $3) \
+                "INSERT INTO mention_dones (mention_id, user_id, done_at, imported) \
+                 VALUES ($1, $2, $3, $4) \
                  ON CONFLICT (mention_id, user_id) DO NOTHING",
-                &[&mention_id, &(user_id as i64), &done_at_unix],
+                &[&mention_id, &(user_id as i64), &done_at_unix, &imported],
             )
             .await
             .context("解決情報の保存に失敗しました")?;
 
-        Ok(())
+        Ok(inserted > 0)
     }
 
     pub async fn delete_mention_by_message_id(&self, message_id: u64) -> anyhow::Result<u64> {
@@ -187,6 +326,75 @@ impl Db {
         Ok(deleted)
     }
 
+    /// メンションの記録を削除し、追跡を停止したメッセージとして残す。削除したメンション数を返す。
+    pub async fn stop_tracking_message(
+        &self,
+        guild_id: u64,
+        message_id: u64,
+        stopped_at_unix: i64,
+    ) -> anyhow::Result<u64> {
+        let mut client = self
+            .pool
+            .get()
+            .await
+            .context("DB接続の取得に失敗しました")?;
+        let tx = client
+            .transaction()
+            .await
+            .context("トランザクション開始に失敗しました")?;
+
+        let deleted = tx
+            .execute(
+                "DELETE FROM mentions WHERE message_id = $1",
+                &[&(message_id as i64)],
+            )
+            .await
+            .context("メンションの削除に失敗しました")?;
+        tx.execute(
+            "INSERT INTO untracked_messages (message_id, guild_id, untracked_at) \
+             VALUES ($1, $2, $3) \
+             ON CONFLICT (message_id) DO UPDATE SET untracked_at = EXCLUDED.untracked_at",
+            &[&(message_id as i64), &(guild_id as i64), &stopped_at_unix],
+        )
+        .await
+        .context("追跡を停止したメッセージの記録に失敗しました")?;
+
+        tx.commit()
+            .await
+            .context("トランザクションのコミットに失敗しました")?;
+        Ok(deleted)
+    }
+
+    /// `message_ids` のうち、追跡を停止したメッセージの ID を返す。
+    pub async fn fetch_untracked_message_ids(
+        &self,
+        message_ids: &[u64],
+    ) -> anyhow::Result<HashSet<u64>> {
+        if message_ids.is_empty() {
+            return Ok(HashSet::new());
+        }
+
+        let client = self
+            .pool
+            .get()
+            .await
+            .context("DB接続の取得に失敗しました")?;
+
+        let message_ids = message_ids.iter().map(|id| *id as i64).collect::<Vec<_>>();
+        let rows = client
+            .query(
+                "SELECT message_id FROM untracked_messages WHERE message_id = ANY($1)",
+                &[&message_ids],
+            )
+            .await
+            .context("追跡を停止したメッセージの取得に失敗しました")?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| row.get::<_, i64>("message_id") as u64)
+            .collect())
+    }
+
     pub async fn delete_mentions_by_message_ids(&self, message_ids: &[u64]) -> anyhow::Result<u64> {
         if message_ids.is_empty() {
             return Ok(0);
@@ -221,7 +429,7 @@ impl Db {
 
         let rows = client
             .query(
-                "SELECT id, guild_id, channel_id, message_id, author_id, content, mention_everyone, created_at \
+                "SELECT id, guild_id, channel_id, message_id, author_id, content, mention_everyone, created_at, due_at, priority \
                  FROM mentions \
                  WHERE author_id = $1 AND created_at >= $2 \
                  ORDER BY created_at DESC \
@@ -240,7 +448,7 @@ impl Db {
             .map(|row| row.get::<_, i64>("id"))
             .collect::<Vec<_>>();
 
-        let targets = fetch_user_ids_by_mention(&client, &mention_ids, "mention_targets").await?;
+        let targets = fetch_tracked_target_ids_by_mention(&client, &mention_ids).await?;
         let reads = fetch_user_ids_by_mention(&client, &mention_ids, "mention_reads").await?;
         let dones = fetch_user_ids_by_mention(&client, &mention_ids, "mention_dones").await?;
 
@@ -255,9 +463,11 @@ impl Db {
                 guild_id: row.get::<_, i64>("guild_id") as u64,
                 channel_id: row.get::<_, i64>("channel_id") as u64,
                 message_id: row.get::<_, i64>("message_id") as u64,
-                content: row.get::<_, String>("content"),
+                content: self.read_content(&row),
                 mention_everyone: row.get::<_, bool>("mention_everyone"),
                 created_at_unix: row.get::<_, i64>("created_at"),
+                due_at_unix: row.get::<_, Option<i64>>("due_at"),
+                priority: MentionPriority::from_db(row.get::<_, &str>("priority")),
                 target_user_ids,
                 read_user_ids,
                 done_user_ids,
@@ -279,7 +489,7 @@ impl Db {
 
         let row = match client
             .query_opt(
-                "SELECT id, guild_id, channel_id, message_id, author_id, content, mention_everyone, created_at \
+                "SELECT id, guild_id, channel_id, message_id, author_id, content, mention_everyone, created_at, due_at, priority \
                  FROM mentions WHERE message_id = $1",
                 &[&(message_id as i64)],
             )
@@ -293,7 +503,7 @@ impl Db {
         let mention_id = row.get::<_, i64>("id");
         let mention_ids = vec![mention_id];
 
-        let targets = fetch_user_ids_by_mention(&client, &mention_ids, "mention_targets").await?;
+        let targets = fetch_tracked_target_ids_by_mention(&client, &mention_ids).await?;
         let reads = fetch_user_ids_by_mention(&client, &mention_ids, "mention_reads").await?;
         let dones = fetch_user_ids_by_mention(&client, &mention_ids, "mention_dones").await?;
 
@@ -302,9 +512,11 @@ impl Db {
             guild_id: row.get::<_, i64>("guild_id") as u64,
             channel_id: row.get::<_, i64>("channel_id") as u64,
             message_id: row.get::<_, i64>("message_id") as u64,
-            content: row.get::<_, String>("content"),
+            content: self.read_content(&row),
             mention_everyone: row.get::<_, bool>("mention_everyone"),
             created_at_unix: row.get::<_, i64>("created_at"),
+            due_at_unix: row.get::<_, Option<i64>>("due_at"),
+            priority: MentionPriority::from_db(row.get::<_, &str>("priority")),
             target_user_ids: targets.get(&mention_id).cloned().unwrap_or_default(),
             read_user_ids: reads.get(&mention_id).cloned().unwrap_or_default(),
             done_user_ids: dones.get(&mention_id).cloned().unwrap_or_default(),
@@ -317,30 +529,42 @@ impl Db {
         offset: i64,
         limit: i64,
         show_done: bool,
+        sort_by_due: bool,
     ) -> anyhow::Result<Vec<MentionForTarget>> {
+        // 未解決の至急メンションを先頭に置く。FYI は既読になれば解決済みとして扱う。
         let client = self
             .pool
             .get()
             .await
             .context("DB接続の取得に失敗しました")?;
 
+        let sql = format!(
+            "SELECT m.id, m.guild_id, m.channel_id, m.message_id, m.author_id, \
+                    m.content, m.mention_everyone, m.created_at, m.due_at, m.priority, mt.extended_until, \
+                    EXISTS(SELECT 1 FROM mention_reads \
+                           WHERE mention_id = m.id AND user_id = $1) AS is_read, \
+                    EXISTS(SELECT 1 FROM mention_dones \
+                           WHERE mention_id = m.id AND user_id = $1) AS is_done \
+             FROM mentions m \
+             JOIN mention_targets mt ON m.id = mt.mention_id \
+             WHERE mt.user_id = $1 AND mt.ignored_at IS NULL \
+               AND {TARGET_NOTIFIABLE_SQL} \
+               AND ($4 OR NOT (EXISTS(\
+                    SELECT 1 FROM mention_dones \
+                    WHERE mention_id = m.id AND user_id = $1) \
+                 OR (m.priority = 'fyi' AND EXISTS(\
+                    SELECT 1 FROM mention_reads \
+                    WHERE mention_id = m.id AND user_id = $1)))) \
+             ORDER BY (m.priority = 'urgent' AND NOT EXISTS(\
+                        SELECT 1 FROM mention_dones \
+                        WHERE mention_id = m.id AND user_id = $1)) DESC, \
+                      CASE WHEN $5 THEN m.due_at END ASC NULLS LAST, m.created_at DESC \
+             LIMIT $2 OFFSET $3"
+        );
         let rows = client
             .query(
-                "SELECT m.id, m.guild_id, m.channel_id, m.message_id, m.author_id, \
-                        m.content, m.mention_everyone, m.created_at, mt.extended_until, \
-                        EXISTS(SELECT 1 FROM mention_reads \
-                               WHERE mention_id = m.id AND user_id = $1) AS is_read, \
-                        EXISTS(SELECT 1 FROM mention_dones \
-                               WHERE mention_id = m.id AND user_id = $1) AS is_done \
-                 FROM mentions m \
-                 JOIN mention_targets mt ON m.id = mt.mention_id \
-                 WHERE mt.user_id = $1 AND mt.ignored_at IS NULL \
-                   AND ($4 OR NOT EXISTS(\
-                        SELECT 1 FROM mention_dones \
-                        WHERE mention_id = m.id AND user_id = $1)) \
-                 ORDER BY m.created_at DESC \
-                 LIMIT $2 OFFSET $3",
-                &[&(user_id as i64), &limit, &offset, &show_done],
+                &sql,
+                &[&(user_id as i64), &limit, &offset, &show_done, &sort_by_due],
             )
             .await
             .context("被メンション一覧の取得に失敗しました")?;
@@ -353,9 +577,11 @@ impl Db {
                 channel_id: row.get::<_, i64>("channel_id") as u64,
                 message_id: row.get::<_, i64>("message_id") as u64,
                 author_id: row.get::<_, i64>("author_id") as u64,
-                content: row.get::<_, String>("content"),
+                content: self.read_content(&row),
                 mention_everyone: row.get::<_, bool>("mention_everyone"),
                 created_at_unix: row.get::<_, i64>("created_at"),
+                due_at_unix: row.get::<_, Option<i64>>("due_at"),
+                priority: MentionPriority::from_db(row.get::<_, &str>("priority")),
                 is_read: row.get::<_, bool>("is_read"),
                 is_done: row.get::<_, bool>("is_done"),
                 extended_until: row.get::<_, Option<i64>>("extended_until"),
@@ -477,7 +703,8 @@ impl Db {
         Ok(deleted)
     }
 
-    /// 週次バッチ用: 未読かつ未DONEのターゲット (mention_id, user_id) を返す
+    /// 週次バッチ用: 未読かつ未DONEのターゲット (mention_id, user_id) を返す。
+    /// 期限付きのメンションは期限リマインダーで通知するため除く。FYI とミュート・DM停止中の対象者には通知しない。
     pub async fn fetch_unread_targets_for_weekly_batch(&self) -> anyhow::Result<Vec<(i64, u64)>> {
         let client = self
             .pool
@@ -485,17 +712,22 @@ impl Db {
             .await
             .context("DB接続の取得に失敗しました")?;
 
+        let sql = format!(
+            "SELECT mt.mention_id, mt.user_id \
+             FROM mention_targets mt \
+             JOIN mentions m ON m.id = mt.mention_id \
+             WHERE mt.ignored_at IS NULL \
+               AND m.due_at IS NULL \
+               AND m.priority <> 'fyi' \
+               AND {TARGET_NOTIFIABLE_SQL} \
+               AND {TARGET_DIGEST_ENABLED_SQL} \
+               AND NOT EXISTS(SELECT 1 FROM mention_reads \
+                              WHERE mention_id = mt.mention_id AND user_id = mt.user_id) \
+               AND NOT EXISTS(SELECT 1 FROM mention_dones \
+                              WHERE mention_id = mt.mention_id AND user_id = mt.user_id)"
+        );
         let rows = client
-            .query(
-                "SELECT mt.mention_id, mt.user_id \
-                 FROM mention_targets mt \
-                 WHERE mt.ignored_at IS NULL \
-                   AND NOT EXISTS(SELECT 1 FROM mention_reads \
-                                  WHERE mention_id = mt.mention_id AND user_id = mt.user_id) \
-                   AND NOT EXISTS(SELECT 1 FROM mention_dones \
-                                  WHERE mention_id = mt.mention_id AND user_id = mt.user_id)",
-                &[],
-            )
+            .query(&sql, &[])
             .await
             .context("週次バッチ用未読ターゲットの取得に失敗しました")?;
 
@@ -524,24 +756,27 @@ impl Db {
             .await
             .context("DB接続の取得に失敗しました")?;
 
+        let sql = format!(
+            "SELECT m.id, m.guild_id, m.channel_id, m.message_id, m.author_id, \
+                    m.content, m.mention_everyone, m.created_at, m.due_at, m.priority, mt.extended_until, mt.user_id, \
+                    EXISTS(SELECT 1 FROM mention_reads \
+                           WHERE mention_id = m.id AND user_id = mt.user_id) AS is_read, \
+                    EXISTS(SELECT 1 FROM mention_dones \
+                           WHERE mention_id = m.id AND user_id = mt.user_id) AS is_done \
+             FROM mentions m \
+             JOIN mention_targets mt ON m.id = mt.mention_id \
+             WHERE m.created_at < $1 \
+               AND m.priority <> 'fyi' \
+               AND mt.ignored_at IS NULL \
+               AND {TARGET_NOTIFIABLE_SQL} \
+               AND {TARGET_DIGEST_ENABLED_SQL} \
+               AND (mt.extended_until IS NULL OR mt.extended_until < $2) \
+               AND NOT EXISTS(SELECT 1 FROM mention_dones \
+                              WHERE mention_id = m.id AND user_id = mt.user_id) \
+             ORDER BY m.created_at DESC"
+        );
         let rows = client
-            .query(
-                "SELECT m.id, m.guild_id, m.channel_id, m.message_id, m.author_id, \
-                        m.content, m.mention_everyone, m.created_at, mt.extended_until, mt.user_id, \
-                        EXISTS(SELECT 1 FROM mention_reads \
-                               WHERE mention_id = m.id AND user_id = mt.user_id) AS is_read, \
-                        EXISTS(SELECT 1 FROM mention_dones \
-                               WHERE mention_id = m.id AND user_id = mt.user_id) AS is_done \
-                 FROM mentions m \
-                 JOIN mention_targets mt ON m.id = mt.mention_id \
-                 WHERE m.created_at < $1 \
-                   AND mt.ignored_at IS NULL \
-                   AND (mt.extended_until IS NULL OR mt.extended_until < $2) \
-                   AND NOT EXISTS(SELECT 1 FROM mention_dones \
-                                  WHERE mention_id = m.id AND user_id = mt.user_id) \
-                 ORDER BY m.created_at DESC",
-                &[&cutoff_unix, &now_unix],
-            )
+            .query(&sql, &[&cutoff_unix, &now_unix])
             .await
             .context("月次バッチ用期限切れターゲットの取得に失敗しました")?;
 
@@ -555,9 +790,11 @@ impl Db {
                     channel_id: row.get::<_, i64>("channel_id") as u64,
                     message_id: row.get::<_, i64>("message_id") as u64,
                     author_id: row.get::<_, i64>("author_id") as u64,
-                    content: row.get::<_, String>("content"),
+                    content: self.read_content(&row),
                     mention_everyone: row.get::<_, bool>("mention_everyone"),
                     created_at_unix: row.get::<_, i64>("created_at"),
+                    due_at_unix: row.get::<_, Option<i64>>("due_at"),
+                    priority: MentionPriority::from_db(row.get::<_, &str>("priority")),
                     is_read: row.get::<_, bool>("is_read"),
                     is_done: row.get::<_, bool>("is_done"),
                     extended_until: row.get::<_, Option<i64>>("extended_until"),
@@ -568,83 +805,2006 @@ impl Db {
 
         Ok(result)
     }
-}
 
-async fn upsert_mention(tx: &Transaction<'_>, mention: &NewMention) -> anyhow::Result<i64> {
-    if let Some(row) = tx
-        .query_opt(
-            "SELECT id FROM mentions WHERE message_id = $1",
-            &[&(mention.message_id as i64)],
-        )
-        .await
-        .context("既存メンションの検索に失敗しました")?
-    {
-        return Ok(row.get::<_, i64>(0));
+    pub async fn fetch_mention_stats(&self, filter: &StatsFilter) -> anyhow::Result<MentionStats> {
+        let client = self
+            .pool
+            .get()
+            .await
+            .context("DB接続の取得に失敗しました")?;
+
+        let sql = format!(
+            "{STATS_SCOPED_CTE}\
+             SELECT COUNT(*) AS target_count, \
+                    COUNT(*) FILTER (WHERE is_read) AS read_count, \
+                    COUNT(*) FILTER (WHERE is_done) AS done_count, \
+                    COUNT(*) FILTER (WHERE NOT is_read) AS never_read_count, \
+                    percentile_cont(0.5) WITHIN GROUP \
+                      (ORDER BY GREATEST(read_secs, 0)::DOUBLE PRECISION) \
+                      FILTER (WHERE read_secs IS NOT NULL) AS read_median, \
+                    percentile_cont(0.9) WITHIN GROUP \
+                      (ORDER BY GREATEST(read_secs, 0)::DOUBLE PRECISION) \
+                      FILTER (WHERE read_secs IS NOT NULL) AS read_p90, \
+                    percentile_cont(0.5) WITHIN GROUP \
+                      (ORDER BY GREATEST(done_secs, 0)::DOUBLE PRECISION) \
+                      FILTER (WHERE done_secs IS NOT NULL) AS done_median, \
+                    percentile_cont(0.9) WITHIN GROUP \
+                      (ORDER BY GREATEST(done_secs, 0)::DOUBLE PRECISION) \
+                      FILTER (WHERE done_secs IS NOT NULL) AS done_p90 \
+             FROM scoped"
+        );
+        let (guild_id, channel_id, target_user_ids) = stats_params(filter);
+        let row = client
+            .query_one(
+                &sql,
+                &[
+                    &guild_id,
+                    &filter.since_unix,
+                    &filter.until_unix,
+                    &channel_id,
+                    &target_user_ids,
+                ],
+            )
+            .await
+            .context("メンション統計の取得に失敗しました")?;
+
+        Ok(MentionStats {
+            target_count: row.get::<_, i64>("target_count"),
+            read_count: row.get::<_, i64>("read_count"),
+            done_count: row.get::<_, i64>("done_count"),
+            never_read_count: row.get::<_, i64>("never_read_count"),
+            read_median_secs: row.get::<_, Option<f64>>("read_median"),
+            read_p90_secs: row.get::<_, Option<f64>>("read_p90"),
+            done_median_secs: row.get::<_, Option<f64>>("done_median"),
+            done_p90_secs: row.get::<_, Option<f64>>("done_p90"),
+        })
     }
 
-    let row = tx
-        .query_one(
-            "INSERT INTO mentions \
-             (guild_id, channel_id, message_id, author_id, content, mention_everyone, created_at) \
-             VALUES ($1, $2, $3, $4, $5, $6, $7) \
-             RETURNING id",
-            &[
-                &(mention.guild_id as i64),
-                &(mention.channel_id as i64),
-                &(mention.message_id as i64),
-                &(mention.author_id as i64),
-                &mention.content,
-                &mention.mention_everyone,
-                &mention.created_at_unix,
-            ],
-        )
-        .await
-        .context("メンションの保存に失敗しました")?;
+    /// 既読までの時間の中央値が長い順に対象者を返す。一度も既読にしていない対象者は除く。
+    pub async fn fetch_slowest_responders(
+        &self,
+        filter: &StatsFilter,
+        limit: i64,
+    ) -> anyhow::Result<Vec<ResponderStats>> {
+        let client = self
+            .pool
+            .get()
+            .await
+            .context("DB接続の取得に失敗しました")?;
 
-    Ok(row.get::<_, i64>(0))
-}
+        let sql = format!(
+            "{STATS_SCOPED_CTE}\
+             SELECT user_id, \
+                    COUNT(*) AS target_count, \
+                    COUNT(*) FILTER (WHERE NOT is_read) AS never_read_count, \
+                    percentile_cont(0.5) WITHIN GROUP \
+                      (ORDER BY GREATEST(read_secs, 0)::DOUBLE PRECISION) \
+                      FILTER (WHERE read_secs IS NOT NULL) AS read_median \
+             FROM scoped \
+             GROUP BY user_id \
+             HAVING COUNT(read_secs) > 0 \
+             ORDER BY read_median DESC, user_id \
+             LIMIT $6"
+        );
+        let (guild_id, channel_id, target_user_ids) = stats_params(filter);
+        let rows = client
+            .query(
+                &sql,
+                &[
+                    &guild_id,
+                    &filter.since_unix,
+                    &filter.until_unix,
+                    &channel_id,
+                    &target_user_ids,
+                    &limit,
+                ],
+            )
+            .await
+            .context("応答の遅い対象者の取得に失敗しました")?;
 
-async fn insert_targets(
-    tx: &Transaction<'_>,
-    mention_id: i64,
-    targets: &[u64],
-) -> anyhow::Result<()> {
-    for user_id in targets {
-        tx.execute(
-            "INSERT INTO mention_targets (mention_id, user_id) \
-             VALUES ($1, $2) \
-             ON CONFLICT (mention_id, user_id) DO NOTHING",
-            &[&mention_id, &(*user_id as i64)],
-        )
-        .await
-        .context("メンション対象者の保存に失敗しました")?;
+        let result = rows
+            .into_iter()
+            .map(|row| ResponderStats {
+                user_id: row.get::<_, i64>("user_id") as u64,
+                target_count: row.get::<_, i64>("target_count"),
+                never_read_count: row.get::<_, i64>("never_read_count"),
+                read_median_secs: row.get::<_, f64>("read_median"),
+            })
+            .collect();
+
+        Ok(result)
     }
-    Ok(())
-}
 
-async fn fetch_user_ids_by_mention(
-    client: &tokio_postgres::Client,
-    mention_ids: &[i64],
-    table: &str,
-) -> anyhow::Result<HashMap<i64, Vec<u64>>> {
-    let sql = format!(
-        "SELECT mention_id, user_id FROM {} WHERE mention_id = ANY($1)",
-        table
-    );
-    let rows = client
-        .query(&sql, &[&mention_ids])
-        .await
-        .with_context(|| format!("{} の取得に失敗しました", table))?;
+    pub async fn upsert_report_settings(
+        &self,
+        guild_id: u64,
+        channel_id: Option<u64>,
+        name_users: bool,
+    ) -> anyhow::Result<()> {
+        let client = self
+            .pool
+            .get()
+            .await
+            .context("DB接続の取得に失敗しました")?;
 
-    let mut map: HashMap<i64, Vec<u64>> = HashMap::new();
-    for row in rows {
-        let mention_id = row.get::<_, i64>("mention_id");
-        let user_id = row.get::<_, i64>("user_id") as u64;
-        map.entry(mention_id).or_default().push(user_id);
+        client
+            .execute(
+                "INSERT INTO guild_settings (guild_id, report_channel_id, report_name_users) \
+                 VALUES ($1, $2, $3) \
+                 ON CONFLICT (guild_id) DO UPDATE \
+                 SET report_channel_id = EXCLUDED.report_channel_id, \
+                     report_name_users = EXCLUDED.report_name_users",
+                &[
+                    &(guild_id as i64),
+                    &channel_id.map(|id| id as i64),
+                    &name_users,
+                ],
+            )
+            .await
+            .context("レポート設定の保存に失敗しました")?;
+
+        Ok(())
     }
-    for users in map.values_mut() {
-        users.sort_unstable();
-        users.dedup();
+
+    /// レポートの投稿先が設定されているギルドの設定を返す。
+    pub async fn fetch_report_settings(&self) -> anyhow::Result<Vec<ReportSettings>> {
+        let client = self
+            .pool
+            .get()
+            .await
+            .context("DB接続の取得に失敗しました")?;
+
+        let rows = client
+            .query(
+                "SELECT guild_id, report_channel_id, report_name_users \
+                 FROM guild_settings \
+                 WHERE report_channel_id IS NOT NULL",
+                &[],
+            )
+            .await
+            .context("レポート設定の取得に失敗しました")?;
+
+        let result = rows
+            .into_iter()
+            .map(|row| ReportSettings {
+                guild_id: row.get::<_, i64>("guild_id") as u64,
+                channel_id: row.get::<_, i64>("report_channel_id") as u64,
+                name_users: row.get::<_, bool>("report_name_users"),
+            })
+            .collect();
+
+        Ok(result)
+    }
+
+    pub async fn count_mentions_in_guild(
+        &self,
+        guild_id: u64,
+        since_unix: i64,
+        until_unix: i64,
+    ) -> anyhow::Result<i64> {
+        let client = self
+            .pool
+            .get()
+            .await
+            .context("DB接続の取得に失敗しました")?;
+
+        let row = client
+            .query_one(
+                "SELECT COUNT(*) FROM mentions \
+                 WHERE guild_id = $1 AND created_at >= $2 AND created_at < $3",
+                &[&(guild_id as i64), &since_unix, &until_unix],
+            )
+            .await
+            .context("メンション数の取得に失敗しました")?;
+
+        Ok(row.get::<_, i64>(0))
+    }
+
+    /// 未読かつ未解決のメンションを多く抱えている対象者を返す。
+    pub async fn fetch_top_unread_users(
+        &self,
+        guild_id: u64,
+        limit: i64,
+    ) -> anyhow::Result<Vec<UnreadCount>> {
+        let client = self
+            .pool
+            .get()
+            .await
+            .context("DB接続の取得に失敗しました")?;
+
+        let sql = format!(
+            "SELECT mt.user_id, COUNT(*) AS unread_count \
+             FROM mention_targets mt \
+             JOIN mentions m ON m.id = mt.mention_id \
+             WHERE m.guild_id = $1 \
+               AND mt.ignored_at IS NULL \
+               AND {TARGET_TRACKED_SQL} \
+               AND NOT EXISTS(SELECT 1 FROM mention_reads \
+                              WHERE mention_id = mt.mention_id AND user_id = mt.user_id) \
+               AND NOT EXISTS(SELECT 1 FROM mention_dones \
+                              WHERE mention_id = mt.mention_id AND user_id = mt.user_id) \
+             GROUP BY mt.user_id \
+             ORDER BY unread_count DESC, mt.user_id \
+             LIMIT $2"
+        );
+        let rows = client
+            .query(&sql, &[&(guild_id as i64), &limit])
+            .await
+            .context("未読の多い対象者の取得に失敗しました")?;
+
+        let result = rows
+            .into_iter()
+            .map(|row| UnreadCount {
+                user_id: row.get::<_, i64>("user_id") as u64,
+                unread_count: row.get::<_, i64>("unread_count"),
+            })
+            .collect();
+
+        Ok(result)
+    }
+
+    /// 未解決の対象者が残っているメンションを古い順に返す。
+    pub async fn fetch_oldest_unresolved(
+        &self,
+        guild_id: u64,
+        limit: i64,
+    ) -> anyhow::Result<Vec<UnresolvedMention>> {
+        let client = self
+            .pool
+            .get()
+            .await
+            .context("DB接続の取得に失敗しました")?;
+
+        let sql = format!(
+            "SELECT m.guild_id, m.channel_id, m.message_id, m.author_id, m.created_at, \
+                    COUNT(*) AS pending_count \
+             FROM mentions m \
+             JOIN mention_targets mt ON mt.mention_id = m.id \
+             WHERE m.guild_id = $1 \
+               AND mt.ignored_at IS NULL \
+               AND {TARGET_TRACKED_SQL} \
+               AND NOT EXISTS(SELECT 1 FROM mention_dones \
+                              WHERE mention_id = m.id AND user_id = mt.user_id) \
+             GROUP BY m.id \
+             ORDER BY m.created_at ASC \
+             LIMIT $2"
+        );
+        let rows = client
+            .query(&sql, &[&(guild_id as i64), &limit])
+            .await
+            .context("未解決メンションの取得に失敗しました")?;
+
+        let result = rows
+            .into_iter()
+            .map(|row| UnresolvedMention {
+                guild_id: row.get::<_, i64>("guild_id") as u64,
+                channel_id: row.get::<_, i64>("channel_id") as u64,
+                message_id: row.get::<_, i64>("message_id") as u64,
+                author_id: row.get::<_, i64>("author_id") as u64,
+                created_at_unix: row.get::<_, i64>("created_at"),
+                pending_count: row.get::<_, i64>("pending_count"),
+            })
+            .collect();
+
+        Ok(result)
+    }
+
+    pub async fn fetch_mention_progress(
+        &self,
+        message_id: u64,
+    ) -> anyhow::Result<Option<MentionProgress>> {
+        let client = self
+            .pool
+            .get()
+            .await
+            .context("DB接続の取得に失敗しました")?;
+
+        let sql = format!(
+            "SELECT m.guild_id, m.channel_id, m.message_id, m.author_id, m.created_at, \
+                    mt.user_id, r.read_at, d.done_at \
+             FROM mentions m \
+             JOIN mention_targets mt ON mt.mention_id = m.id \
+             LEFT JOIN mention_reads r ON r.mention_id = m.id AND r.user_id = mt.user_id \
+             LEFT JOIN mention_dones d ON d.mention_id = m.id AND d.user_id = mt.user_id \
+             WHERE m.message_id = $1 AND mt.ignored_at IS NULL \
+               AND {TARGET_TRACKED_SQL}"
+        );
+        let rows = client
+            .query(&sql, &[&(message_id as i64)])
+            .await
+            .context("メンションの進捗の取得に失敗しました")?;
+
+        let Some(first) = rows.first() else {
+            return Ok(None);
+        };
+        let targets = rows
+            .iter()
+            .map(|row| TargetProgress {
+                user_id: row.get::<_, i64>("user_id") as u64,
+                read_at_unix: row.get::<_, Option<i64>>("read_at"),
+                done_at_unix: row.get::<_, Option<i64>>("done_at"),
+            })
+            .collect();
+
+        Ok(Some(MentionProgress {
+            guild_id: first.get::<_, i64>("guild_id") as u64,
+            channel_id: first.get::<_, i64>("channel_id") as u64,
+            message_id: first.get::<_, i64>("message_id") as u64,
+            author_id: first.get::<_, i64>("author_id") as u64,
+            created_at_unix: first.get::<_, i64>("created_at"),
+            targets,
+        }))
+    }
+
+    /// 送信者の設定を優先し、未設定ならギルドの既定値を返す。
+    pub async fn fetch_all_read_notify_mode(
+        &self,
+        author_id: u64,
+        guild_id: u64,
+    ) -> anyhow::Result<AllReadNotifyMode> {
+        let client = self
+            .pool
+            .get()
+            .await
+            .context("DB接続の取得に失敗しました")?;
+
+        let row = client
+            .query_one(
+                "SELECT COALESCE(\
+                    (SELECT all_read_notify FROM user_settings WHERE user_id = $1), \
+                    (SELECT all_read_notify FROM guild_settings WHERE guild_id = $2), \
+                    'off')",
+                &[&(author_id as i64), &(guild_id as i64)],
+            )
+            .await
+            .context("完了通知設定の取得に失敗しました")?;
+
+        Ok(AllReadNotifyMode::from_db(&row.get::<_, String>(0)))
+    }
+
+    /// `None` を渡すとギルドの既定値に従う。
+    pub async fn upsert_user_all_read_notify(
+        &self,
+        user_id: u64,
+        mode: Option<AllReadNotifyMode>,
+    ) -> anyhow::Result<()> {
+        let client = self
+            .pool
+            .get()
+            .await
+            .context("DB接続の取得に失敗しました")?;
+
+        client
+            .execute(
+                "INSERT INTO user_settings (user_id, all_read_notify) \
+                 VALUES ($1, $2) \
+                 ON CONFLICT (user_id) DO UPDATE \
+                 SET all_read_notify = EXCLUDED.all_read_notify",
+                &[&(user_id as i64), &mode.map(AllReadNotifyMode::as_str)],
+            )
+            .await
+            .context("完了通知設定の保存に失敗しました")?;
+
+        Ok(())
+    }
+
+    pub async fn upsert_guild_all_read_notify(
+        &self,
+        guild_id: u64,
+        mode: AllReadNotifyMode,
+    ) -> anyhow::Result<()> {
+        let client = self
+            .pool
+            .get()
+            .await
+            .context("DB接続の取得に失敗しました")?;
+
+        client
+            .execute(
+                "INSERT INTO guild_settings (guild_id, all_read_notify) \
+                 VALUES ($1, $2) \
+                 ON CONFLICT (guild_id) DO UPDATE \
+                 SET all_read_notify = EXCLUDED.all_read_notify",
+                &[&(guild_id as i64), &mode.as_str()],
+            )
+            .await
+            .context("完了通知設定の保存に失敗しました")?;
+
+        Ok(())
+    }
+
+    pub async fn fetch_user_notify_settings(
+        &self,
+        user_id: u64,
+    ) -> anyhow::Result<UserNotifySettings> {
+        let client = self
+            .pool
+            .get()
+            .await
+            .context("DB接続の取得に失敗しました")?;
+
+        let row = client
+            .query_opt(
+                "SELECT digest_dm_disabled, tracking_opt_out FROM user_settings \
+                 WHERE user_id = $1",
+                &[&(user_id as i64)],
+            )
+            .await
+            .context("通知設定の取得に失敗しました")?;
+        let mute_rows = client
+            .query(
+                "SELECT kind, target_id FROM user_mutes \
+                 WHERE user_id = $1 \
+                 ORDER BY kind, target_id",
+                &[&(user_id as i64)],
+            )
+            .await
+            .context("ミュート設定の取得に失敗しました")?;
+
+        let mutes = mute_rows
+            .into_iter()
+            .filter_map(|row| {
+                UserMute::from_db(
+                    row.get::<_, &str>("kind"),
+                    row.get::<_, i64>("target_id") as u64,
+                )
+            })
+            .collect();
+
+        Ok(UserNotifySettings {
+            digest_dm_disabled: row
+                .as_ref()
+                .is_some_and(|row| row.get::<_, bool>("digest_dm_disabled")),
+            tracking_opt_out: row
+                .as_ref()
+                .is_some_and(|row| row.get::<_, bool>("tracking_opt_out")),
+            mutes,
+        })
+    }
+
+    pub async fn update_user_digest_dm_disabled(
+        &self,
+        user_id: u64,
+        disabled: bool,
+    ) -> anyhow::Result<()> {
+        let client = self
+            .pool
+            .get()
+            .await
+            .context("DB接続の取得に失敗しました")?;
+
+        client
+            .execute(
+                "INSERT INTO user_settings (user_id, digest_dm_disabled) \
+                 VALUES ($1, $2) \
+                 ON CONFLICT (user_id) DO UPDATE \
+                 SET digest_dm_disabled = EXCLUDED.digest_dm_disabled",
+                &[&(user_id as i64), &disabled],
+            )
+            .await
+            .context("定期DM設定の保存に失敗しました")?;
+
+        Ok(())
+    }
+
+    pub async fn update_user_tracking_opt_out(
+        &self,
+        user_id: u64,
+        opt_out: bool,
+    ) -> anyhow::Result<()> {
+        let client = self
+            .pool
+            .get()
+            .await
+            .context("DB接続の取得に失敗しました")?;
+
+        client
+            .execute(
+                "INSERT INTO user_settings (user_id, tracking_opt_out) \
+                 VALUES ($1, $2) \
+                 ON CONFLICT (user_id) DO UPDATE \
+                 SET tracking_opt_out = EXCLUDED.tracking_opt_out",
+                &[&(user_id as i64), &opt_out],
+            )
+            .await
+            .context("追跡拒否設定の保存に失敗しました")?;
+
+        Ok(())
+    }
+
+    /// ミュートを追加する。追加済みなら `false` を返す。
+    pub async fn insert_user_mute(&self, user_id: u64, mute: UserMute) -> anyhow::Result<bool> {
+        let client = self
+            .pool
+            .get()
+            .await
+            .context("DB接続の取得に失敗しました")?;
+
+        let inserted = client
+            .execute(
+                "INSERT INTO user_mutes (user_id, kind, target_id) \
+                 VALUES ($1, $2, $3) \
+                 ON CONFLICT DO NOTHING",
+                &[&(user_id as i64), &mute.kind_str(), &(mute.id() as i64)],
+            )
+            .await
+            .context("ミュート設定の保存に失敗しました")?;
+
+        Ok(inserted > 0)
+    }
+
+    pub async fn delete_user_mute(&self, user_id: u64, mute: UserMute) -> anyhow::Result<u64> {
+        let client = self
+            .pool
+            .get()
+            .await
+            .context("DB接続の取得に失敗しました")?;
+
+        let deleted = client
+            .execute(
+                "DELETE FROM user_mutes \
+                 WHERE user_id = $1 AND kind = $2 AND target_id = $3",
+                &[&(user_id as i64), &mute.kind_str(), &(mute.id() as i64)],
+            )
+            .await
+            .context("ミュート設定の削除に失敗しました")?;
+
+        Ok(deleted)
+    }
+
+    /// `user_ids` のうち追跡を拒否しているユーザーを返す。
+    pub async fn fetch_tracking_opt_out_user_ids(
+        &self,
+        user_ids: &[u64],
+    ) -> anyhow::Result<Vec<u64>> {
+        if user_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let client = self
+            .pool
+            .get()
+            .await
+            .context("DB接続の取得に失敗しました")?;
+
+        let ids = user_ids.iter().map(|id| *id as i64).collect::<Vec<_>>();
+        let rows = client
+            .query(
+                "SELECT user_id FROM user_settings \
+                 WHERE user_id = ANY($1) AND tracking_opt_out",
+                &[&ids],
+            )
+            .await
+            .context("追跡拒否ユーザーの取得に失敗しました")?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| row.get::<_, i64>("user_id") as u64)
+            .collect())
+    }
+
+    pub async fn fetch_content_storage_mode(
+        &self,
+        guild_id: u64,
+    ) -> anyhow::Result<ContentStorageMode> {
+        let client = self
+            .pool
+            .get()
+            .await
+            .context("DB接続の取得に失敗しました")?;
+
+        let row = client
+            .query_opt(
+                "SELECT content_storage FROM guild_settings WHERE guild_id = $1",
+                &[&(guild_id as i64)],
+            )
+            .await
+            .context("本文の保存方式の取得に失敗しました")?;
+
+        Ok(row
+            .map(|row| ContentStorageMode::from_db(row.get::<_, &str>("content_storage")))
+            .unwrap_or_default())
+    }
+
+    pub async fn upsert_content_storage_mode(
+        &self,
+        guild_id: u64,
+        mode: ContentStorageMode,
+    ) -> anyhow::Result<()> {
+        let client = self
+            .pool
+            .get()
+            .await
+            .context("DB接続の取得に失敗しました")?;
+
+        client
+            .execute(
+                "INSERT INTO guild_settings (guild_id, content_storage) \
+                 VALUES ($1, $2) \
+                 ON CONFLICT (guild_id) DO UPDATE \
+                 SET content_storage = EXCLUDED.content_storage",
+                &[&(guild_id as i64), &mode.as_str()],
+            )
+            .await
+            .context("本文の保存方式の保存に失敗しました")?;
+
+        Ok(())
+    }
+
+    /// ユーザーについて保存しているデータを返す。`guild_id` を渡すとそのギルドの記録に限る。
+    pub async fn fetch_personal_data(
+        &self,
+        user_id: u64,
+        guild_id: Option<u64>,
+    ) -> anyhow::Result<PersonalData> {
+        let client = self
+            .pool
+            .get()
+            .await
+            .context("DB接続の取得に失敗しました")?;
+        let user_id_param = user_id as i64;
+        let guild_id_param = guild_id.map(|id| id as i64);
+
+        let authored_rows = client
+            .query(
+                "SELECT id, guild_id, channel_id, message_id, content, created_at, due_at, priority \
+                 FROM mentions \
+                 WHERE author_id = $1 AND ($2::BIGINT IS NULL OR guild_id = $2) \
+                 ORDER BY created_at",
+                &[&user_id_param, &guild_id_param],
+            )
+            .await
+            .context("送信したメンションの取得に失敗しました")?;
+        let mention_ids = authored_rows
+            .iter()
+            .map(|row| row.get::<_, i64>("id"))
+            .collect::<Vec<_>>();
+        let targets = fetch_user_ids_by_mention(&client, &mention_ids, "mention_targets").await?;
+        let authored = authored_rows
+            .into_iter()
+            .map(|row| {
+                let mention_id = row.get::<_, i64>("id");
+                AuthoredMentionRecord {
+                    guild_id: row.get::<_, i64>("guild_id") as u64,
+                    channel_id: row.get::<_, i64>("channel_id") as u64,
+                    message_id: row.get::<_, i64>("message_id") as u64,
+                    content: self.read_content(&row),
+                    created_at_unix: row.get::<_, i64>("created_at"),
+                    due_at_unix: row.get::<_, Option<i64>>("due_at"),
+                    priority: MentionPriority::from_db(row.get::<_, &str>("priority")),
+                    target_user_ids: targets.get(&mention_id).cloned().unwrap_or_default(),
+                }
+            })
+            .collect();
+
+        let received_rows = client
+            .query(
+                "SELECT m.guild_id, m.channel_id, m.message_id, m.author_id, m.content, m.created_at, \
+                        mt.user_id IS NOT NULL AS is_target, mt.extended_until, mt.ignored_at, \
+                        r.read_at, d.done_at \
+                 FROM mentions m \
+                 LEFT JOIN mention_targets mt ON mt.mention_id = m.id AND mt.user_id = $1 \
+                 LEFT JOIN mention_reads r ON r.mention_id = m.id AND r.user_id = $1 \
+                 LEFT JOIN mention_dones d ON d.mention_id = m.id AND d.user_id = $1 \
+                 WHERE ($2::BIGINT IS NULL OR m.guild_id = $2) \
+                   AND (mt.user_id IS NOT NULL OR r.user_id IS NOT NULL OR d.user_id IS NOT NULL) \
+                 ORDER BY m.created_at",
+                &[&user_id_param, &guild_id_param],
+            )
+            .await
+            .context("受け取ったメンションの取得に失敗しました")?;
+        let received = received_rows
+            .into_iter()
+            .map(|row| ReceivedMentionRecord {
+                guild_id: row.get::<_, i64>("guild_id") as u64,
+                channel_id: row.get::<_, i64>("channel_id") as u64,
+                message_id: row.get::<_, i64>("message_id") as u64,
+                author_id: row.get::<_, i64>("author_id") as u64,
+                content: self.read_content(&row),
+                created_at_unix: row.get::<_, i64>("created_at"),
+                is_target: row.get::<_, bool>("is_target"),
+                read_at_unix: row.get::<_, Option<i64>>("read_at"),
+                done_at_unix: row.get::<_, Option<i64>>("done_at"),
+                extended_until: row.get::<_, Option<i64>>("extended_until"),
+                ignored_at_unix: row.get::<_, Option<i64>>("ignored_at"),
+            })
+            .collect();
+
+        // 個人設定はギルドをまたぐため、本人のエクスポートにだけ含める。
+        let (settings, all_read_notify) = if guild_id.is_none() {
+            let settings = self.fetch_user_notify_settings(user_id).await?;
+            let all_read_notify = client
+                .query_opt(
+                    "SELECT all_read_notify FROM user_settings WHERE user_id = $1",
+                    &[&user_id_param],
+                )
+                .await
+                .context("完了通知設定の取得に失敗しました")?
+                .and_then(|row| row.get::<_, Option<String>>("all_read_notify"))
+                .map(|value| AllReadNotifyMode::from_db(&value));
+            (Some(settings), all_read_notify)
+        } else {
+            (None, None)
+        };
+
+        Ok(PersonalData {
+            authored,
+            received,
+            settings,
+            all_read_notify,
+        })
+    }
+
+    /// ユーザーについて保存しているデータを削除する。送信したメンションは対象者の記録ごと消える。
+    /// `guild_id` を渡すとそのギルドの記録に限り、個人設定は残す。
+    pub async fn delete_personal_data(
+        &self,
+        user_id: u64,
+        guild_id: Option<u64>,
+    ) -> anyhow::Result<PersonalDataDeletion> {
+        let mut client = self
+            .pool
+            .get()
+            .await
+            .context("DB接続の取得に失敗しました")?;
+        let tx = client
+            .transaction()
+            .await
+            .context("トランザクション開始に失敗しました")?;
+        let user_id_param = user_id as i64;
+        let guild_id_param = guild_id.map(|id| id as i64);
+
+        let mentions = tx
+            .execute(
+                "DELETE FROM mentions \
+                 WHERE author_id = $1 AND ($2::BIGINT IS NULL OR guild_id = $2)",
+                &[&user_id_param, &guild_id_param],
+            )
+            .await
+            .context("送信したメンションの削除に失敗しました")?;
+        let mut deleted = PersonalDataDeletion {
+            mentions,
+            ..Default::default()
+        };
+        for (table, count) in [
+            ("mention_targets", &mut deleted.targets),
+            ("mention_reads", &mut deleted.reads),
+            ("mention_dones", &mut deleted.dones),
+        ] {
+            *count = delete_user_rows_in_guild(&tx, table, user_id_param, guild_id_param).await?;
+        }
+        delete_user_rows_in_guild(&tx, "mention_due_reminders", user_id_param, guild_id_param)
+            .await?;
+
+        // 追跡の停止とミュートは、削除後に記録や通知が再開しないよう残す。
+        if guild_id.is_none() {
+            deleted.settings += tx
+                .execute(
+                    "DELETE FROM user_settings WHERE user_id = $1 AND NOT tracking_opt_out",
+                    &[&user_id_param],
+                )
+                .await
+                .context("個人設定の削除に失敗しました")?;
+            deleted.settings += tx
+                .execute(
+                    "UPDATE user_settings \
+                     SET all_read_notify = NULL, digest_dm_disabled = FALSE \
+                     WHERE user_id = $1 AND tracking_opt_out",
+                    &[&user_id_param],
+                )
+                .await
+                .context("個人設定の初期化に失敗しました")?;
+        }
+
+        tx.commit()
+            .await
+            .context("トランザクションのコミットに失敗しました")?;
+        Ok(deleted)
+    }
+
+    /// チャンネルまたはスレッドで記録したメンションを削除する。
+    pub async fn delete_mentions_by_channel_id(&self, channel_id: u64) -> anyhow::Result<u64> {
+        let client = self
+            .pool
+            .get()
+            .await
+            .context("DB接続の取得に失敗しました")?;
+
+        let deleted = client
+            .execute(
+                "DELETE FROM mentions WHERE channel_id = $1",
+                &[&(channel_id as i64)],
+            )
+            .await
+            .context("チャンネルのメンション削除に失敗しました")?;
+
+        client
+            .execute(
+                "DELETE FROM channel_settings WHERE channel_id = $1",
+                &[&(channel_id as i64)],
+            )
+            .await
+            .context("チャンネル設定の削除に失敗しました")?;
+
+        Ok(deleted)
+    }
+
+    /// Bot がギルドから退出したことを記録する。記録済みなら最初の退出時刻を残す。
+    pub async fn mark_guild_departed(
+        &self,
+        guild_id: u64,
+        left_at_unix: i64,
+    ) -> anyhow::Result<()> {
+        let client = self
+            .pool
+            .get()
+            .await
+            .context("DB接続の取得に失敗しました")?;
+
+        client
+            .execute(
+                "INSERT INTO departed_guilds (guild_id, left_at) \
+                 VALUES ($1, $2) \
+                 ON CONFLICT (guild_id) DO NOTHING",
+                &[&(guild_id as i64), &left_at_unix],
+            )
+            .await
+            .context("ギルドの退出記録に失敗しました")?;
+
+        Ok(())
+    }
+
+    /// 再招待されたギルドの退出記録を消す。記録があった場合は `true` を返す。
+    pub async fn clear_guild_departure(&self, guild_id: u64) -> anyhow::Result<bool> {
+        let client = self
+            .pool
+            .get()
+            .await
+            .context("DB接続の取得に失敗しました")?;
+
+        let deleted = client
+            .execute(
+                "DELETE FROM departed_guilds WHERE guild_id = $1",
+                &[&(guild_id as i64)],
+            )
+            .await
+            .context("ギルドの退出記録の削除に失敗しました")?;
+
+        Ok(deleted > 0)
+    }
+
+    /// `left_before_unix` より前に退出したギルドのメンションと設定を削除し、削除したメンション数を返す。
+    pub async fn purge_departed_guilds(&self, left_before_unix: i64) -> anyhow::Result<u64> {
+        let mut client = self
+            .pool
+            .get()
+            .await
+            .context("DB接続の取得に失敗しました")?;
+        let tx = client
+            .transaction()
+            .await
+            .context("トランザクション開始に失敗しました")?;
+
+        let rows = tx
+            .query(
+                "SELECT guild_id FROM departed_guilds WHERE left_at < $1",
+                &[&left_before_unix],
+            )
+            .await
+            .context("退出したギルドの取得に失敗しました")?;
+        let guild_ids = rows
+            .iter()
+            .map(|row| row.get::<_, i64>("guild_id"))
+            .collect::<Vec<_>>();
+        if guild_ids.is_empty() {
+            return Ok(0);
+        }
+
+        let deleted = tx
+            .execute(
+                "DELETE FROM mentions WHERE guild_id = ANY($1)",
+                &[&guild_ids],
+            )
+            .await
+            .context("退出したギルドのメンション削除に失敗しました")?;
+        for table in [
+            "guild_settings",
+            "channel_settings",
+            "tracking_scope_rules",
+            "untracked_messages",
+            "departed_guilds",
+        ] {
+            tx.execute(
+                &format!("DELETE FROM {table} WHERE guild_id = ANY($1)"),
+                &[&guild_ids],
+            )
+            .await
+            .with_context(|| format!("{table} の削除に失敗しました"))?;
+        }
+
+        tx.commit()
+            .await
+            .context("トランザクションのコミットに失敗しました")?;
+        Ok(deleted)
+    }
+
+    /// メンションを記録したチャンネルごとに、最新のメッセージを返す。
+    /// 最後の記録が古いチャンネルにも停止中の投稿はあり得るため、記録日時では絞らない。
+    pub async fn fetch_channel_cursors(&self) -> anyhow::Result<Vec<ChannelCursor>> {
+        let client = self
+            .pool
+            .get()
+            .await
+            .context("DB接続の取得に失敗しました")?;
+
+        let rows = client
+            .query(
+                "SELECT DISTINCT ON (m.channel_id) m.guild_id, m.channel_id, m.message_id \
+                 FROM mentions m \
+                 WHERE NOT EXISTS(SELECT 1 FROM departed_guilds dg WHERE dg.guild_id = m.guild_id) \
+                 ORDER BY m.channel_id, m.created_at DESC, m.message_id DESC",
+                &[],
+            )
+            .await
+            .context("チャンネルごとの最新メンションの取得に失敗しました")?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| ChannelCursor {
+                guild_id: row.get::<_, i64>("guild_id") as u64,
+                channel_id: row.get::<_, i64>("channel_id") as u64,
+                last_message_id: row.get::<_, i64>("message_id") as u64,
+            })
+            .collect())
+    }
+
+    /// `since_unix` 以降のメンションのうち、解決していない対象者が残るものを新しい順に最大 `limit` 件返す。
+    pub async fn fetch_open_mention_messages(
+        &self,
+        since_unix: i64,
+        limit: i64,
+    ) -> anyhow::Result<Vec<OpenMentionMessage>> {
+        let client = self
+            .pool
+            .get()
+            .await
+            .context("DB接続の取得に失敗しました")?;
+
+        let rows = client
+            .query(
+                "SELECT m.channel_id, m.message_id \
+                 FROM mentions m \
+                 WHERE m.created_at >= $1 \
+                   AND NOT EXISTS(SELECT 1 FROM departed_guilds dg WHERE dg.guild_id = m.guild_id) \
+                   AND EXISTS(SELECT 1 FROM mention_targets mt \
+                              WHERE mt.mention_id = m.id \
+                                AND mt.ignored_at IS NULL \
+                                AND NOT EXISTS(SELECT 1 FROM mention_dones d \
+                                               WHERE d.mention_id = m.id AND d.user_id = mt.user_id)) \
+                 ORDER BY m.created_at DESC \
+                 LIMIT $2",
+                &[&since_unix, &limit],
+            )
+            .await
+            .context("未解決メンションの取得に失敗しました")?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| OpenMentionMessage {
+                channel_id: row.get::<_, i64>("channel_id") as u64,
+                message_id: row.get::<_, i64>("message_id") as u64,
+            })
+            .collect())
+    }
+
+    /// `None` を渡すと既定の保持期間に従う。`Some(0)` は削除しない。
+    pub async fn upsert_retention_days(
+        &self,
+        guild_id: u64,
+        days: Option<u32>,
+    ) -> anyhow::Result<()> {
+        let client = self
+            .pool
+            .get()
+            .await
+            .context("DB接続の取得に失敗しました")?;
+
+        client
+            .execute(
+                "INSERT INTO guild_settings (guild_id, retention_days) \
+                 VALUES ($1, $2) \
+                 ON CONFLICT (guild_id) DO UPDATE \
+                 SET retention_days = EXCLUDED.retention_days",
+                &[&(guild_id as i64), &days.map(|days| days as i32)],
+            )
+            .await
+            .context("保持期間の保存に失敗しました")?;
+
+        Ok(())
+    }
+
+    pub async fn fetch_retention_days(&self, guild_id: u64) -> anyhow::Result<Option<u32>> {
+        let client = self
+            .pool
+            .get()
+            .await
+            .context("DB接続の取得に失敗しました")?;
+
+        let row = client
+            .query_opt(
+                "SELECT retention_days FROM guild_settings WHERE guild_id = $1",
+                &[&(guild_id as i64)],
+            )
+            .await
+            .context("保持期間の取得に失敗しました")?;
+
+        Ok(row
+            .and_then(|row| row.get::<_, Option<i32>>("retention_days"))
+            .map(|days| days.max(0) as u32))
+    }
+
+    /// 保持期間を過ぎて削除対象になるメンションを数える。削除はしない。
+    pub async fn preview_purge(
+        &self,
+        default_days: Option<u32>,
+        now_unix: i64,
+        guild_id: Option<u64>,
+    ) -> anyhow::Result<PurgePreview> {
+        let client = self
+            .pool
+            .get()
+            .await
+            .context("DB接続の取得に失敗しました")?;
+
+        let sql = format!("SELECT COUNT(*), MIN(m.created_at) {PURGEABLE_MENTIONS_SQL}");
+        let row = client
+            .query_one(
+                &sql,
+                &[
+                    &(default_days.unwrap_or(0) as i32),
+                    &now_unix,
+                    &guild_id.map(|id| id as i64),
+                ],
+            )
+            .await
+            .context("削除対象メンションの集計に失敗しました")?;
+
+        Ok(PurgePreview {
+            mention_count: row.get::<_, i64>(0),
+            oldest_created_at_unix: row.get::<_, Option<i64>>(1),
+        })
+    }
+
+    /// 保持期間を過ぎたメンションを古い順に最大 `limit` 件削除し、削除件数を返す。
+    /// 対象者・既読・解決などの記録は外部キーでまとめて消える。
+    pub async fn purge_expired_mentions(
+        &self,
+        default_days: Option<u32>,
+        now_unix: i64,
+        limit: i64,
+    ) -> anyhow::Result<u64> {
+        let client = self
+            .pool
+            .get()
+            .await
+            .context("DB接続の取得に失敗しました")?;
+
+        let sql = format!(
+            "DELETE FROM mentions WHERE id IN (\
+                 SELECT m.id {PURGEABLE_MENTIONS_SQL} \
+                 ORDER BY m.created_at \
+                 LIMIT $4)"
+        );
+        let deleted = client
+            .execute(
+                &sql,
+                &[
+                    &(default_days.unwrap_or(0) as i32),
+                    &now_unix,
+                    &None::<i64>,
+                    &limit,
+                ],
+            )
+            .await
+            .context("保持期間を過ぎたメンションの削除に失敗しました")?;
+
+        Ok(deleted)
+    }
+
+    /// `None` を渡した種類のエスカレーションは無効になる。
+    pub async fn upsert_escalation_settings(
+        &self,
+        guild_id: u64,
+        author_after_hours: Option<i32>,
+        reping_after_hours: Option<i32>,
+    ) -> anyhow::Result<()> {
+        let client = self
+            .pool
+            .get()
+            .await
+            .context("DB接続の取得に失敗しました")?;
+
+        client
+            .execute(
+                "INSERT INTO guild_settings \
+                 (guild_id, escalate_author_after_hours, escalate_reping_after_hours) \
+                 VALUES ($1, $2, $3) \
+                 ON CONFLICT (guild_id) DO UPDATE \
+                 SET escalate_author_after_hours = EXCLUDED.escalate_author_after_hours, \
+                     escalate_reping_after_hours = EXCLUDED.escalate_reping_after_hours",
+                &[&(guild_id as i64), &author_after_hours, &reping_after_hours],
+            )
+            .await
+            .context("エスカレーション設定の保存に失敗しました")?;
+
+        Ok(())
+    }
+
+    /// ギルドに設定された時間を過ぎても未読の対象者が残り、まだエスカレーションしていない
+    /// メンションを返す。`lookback_secs` より前に期限を迎えたものは対象外とする。
+    pub async fn fetch_escalation_candidates(
+        &self,
+        kind: EscalationKind,
+        now_unix: i64,
+        lookback_secs: i64,
+    ) -> anyhow::Result<Vec<EscalationCandidate>> {
+        let client = self
+            .pool
+            .get()
+            .await
+            .context("DB接続の取得に失敗しました")?;
+
+        let sql = format!(
+            "SELECT m.id, m.guild_id, m.channel_id, m.message_id, m.author_id, m.content, \
+                    m.mention_everyone, m.created_at, m.due_at, m.priority \
+             FROM mentions m \
+             JOIN guild_settings gs ON gs.guild_id = m.guild_id \
+             WHERE gs.{column} IS NOT NULL \
+               AND m.priority <> 'fyi' \
+               AND m.created_at + gs.{column}::BIGINT * 3600 <= $1 \
+               AND m.created_at + gs.{column}::BIGINT * 3600 > $1 - $2 \
+               AND NOT EXISTS(SELECT 1 FROM mention_escalations \
+                              WHERE mention_id = m.id AND kind = $3) \
+               AND EXISTS(SELECT 1 FROM mention_targets mt \
+                          WHERE mt.mention_id = m.id AND mt.ignored_at IS NULL \
+                            AND {TARGET_NOTIFIABLE_SQL} \
+                            AND NOT EXISTS(SELECT 1 FROM mention_reads \
+                                           WHERE mention_id = m.id AND user_id = mt.user_id) \
+                            AND NOT EXISTS(SELECT 1 FROM mention_dones \
+                                           WHERE mention_id = m.id AND user_id = mt.user_id)) \
+             ORDER BY m.created_at",
+            column = escalation_settings_column(kind)
+        );
+        let rows = client
+            .query(&sql, &[&now_unix, &lookback_secs, &kind.as_str()])
+            .await
+            .context("エスカレーション対象の取得に失敗しました")?;
+
+        if rows.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mention_ids = rows
+            .iter()
+            .map(|row| row.get::<_, i64>("id"))
+            .collect::<Vec<_>>();
+        let targets = fetch_notifiable_target_ids_by_mention(&client, &mention_ids).await?;
+        let reads = fetch_user_ids_by_mention(&client, &mention_ids, "mention_reads").await?;
+        let dones = fetch_user_ids_by_mention(&client, &mention_ids, "mention_dones").await?;
+
+        let result = rows
+            .into_iter()
+            .map(|row| {
+                let mention_id = row.get::<_, i64>("id");
+                EscalationCandidate {
+                    mention_id,
+                    mention: StoredMention {
+                        author_id: row.get::<_, i64>("author_id") as u64,
+                        guild_id: row.get::<_, i64>("guild_id") as u64,
+                        channel_id: row.get::<_, i64>("channel_id") as u64,
+                        message_id: row.get::<_, i64>("message_id") as u64,
+                        content: self.read_content(&row),
+                        mention_everyone: row.get::<_, bool>("mention_everyone"),
+                        created_at_unix: row.get::<_, i64>("created_at"),
+                        due_at_unix: row.get::<_, Option<i64>>("due_at"),
+                        priority: MentionPriority::from_db(row.get::<_, &str>("priority")),
+                        target_user_ids: targets.get(&mention_id).cloned().unwrap_or_default(),
+                        read_user_ids: reads.get(&mention_id).cloned().unwrap_or_default(),
+                        done_user_ids: dones.get(&mention_id).cloned().unwrap_or_default(),
+                    },
+                }
+            })
+            .collect();
+
+        Ok(result)
+    }
+
+    /// エスカレーションの送信を記録する。記録済みなら `false` を返し、再送しない。
+    pub async fn record_escalation(
+        &self,
+        mention_id: i64,
+        kind: EscalationKind,
+        sent_at_unix: i64,
+    ) -> anyhow::Result<bool> {
+        let client = self
+            .pool
+            .get()
+            .await
+            .context("DB接続の取得に失敗しました")?;
+
+        let inserted = client
+            .execute(
+                "INSERT INTO mention_escalations (mention_id, kind, sent_at) \
+                 VALUES ($1, $2, $3) \
+                 ON CONFLICT (mention_id, kind) DO NOTHING",
+                &[&mention_id, &kind.as_str(), &sent_at_unix],
+            )
+            .await
+            .context("エスカレーション送信記録の保存に失敗しました")?;
+
+        Ok(inserted > 0)
+    }
+
+    /// 完了通知の送信記録を追加する。既に記録済みなら `false` を返し、通知は送らない。
+    pub async fn claim_completion_notification(
+        &self,
+        message_id: u64,
+        kind: CompletionKind,
+        notified_at_unix: i64,
+    ) -> anyhow::Result<bool> {
+        let client = self
+            .pool
+            .get()
+            .await
+            .context("DB接続の取得に失敗しました")?;
+
+        let claimed = client
+            .query_opt(
+                "INSERT INTO mention_completion_notifications (mention_id, kind, notified_at) \
+                 SELECT id, $2, $3 FROM mentions WHERE message_id = $1 \
+                 ON CONFLICT (mention_id, kind) DO NOTHING \
+                 RETURNING mention_id",
+                &[&(message_id as i64), &kind.as_str(), &notified_at_unix],
+            )
+            .await
+            .context("完了通知の送信記録の保存に失敗しました")?;
+
+        Ok(claimed.is_some())
+    }
+
+    /// チャンネルの既定の優先度を保存する。`None` で既定を解除する。
+    pub async fn upsert_channel_default_priority(
+        &self,
+        guild_id: u64,
+        channel_id: u64,
+        priority: Option<MentionPriority>,
+    ) -> anyhow::Result<()> {
+        let client = self
+            .pool
+            .get()
+            .await
+            .context("DB接続の取得に失敗しました")?;
+
+        client
+            .execute(
+                "INSERT INTO channel_settings (channel_id, guild_id, default_priority) \
+                 VALUES ($1, $2, $3) \
+                 ON CONFLICT (channel_id) DO UPDATE \
+                 SET default_priority = EXCLUDED.default_priority",
+                &[
+                    &(channel_id as i64),
+                    &(guild_id as i64),
+                    &priority.map(MentionPriority::as_str),
+                ],
+            )
+            .await
+            .context("チャンネルの既定優先度の保存に失敗しました")?;
+
+        Ok(())
+    }
+
+    pub async fn fetch_channel_default_priority(
+        &self,
+        channel_id: u64,
+    ) -> anyhow::Result<Option<MentionPriority>> {
+        let client = self
+            .pool
+            .get()
+            .await
+            .context("DB接続の取得に失敗しました")?;
+
+        let row = client
+            .query_opt(
+                "SELECT default_priority FROM channel_settings WHERE channel_id = $1",
+                &[&(channel_id as i64)],
+            )
+            .await
+            .context("チャンネルの既定優先度の取得に失敗しました")?;
+
+        Ok(row
+            .and_then(|row| row.get::<_, Option<String>>("default_priority"))
+            .map(|value| MentionPriority::from_db(&value)))
+    }
+
+    pub async fn fetch_tracking_scope_rules(
+        &self,
+        guild_id: u64,
+    ) -> anyhow::Result<Vec<ScopeRule>> {
+        let client = self
+            .pool
+            .get()
+            .await
+            .context("DB接続の取得に失敗しました")?;
+
+        let rows = client
+            .query(
+                "SELECT kind, target_id, mode FROM tracking_scope_rules \
+                 WHERE guild_id = $1 \
+                 ORDER BY kind, target_id",
+                &[&(guild_id as i64)],
+            )
+            .await
+            .context("追跡範囲ルールの取得に失敗しました")?;
+
+        let result = rows
+            .into_iter()
+            .filter_map(|row| {
+                let target = ScopeTarget::from_db(
+                    row.get::<_, &str>("kind"),
+                    row.get::<_, i64>("target_id") as u64,
+                )?;
+                let mode = ScopeMode::from_db(row.get::<_, &str>("mode"))?;
+                Some(ScopeRule { target, mode })
+            })
+            .collect();
+
+        Ok(result)
+    }
+
+    /// 追跡範囲ルールを保存する。同じ対象のルールは置き換える。
+    pub async fn upsert_tracking_scope_rule(
+        &self,
+        guild_id: u64,
+        rule: ScopeRule,
+    ) -> anyhow::Result<()> {
+        let client = self
+            .pool
+            .get()
+            .await
+            .context("DB接続の取得に失敗しました")?;
+
+        client
+            .execute(
+                "INSERT INTO tracking_scope_rules (guild_id, kind, target_id, mode) \
+                 VALUES ($1, $2, $3, $4) \
+                 ON CONFLICT (guild_id, kind, target_id) DO UPDATE \
+                 SET mode = EXCLUDED.mode",
+                &[
+                    &(guild_id as i64),
+                    &rule.target.kind_str(),
+                    &(rule.target.id() as i64),
+                    &rule.mode.as_str(),
+                ],
+            )
+            .await
+            .context("追跡範囲ルールの保存に失敗しました")?;
+
+        Ok(())
+    }
+
+    pub async fn delete_tracking_scope_rule(
+        &self,
+        guild_id: u64,
+        target: ScopeTarget,
+    ) -> anyhow::Result<u64> {
+        let client = self
+            .pool
+            .get()
+            .await
+            .context("DB接続の取得に失敗しました")?;
+
+        let deleted = client
+            .execute(
+                "DELETE FROM tracking_scope_rules \
+                 WHERE guild_id = $1 AND kind = $2 AND target_id = $3",
+                &[
+                    &(guild_id as i64),
+                    &target.kind_str(),
+                    &(target.id() as i64),
+                ],
+            )
+            .await
+            .context("追跡範囲ルールの削除に失敗しました")?;
+
+        Ok(deleted)
+    }
+
+    /// リマインダーの送信時刻を過ぎても未解決で、まだ送っていない (対象者, メンション) を返す。
+    /// `lookback_secs` より前に送信時刻を迎えたものは対象外とする。
+    pub async fn fetch_due_reminder_candidates(
+        &self,
+        kind: DueReminderKind,
+        now_unix: i64,
+        lookback_secs: i64,
+    ) -> anyhow::Result<Vec<DueReminderCandidate>> {
+        let client = self
+            .pool
+            .get()
+            .await
+            .context("DB接続の取得に失敗しました")?;
+
+        let (send_at, condition) = due_reminder_schedule_sql(kind);
+        let sql = format!(
+            "SELECT m.id, m.guild_id, m.channel_id, m.message_id, m.author_id, \
+                    m.content, m.mention_everyone, m.created_at, m.due_at, m.priority, mt.extended_until, mt.user_id, \
+                    EXISTS(SELECT 1 FROM mention_reads \
+                           WHERE mention_id = m.id AND user_id = mt.user_id) AS is_read \
+             FROM mentions m \
+             JOIN mention_targets mt ON m.id = mt.mention_id \
+             WHERE {send_at} IS NOT NULL \
+               AND {send_at} <= $1 \
+               AND {send_at} > $1 - $2 \
+               AND {condition} \
+               AND m.priority <> 'fyi' \
+               AND mt.ignored_at IS NULL \
+               AND {TARGET_NOTIFIABLE_SQL} \
+               AND NOT EXISTS(SELECT 1 FROM mention_dones \
+                              WHERE mention_id = m.id AND user_id = mt.user_id) \
+               AND NOT EXISTS(SELECT 1 FROM mention_due_reminders \
+                              WHERE mention_id = m.id AND user_id = mt.user_id AND kind = $3) \
+             ORDER BY mt.user_id, {send_at}"
+        );
+        let rows = client
+            .query(&sql, &[&now_unix, &lookback_secs, &kind.as_str()])
+            .await
+            .context("期限リマインダー対象の取得に失敗しました")?;
+
+        let result = rows
+            .into_iter()
+            .map(|row| DueReminderCandidate {
+                user_id: row.get::<_, i64>("user_id") as u64,
+                mention: MentionForTarget {
+                    mention_id: row.get::<_, i64>("id"),
+                    guild_id: row.get::<_, i64>("guild_id") as u64,
+                    channel_id: row.get::<_, i64>("channel_id") as u64,
+                    message_id: row.get::<_, i64>("message_id") as u64,
+                    author_id: row.get::<_, i64>("author_id") as u64,
+                    content: self.read_content(&row),
+                    mention_everyone: row.get::<_, bool>("mention_everyone"),
+                    created_at_unix: row.get::<_, i64>("created_at"),
+                    due_at_unix: row.get::<_, Option<i64>>("due_at"),
+                    priority: MentionPriority::from_db(row.get::<_, &str>("priority")),
+                    is_read: row.get::<_, bool>("is_read"),
+                    is_done: false,
+                    extended_until: row.get::<_, Option<i64>>("extended_until"),
+                },
+            })
+            .collect();
+
+        Ok(result)
+    }
+
+    /// 期限リマインダーの送信を記録する。記録済みなら `false` を返し、再送しない。
+    pub async fn record_due_reminder(
+        &self,
+        mention_id: i64,
+        user_id: u64,
+        kind: DueReminderKind,
+        sent_at_unix: i64,
+    ) -> anyhow::Result<bool> {
+        let client = self
+            .pool
+            .get()
+            .await
+            .context("DB接続の取得に失敗しました")?;
+
+        let inserted = client
+            .execute(
+                "INSERT INTO mention_due_reminders (mention_id, user_id, kind, sent_at) \
+                 VALUES ($1, $2, $3, $4) \
+                 ON CONFLICT (mention_id, user_id, kind) DO NOTHING",
+                &[
+                    &mention_id,
+                    &(user_id as i64),
+                    &kind.as_str(),
+                    &sent_at_unix,
+                ],
+            )
+            .await
+            .context("期限リマインダー送信記録の保存に失敗しました")?;
+
+        Ok(inserted > 0)
+    }
+}
+
+impl MentionRepository for Db {
+    async fn fetch_mention_by_message_id(
+        &self,
+        message_id: u64,
+    ) -> anyhow::Result<Option<StoredMention>> {
+        Db::fetch_mention_by_message_id(self, message_id).await
+    }
+
+    async fn delete_targets_except_by_message_id(
+        &self,
+        message_id: u64,
+        keep_user_ids: &[u64],
+    ) -> anyhow::Result<u64> {
+        Db::delete_targets_except_by_message_id(self, message_id, keep_user_ids).await
+    }
+
+    async fn record_done(
+        &self,
+        message_id: u64,
+        user_id: u64,
+        done_at_unix: i64,
+    ) -> anyhow::Result<bool> {
+        Db::record_done(self, message_id, user_id, done_at_unix, false).await
+    }
+}
+
+impl StatsRepository for Db {
+    async fn fetch_mention_stats(&self, filter: &StatsFilter) -> anyhow::Result<MentionStats> {
+        Db::fetch_mention_stats(self, filter).await
+    }
+
+    async fn fetch_slowest_responders(
+        &self,
+        filter: &StatsFilter,
+        limit: i64,
+    ) -> anyhow::Result<Vec<ResponderStats>> {
+        Db::fetch_slowest_responders(self, filter, limit).await
+    }
+}
+
+impl TeamReportRepository for Db {
+    async fn count_mentions_in_guild(
+        &self,
+        guild_id: u64,
+        since_unix: i64,
+        until_unix: i64,
+    ) -> anyhow::Result<i64> {
+        Db::count_mentions_in_guild(self, guild_id, since_unix, until_unix).await
+    }
+
+    async fn fetch_mention_stats(&self, filter: &StatsFilter) -> anyhow::Result<MentionStats> {
+        Db::fetch_mention_stats(self, filter).await
+    }
+
+    async fn fetch_top_unread_users(
+        &self,
+        guild_id: u64,
+        limit: i64,
+    ) -> anyhow::Result<Vec<UnreadCount>> {
+        Db::fetch_top_unread_users(self, guild_id, limit).await
+    }
+
+    async fn fetch_oldest_unresolved(
+        &self,
+        guild_id: u64,
+        limit: i64,
+    ) -> anyhow::Result<Vec<UnresolvedMention>> {
+        Db::fetch_oldest_unresolved(self, guild_id, limit).await
+    }
+}
+
+impl CompletionNotifyRepository for Db {
+    async fn fetch_mention_progress(
+        &self,
+        message_id: u64,
+    ) -> anyhow::Result<Option<MentionProgress>> {
+        Db::fetch_mention_progress(self, message_id).await
+    }
+
+    async fn fetch_all_read_notify_mode(
+        &self,
+        author_id: u64,
+        guild_id: u64,
+    ) -> anyhow::Result<AllReadNotifyMode> {
+        Db::fetch_all_read_notify_mode(self, author_id, guild_id).await
+    }
+
+    async fn claim_completion_notification(
+        &self,
+        message_id: u64,
+        kind: CompletionKind,
+        notified_at_unix: i64,
+    ) -> anyhow::Result<bool> {
+        Db::claim_completion_notification(self, message_id, kind, notified_at_unix).await
+    }
+}
+
+impl EscalationRepository for Db {
+    async fn fetch_escalation_candidates(
+        &self,
+        kind: EscalationKind,
+        now_unix: i64,
+        lookback_secs: i64,
+    ) -> anyhow::Result<Vec<EscalationCandidate>> {
+        Db::fetch_escalation_candidates(self, kind, now_unix, lookback_secs).await
+    }
+
+    async fn record_escalation(
+        &self,
+        mention_id: i64,
+        kind: EscalationKind,
+        sent_at_unix: i64,
+    ) -> anyhow::Result<bool> {
+        Db::record_escalation(self, mention_id, kind, sent_at_unix).await
+    }
+}
+
+impl TrackingRepository for Db {
+    async fn insert_mention(&self, mention: NewMention) -> anyhow::Result<()> {
+        Db::insert_mention(self, mention).await
+    }
+
+    async fn stop_tracking_message(
+        &self,
+        guild_id: u64,
+        message_id: u64,
+        stopped_at_unix: i64,
+    ) -> anyhow::Result<u64> {
+        Db::stop_tracking_message(self, guild_id, message_id, stopped_at_unix).await
+    }
+}
+
+impl PersonalDataRepository for Db {
+    async fn fetch_personal_data(
+        &self,
+        user_id: u64,
+        guild_id: Option<u64>,
+    ) -> anyhow::Result<PersonalData> {
+        Db::fetch_personal_data(self, user_id, guild_id).await
+    }
+
+    async fn delete_personal_data(
+        &self,
+        user_id: u64,
+        guild_id: Option<u64>,
+    ) -> anyhow::Result<PersonalDataDeletion> {
+        Db::delete_personal_data(self, user_id, guild_id).await
+    }
+}
+
+impl RetentionRepository for Db {
+    async fn purge_expired_mentions(
+        &self,
+        default_days: Option<u32>,
+        now_unix: i64,
+        limit: i64,
+    ) -> anyhow::Result<u64> {
+        Db::purge_expired_mentions(self, default_days, now_unix, limit).await
+    }
+
+    async fn purge_departed_guilds(&self, left_before_unix: i64) -> anyhow::Result<u64> {
+        Db::purge_departed_guilds(self, left_before_unix).await
+    }
+}
+
+impl DueReminderRepository for Db {
+    async fn fetch_due_reminder_candidates(
+        &self,
+        kind: DueReminderKind,
+        now_unix: i64,
+        lookback_secs: i64,
+    ) -> anyhow::Result<Vec<DueReminderCandidate>> {
+        Db::fetch_due_reminder_candidates(self, kind, now_unix, lookback_secs).await
+    }
+
+    async fn record_due_reminder(
+        &self,
+        mention_id: i64,
+        user_id: u64,
+        kind: DueReminderKind,
+        sent_at_unix: i64,
+    ) -> anyhow::Result<bool> {
+        Db::record_due_reminder(self, mention_id, user_id, kind, sent_at_unix).await
+    }
+}
+
+fn stats_params(filter: &StatsFilter) -> (i64, Option<i64>, Option<Vec<i64>>) {
+    (
+        filter.guild_id as i64,
+        filter.channel_id.map(|id| id as i64),
+        filter
+            .target_user_ids
+            .as_ref()
+            .map(|ids| ids.iter().map(|id| *id as i64).collect()),
+    )
+}
+
+async fn upsert_mention(tx: &Transaction<'_>, mention: &NewMention) -> anyhow::Result<i64> {
+    if let Some(row) = tx
+        .query_opt(
+            "SELECT id FROM mentions WHERE message_id = $1",
+            &[&(mention.message_id as i64)],
+        )
+        .await
+        .context("既存メンションの検索に失敗しました")?
+    {
+        return Ok(row.get::<_, i64>(0));
+    }
+
+    let row = tx
+        .query_one(
+            "INSERT INTO mentions \
+             (guild_id, channel_id, message_id, author_id, content, mention_everyone, created_at, due_at, priority) \
+             VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9) \
+             RETURNING id",
+            &[
+                &(mention.guild_id as i64),
+                &(mention.channel_id as i64),
+                &(mention.message_id as i64),
+                &(mention.author_id as i64),
+                &mention.content,
+                &mention.mention_everyone,
+                &mention.created_at_unix,
+                &mention.due_at_unix,
+                &mention.priority.as_str(),
+            ],
+        )
+        .await
+        .context("メンションの保存に失敗しました")?;
+
+    Ok(row.get::<_, i64>(0))
+}
+
+async fn insert_targets(
+    tx: &Transaction<'_>,
+    mention_id: i64,
+    targets: &[u64],
+) -> anyhow::Result<()> {
+    for user_id in targets {
+        tx.execute(
+            "INSERT INTO mention_targets (mention_id, user_id) \
+             VALUES ($1, $2) \
+             ON CONFLICT (mention_id, user_id) DO NOTHING",
+            &[&mention_id, &(*user_id as i64)],
+        )
+        .await
+        .context("メンション対象者の保存に失敗しました")?;
+    }
+    Ok(())
+}
+
+async fn delete_user_rows_in_guild(
+    tx: &Transaction<'_>,
+    table: &str,
+    user_id: i64,
+    guild_id: Option<i64>,
+) -> anyhow::Result<u64> {
+    let sql = format!(
+        "DELETE FROM {table} t \
+         USING mentions m \
+         WHERE t.mention_id = m.id AND t.user_id = $1 \
+           AND ($2::BIGINT IS NULL OR m.guild_id = $2)"
+    );
+    tx.execute(&sql, &[&user_id, &guild_id])
+        .await
+        .with_context(|| format!("{table} の削除に失敗しました"))
+}
+
+/// 追跡を拒否した対象者を除いた対象者一覧を返す。
+async fn fetch_tracked_target_ids_by_mention(
+    client: &tokio_postgres::Client,
+    mention_ids: &[i64],
+) -> anyhow::Result<HashMap<i64, Vec<u64>>> {
+    let sql = format!(
+        "SELECT mt.mention_id, mt.user_id FROM mention_targets mt \
+         WHERE mt.mention_id = ANY($1) AND {TARGET_TRACKED_SQL}"
+    );
+    query_target_ids_by_mention(client, &sql, mention_ids).await
+}
+
+/// 無視・追跡拒否・ミュートしていない、通知してよい対象者一覧を返す。
+async fn fetch_notifiable_target_ids_by_mention(
+    client: &tokio_postgres::Client,
+    mention_ids: &[i64],
+) -> anyhow::Result<HashMap<i64, Vec<u64>>> {
+    let sql = format!(
+        "SELECT mt.mention_id, mt.user_id FROM mention_targets mt \
+         JOIN mentions m ON m.id = mt.mention_id \
+         WHERE mt.mention_id = ANY($1) AND mt.ignored_at IS NULL \
+           AND {TARGET_NOTIFIABLE_SQL}"
+    );
+    query_target_ids_by_mention(client, &sql, mention_ids).await
+}
+
+async fn query_target_ids_by_mention(
+    client: &tokio_postgres::Client,
+    sql: &str,
+    mention_ids: &[i64],
+) -> anyhow::Result<HashMap<i64, Vec<u64>>> {
+    let rows = client
+        .query(sql, &[&mention_ids])
+        .await
+        .context("mention_targets の取得に失敗しました")?;
+
+    let mut map: HashMap<i64, Vec<u64>> = HashMap::new();
+    for row in rows {
+        let mention_id = row.get::<_, i64>("mention_id");
+        let user_id = row.get::<_, i64>("user_id") as u64;
+        map.entry(mention_id).or_default().push(user_id);
+    }
+    Ok(map)
+}
+
+async fn fetch_user_ids_by_mention(
+    client: &tokio_postgres::Client,
+    mention_ids: &[i64],
+    table: &str,
+) -> anyhow::Result<HashMap<i64, Vec<u64>>> {
+    let sql = format!(
+        "SELECT mention_id, user_id FROM {} WHERE mention_id = ANY($1)",
+        table
+    );
+    let rows = client
+        .query(&sql, &[&mention_ids])
+        .await
+        .with_context(|| format!("{} の取得に失敗しました", table))?;
+
+    let mut map: HashMap<i64, Vec<u64>> = HashMap::new();
+    for row in rows {
+        let mention_id = row.get::<_, i64>("mention_id");
+        let user_id = row.get::<_, i64>("user_id") as u64;
+        map.entry(mention_id).or_default().push(user_id);
+    }
+    for users in map.values_mut() {
+        users.sort_unstable();
+        users.dedup();
+    }
+    Ok(map)
+}
+
+/// エスカレーションまでの時間を保持するギルド設定の列。
+fn escalation_settings_column(kind: EscalationKind) -> &'static str {
+    match kind {
+        EscalationKind::AuthorDm => "escalate_author_after_hours",
+        EscalationKind::Reping => "escalate_reping_after_hours",
+    }
+}
+
+/// 期限リマインダーの送信時刻を表す SQL 式と、送信する追加条件（`$1` は現在時刻）。
+fn due_reminder_schedule_sql(kind: DueReminderKind) -> (&'static str, &'static str) {
+    match kind {
+        DueReminderKind::DueSoon => ("(m.due_at - 86400)", "m.due_at > $1"),
+        DueReminderKind::Overdue => ("m.due_at", "TRUE"),
+        DueReminderKind::UrgentUnread => (
+            "(m.created_at + 3600)",
+            "m.priority = 'urgent' \
+             AND NOT EXISTS(SELECT 1 FROM mention_reads \
+                            WHERE mention_id = m.id AND user_id = mt.user_id)",
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `TEST_DATABASE_URL` を指定したときだけ実データベースで確認する。未指定ならスキップする。
+    async fn test_db() -> Option<Db> {
+        let url = std::env::var("TEST_DATABASE_URL").ok()?;
+        let tls = DatabaseTls::new(SslMode::Disable, None).expect("tls config");
+        let db = Db::connect(&url, 2, &tls).await.expect("connect test db");
+        let client = db.pool.get().await.expect("get client");
+        // 並行するテストが同時にテーブルを作らないよう、ロックを取ってからスキーマを適用する。
+        client
+            .batch_execute(&format!(
+                "BEGIN; SELECT pg_advisory_xact_lock(0); {} COMMIT;",
+                include_str!("../../docker/init/01_schema.sql")
+            ))
+            .await
+            .expect("apply schema");
+        Some(db)
+    }
+
+    /// テストごとに他のデータと重ならないギルド ID。
+    fn unique_guild_id() -> i64 {
+        let nanos = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .expect("clock")
+            .as_nanos();
+        (nanos % i64::MAX as u128) as i64
+    }
+
+    #[tokio::test]
+    async fn stats_percentiles_ignore_unread_targets() {
+        let Some(db) = test_db().await else {
+            return;
+        };
+        let guild_id = unique_guild_id();
+        let client = db.pool.get().await.expect("get client");
+        client
+            .batch_execute(&format!(
+                "INSERT INTO mentions (guild_id, channel_id, message_id, author_id, content, created_at) \
+                 VALUES ({guild_id}, 1, {guild_id}, 9, '', 1000), \
+                        ({guild_id}, 1, -{guild_id}, 9, '', 1000); \
+                 INSERT INTO mention_targets (mention_id, user_id) \
+                 SELECT m.id, u.user_id FROM mentions m, (VALUES (1), (2), (3), (4)) AS u(user_id) \
+                 WHERE m.guild_id = {guild_id} AND m.message_id = {guild_id}; \
+                 INSERT INTO mention_targets (mention_id, user_id) \
+                 SELECT id, 1 FROM mentions WHERE guild_id = {guild_id} AND message_id = -{guild_id}; \
+                 INSERT INTO mention_reads (mention_id, user_id, read_at) \
+                 SELECT id, u.user_id, u.read_at FROM mentions, (VALUES (1, 1100), (2, 1300)) AS u(user_id, read_at) \
+                 WHERE guild_id = {guild_id} AND message_id = {guild_id};"
+            ))
+            .await
+            .expect("insert fixtures");
+        let filter = StatsFilter {
+            guild_id: guild_id as u64,
+            since_unix: 0,
+            until_unix: 2000,
+            channel_id: None,
+            target_user_ids: None,
+        };
+
+        let stats = db.fetch_mention_stats(&filter).await;
+        let responders = db.fetch_slowest_responders(&filter, 10).await;
+        client
+            .execute("DELETE FROM mentions WHERE guild_id = $1", &[&guild_id])
+            .await
+            .expect("cleanup");
+
+        let stats = stats.expect("fetch stats");
+        assert_eq!(stats.target_count, 5);
+        assert_eq!(stats.read_count, 2);
+        assert_eq!(stats.never_read_count, 3);
+        assert_eq!(stats.read_median_secs, Some(200.0));
+        assert_eq!(stats.done_median_secs, None);
+        assert_eq!(stats.done_p90_secs, None);
+
+        let responders = responders.expect("fetch responders");
+        let medians: Vec<(u64, f64)> = responders
+            .iter()
+            .map(|r| (r.user_id, r.read_median_secs))
+            .collect();
+        assert_eq!(medians, vec![(2, 300.0), (1, 100.0)]);
+    }
+
+    #[tokio::test]
+    async fn stats_count_imported_reactions_without_their_times() {
+        let Some(db) = test_db().await else {
+            return;
+        };
+        let guild_id = unique_guild_id();
+        let client = db.pool.get().await.expect("get client");
+        client
+            .batch_execute(&format!(
+                "INSERT INTO mentions (guild_id, channel_id, message_id, author_id, content, created_at) \
+                 VALUES ({guild_id}, 1, {guild_id}, 9, '', 1000); \
+                 INSERT INTO mention_targets (mention_id, user_id) \
+                 SELECT m.id, u.user_id FROM mentions m, (VALUES (1), (2)) AS u(user_id) \
+                 WHERE m.guild_id = {guild_id}; \
+                 INSERT INTO mention_reads (mention_id, user_id, read_at, imported) \
+                 SELECT id, u.user_id, u.read_at, u.imported FROM mentions, \
+                        (VALUES (1, 1100, FALSE), (2, 90000, TRUE)) AS u(user_id, read_at, imported) \
+                 WHERE guild_id = {guild_id}; \
+                 INSERT INTO mention_dones (mention_id, user_id, done_at, imported) \
+                 SELECT id, 2, 90000, TRUE FROM mentions WHERE guild_id = {guild_id};"
+            ))
+            .await
+            .expect("insert fixtures");
+        let filter = StatsFilter {
+            guild_id: guild_id as u64,
+            since_unix: 0,
+            until_unix: 2000,
+            channel_id: None,
+            target_user_ids: None,
+        };
+
+        let stats = db.fetch_mention_stats(&filter).await;
+        client
+            .execute("DELETE FROM mentions WHERE guild_id = $1", &[&guild_id])
+            .await
+            .expect("cleanup");
+
+        let stats = stats.expect("fetch stats");
+        assert_eq!(stats.read_count, 2);
+        assert_eq!(stats.done_count, 1);
+        assert_eq!(stats.never_read_count, 0);
+        assert_eq!(stats.read_median_secs, Some(100.0));
+        assert_eq!(stats.done_median_secs, None);
     }
-    Ok(map)
 }
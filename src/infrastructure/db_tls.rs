@@ -0,0 +1,558 @@
+use std::future::Future;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use anyhow::{bail, Context as _};
+use rustls::client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier};
+use rustls::client::WebPkiServerVerifier;
+use rustls::crypto::{verify_tls12_signature, verify_tls13_signature, CryptoProvider};
+use rustls::pki_types::pem::PemObject;
+use rustls::pki_types::{CertificateDer, ServerName, UnixTime};
+use rustls::{
+    CertificateError, ClientConfig, DigitallySignedStruct, RootCertStore, SignatureScheme,
+};
+use tokio::io::{AsyncRead, AsyncWrite, ReadBuf};
+use tokio_postgres::tls::{ChannelBinding, MakeTlsConnect, TlsConnect, TlsStream};
+use tokio_rustls::TlsConnector;
+
+/// PostgreSQL への接続で TLS をどこまで求めるか。libpq の `sslmode` と同じ意味で扱う。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SslMode {
+    /// TLS を使わない。
+    Disable,
+    /// サーバーが対応していれば TLS を使う。証明書は検証しない。
+    Prefer,
+    /// TLS を必須にする。CA を指定した場合だけ証明書を検証する。
+    Require,
+    /// TLS を必須にし、証明書が信頼できる CA のものか検証する。
+    VerifyCa,
+    /// `VerifyCa` に加えて、証明書が接続先のホスト名のものか検証する。
+    VerifyFull,
+}
+
+impl SslMode {
+    /// `allow` は libpq と異なり `prefer` と同じく TLS を先に試す。
+    pub fn parse(value: &str) -> anyhow::Result<Self> {
+        match value.trim() {
+            "disable" => Ok(Self::Disable),
+            "allow" | "prefer" => Ok(Self::Prefer),
+            "require" => Ok(Self::Require),
+            "verify-ca" => Ok(Self::VerifyCa),
+            "verify-full" => Ok(Self::VerifyFull),
+            other => bail!(
+                "sslmode must be one of disable, allow, prefer, require, verify-ca, verify-full: {other}"
+            ),
+        }
+    }
+
+    fn to_postgres(self) -> tokio_postgres::config::SslMode {
+        match self {
+            Self::Disable => tokio_postgres::config::SslMode::Disable,
+            Self::Prefer => tokio_postgres::config::SslMode::Prefer,
+            Self::Require | Self::VerifyCa | Self::VerifyFull => {
+                tokio_postgres::config::SslMode::Require
+            }
+        }
+    }
+}
+
+/// PostgreSQL 接続の TLS 設定。CA 証明書は読み込み済みのものを持つ。
+#[derive(Debug, Clone)]
+pub struct DatabaseTls {
+    pub mode: SslMode,
+    /// 信頼する CA。空なら webpki-roots の公開 CA を使う。
+    root_certs: Vec<CertificateDer<'static>>,
+}
+
+impl Default for DatabaseTls {
+    fn default() -> Self {
+        Self {
+            mode: SslMode::Prefer,
+            root_certs: Vec::new(),
+        }
+    }
+}
+
+impl DatabaseTls {
+    /// `root_cert` は PEM 形式の CA 証明書ファイル。
+    pub fn new(mode: SslMode, root_cert: Option<&Path>) -> anyhow::Result<Self> {
+        let root_certs = match root_cert {
+            Some(path) => read_root_certs(path)?,
+            None => Vec::new(),
+        };
+        Ok(Self { mode, root_certs })
+    }
+
+    /// `tokio_postgres::Config` に sslmode を反映する。
+    pub fn apply(&self, config: &mut tokio_postgres::Config) {
+        config.ssl_mode(self.mode.to_postgres());
+    }
+
+    /// 設定に従って証明書を検証するコネクタを作る。
+    pub fn connector(&self) -> anyhow::Result<RustlsConnect> {
+        let provider = Arc::new(rustls::crypto::ring::default_provider());
+        let verifier: Arc<dyn ServerCertVerifier> = match self.mode {
+            SslMode::Disable | SslMode::Prefer => Arc::new(NoVerification(provider.clone())),
+            // libpq と同じく、require でも CA を指定すれば verify-ca として扱う。
+            SslMode::Require if self.root_certs.is_empty() => {
+                Arc::new(NoVerification(provider.clone()))
+            }
+            SslMode::Require | SslMode::VerifyCa => {
+                Arc::new(SkipHostname(self.webpki_verifier(provider.clone())?))
+            }
+            SslMode::VerifyFull => self.webpki_verifier(provider.clone())?,
+        };
+        let config = ClientConfig::builder_with_provider(provider)
+            .with_safe_default_protocol_versions()
+            .context("failed to configure TLS for PostgreSQL")?
+            .dangerous()
+            .with_custom_certificate_verifier(verifier)
+            .with_no_client_auth();
+        Ok(RustlsConnect {
+            connector: TlsConnector::from(Arc::new(config)),
+        })
+    }
+
+    fn webpki_verifier(
+        &self,
+        provider: Arc<CryptoProvider>,
+    ) -> anyhow::Result<Arc<WebPkiServerVerifier>> {
+        let mut roots = RootCertStore::empty();
+        if self.root_certs.is_empty() {
+            roots.extend(webpki_roots::TLS_SERVER_ROOTS.iter().cloned());
+        } else {
+            for cert in &self.root_certs {
+                roots
+                    .add(cert.clone())
+                    .context("invalid CA certificate for PostgreSQL")?;
+            }
+        }
+        WebPkiServerVerifier::builder_with_provider(Arc::new(roots), provider)
+            .build()
+            .context("failed to build PostgreSQL certificate verifier")
+    }
+}
+
+fn read_root_certs(path: &Path) -> anyhow::Result<Vec<CertificateDer<'static>>> {
+    let pem = std::fs::read(path)
+        .with_context(|| format!("failed to read CA certificate {}", path.display()))?;
+    let certs = CertificateDer::pem_slice_iter(&pem)
+        .collect::<Result<Vec<_>, _>>()
+        .with_context(|| format!("invalid CA certificate {}", path.display()))?;
+    if certs.is_empty() {
+        bail!("no certificate found in {}", path.display());
+    }
+    Ok(certs)
+}
+
+/// 接続文字列に含まれる TLS の指定。tokio-postgres が解釈できないため取り除いて扱う。
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct UrlTlsParams {
+    pub sslmode: Option<String>,
+    pub sslrootcert: Option<PathBuf>,
+}
+
+/// 接続文字列から `sslmode` と `sslrootcert` を取り除き、残りの接続文字列と一緒に返す。
+/// URL 形式 (`postgres://...?sslmode=require`) と key=value 形式の両方に対応する。
+pub fn split_tls_params(database_url: &str) -> (String, UrlTlsParams) {
+    let mut params = UrlTlsParams::default();
+    let mut take = |key: &str, value: String| match key {
+        "sslmode" => {
+            params.sslmode = Some(value);
+            true
+        }
+        "sslrootcert" => {
+            params.sslrootcert = Some(PathBuf::from(value));
+            true
+        }
+        _ => false,
+    };
+
+    if database_url.starts_with("postgres://") || database_url.starts_with("postgresql://") {
+        let Some((base, query)) = database_url.split_once('?') else {
+            return (database_url.to_string(), params);
+        };
+        let rest = query
+            .split('&')
+            .filter(|pair| {
+                let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+                !take(key, percent_decode(value))
+            })
+            .collect::<Vec<_>>();
+        let url = if rest.is_empty() {
+            base.to_string()
+        } else {
+            format!("{base}?{}", rest.join("&"))
+        };
+        return (url, params);
+    }
+
+    let rest = split_key_values(database_url)
+        .into_iter()
+        .filter(|pair| {
+            let Some((key, value)) = pair.split_once('=') else {
+                return true;
+            };
+            !take(key.trim(), unquote(value.trim()))
+        })
+        .collect::<Vec<_>>();
+    (rest.join(" "), params)
+}
+
+/// key=value 形式を空白で分ける。シングルクォートで囲んだ値の中の空白は区切りにしない。
+fn split_key_values(text: &str) -> Vec<String> {
+    let mut pairs = Vec::new();
+    let mut current = String::new();
+    let mut quoted = false;
+    let mut escaped = false;
+    for c in text.chars() {
+        match c {
+            _ if escaped => {
+                current.push(c);
+                escaped = false;
+            }
+            '\\' if quoted => {
+                current.push(c);
+                escaped = true;
+            }
+            '\'' => {
+                current.push(c);
+                quoted = !quoted;
+            }
+            c if c.is_whitespace() && !quoted => {
+                if !current.is_empty() {
+                    pairs.push(std::mem::take(&mut current));
+                }
+            }
+            c => current.push(c),
+        }
+    }
+    if !current.is_empty() {
+        pairs.push(current);
+    }
+    pairs
+}
+
+fn unquote(value: &str) -> String {
+    let Some(inner) = value
+        .strip_prefix('\'')
+        .and_then(|value| value.strip_suffix('\''))
+    else {
+        return value.to_string();
+    };
+    let mut out = String::new();
+    let mut chars = inner.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => out.extend(chars.next()),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+fn percent_decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let decoded = (bytes[i] == b'%')
+            .then(|| value.get(i + 1..i + 3))
+            .flatten()
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match decoded {
+            Some(byte) => {
+                out.push(byte);
+                i += 3;
+            }
+            None => {
+                out.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+/// 証明書を検証しない。libpq の prefer / require（CA 指定なし）と同じく、暗号化だけを行う。
+#[derive(Debug)]
+struct NoVerification(Arc<CryptoProvider>);
+
+impl ServerCertVerifier for NoVerification {
+    fn verify_server_cert(
+        &self,
+        _end_entity: &CertificateDer<'_>,
+        _intermediates: &[CertificateDer<'_>],
+        _server_name: &ServerName<'_>,
+        _ocsp_response: &[u8],
+        _now: UnixTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        Ok(ServerCertVerified::assertion())
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        verify_tls12_signature(
+            message,
+            cert,
+            dss,
+            &self.0.signature_verification_algorithms,
+        )
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        verify_tls13_signature(
+            message,
+            cert,
+            dss,
+            &self.0.signature_verification_algorithms,
+        )
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.0.signature_verification_algorithms.supported_schemes()
+    }
+}
+
+/// 証明書の発行元は検証し、ホスト名の不一致だけを許す（verify-ca）。
+#[derive(Debug)]
+struct SkipHostname(Arc<WebPkiServerVerifier>);
+
+impl ServerCertVerifier for SkipHostname {
+    fn verify_server_cert(
+        &self,
+        end_entity: &CertificateDer<'_>,
+        intermediates: &[CertificateDer<'_>],
+        server_name: &ServerName<'_>,
+        ocsp_response: &[u8],
+        now: UnixTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        match self
+            .0
+            .verify_server_cert(end_entity, intermediates, server_name, ocsp_response, now)
+        {
+            Err(rustls::Error::InvalidCertificate(
+                CertificateError::NotValidForName | CertificateError::NotValidForNameContext { .. },
+            )) => Ok(ServerCertVerified::assertion()),
+            result => result,
+        }
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        self.0.verify_tls12_signature(message, cert, dss)
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        self.0.verify_tls13_signature(message, cert, dss)
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.0.supported_verify_schemes()
+    }
+}
+
+/// tokio-postgres から rustls で TLS 接続するためのコネクタ。
+#[derive(Clone)]
+pub struct RustlsConnect {
+    connector: TlsConnector,
+}
+
+impl<S> MakeTlsConnect<S> for RustlsConnect
+where
+    S: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+{
+    type Stream = RustlsStream<S>;
+    type TlsConnect = RustlsConnectFuture;
+    type Error = rustls::pki_types::InvalidDnsNameError;
+
+    /// Unix ドメインソケットでは `domain` が空になる。サーバーは TLS を断るため通常は
+    /// ハンドシェイクまで進まないので、ホスト名の誤りは実際に TLS で接続するときに返す。
+    fn make_tls_connect(&mut self, domain: &str) -> Result<Self::TlsConnect, Self::Error> {
+        let server_name = ServerName::try_from(domain).map(|name| name.to_owned());
+        Ok(RustlsConnectFuture {
+            connector: self.connector.clone(),
+            server_name,
+        })
+    }
+}
+
+pub struct RustlsConnectFuture {
+    connector: TlsConnector,
+    server_name: Result<ServerName<'static>, rustls::pki_types::InvalidDnsNameError>,
+}
+
+impl<S> TlsConnect<S> for RustlsConnectFuture
+where
+    S: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+{
+    type Stream = RustlsStream<S>;
+    type Error = io::Error;
+    type Future = Pin<Box<dyn Future<Output = io::Result<RustlsStream<S>>> + Send>>;
+
+    fn connect(self, stream: S) -> Self::Future {
+        Box::pin(async move {
+            let server_name = self
+                .server_name
+                .map_err(|err| io::Error::new(io::ErrorKind::InvalidInput, err))?;
+            let stream = self.connector.connect(server_name, stream).await?;
+            Ok(RustlsStream(stream))
+        })
+    }
+}
+
+/// TLS で包んだ PostgreSQL との接続。
+pub struct RustlsStream<S>(tokio_rustls::client::TlsStream<S>);
+
+impl<S> TlsStream for RustlsStream<S>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    /// チャネルバインディングは使わない（SCRAM はバインディングなしで認証する）。
+    fn channel_binding(&self) -> ChannelBinding {
+        ChannelBinding::none()
+    }
+}
+
+impl<S> AsyncRead for RustlsStream<S>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.0).poll_read(cx, buf)
+    }
+}
+
+impl<S> AsyncWrite for RustlsStream<S>
+where
+    S: AsyncRead + AsyncWrite + Unpin,
+{
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.0).poll_write(cx, buf)
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.0).poll_flush(cx)
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.0).poll_shutdown(cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_ssl_modes() {
+        assert_eq!(SslMode::parse("verify-full").unwrap(), SslMode::VerifyFull);
+        assert_eq!(SslMode::parse("allow").unwrap(), SslMode::Prefer);
+        assert!(SslMode::parse("verify_full").is_err());
+    }
+
+    #[test]
+    fn splits_tls_params_from_url() {
+        let (url, params) = split_tls_params(
+            "postgres://kiduku@db.example.com/kiduku?sslmode=verify-full&application_name=kiduku&sslrootcert=%2Fetc%2Fssl%2Fca.pem",
+        );
+        assert_eq!(
+            url,
+            "postgres://kiduku@db.example.com/kiduku?application_name=kiduku"
+        );
+        assert_eq!(params.sslmode.as_deref(), Some("verify-full"));
+        assert_eq!(params.sslrootcert, Some(PathBuf::from("/etc/ssl/ca.pem")));
+
+        let (url, params) = split_tls_params("postgres://kiduku@localhost/kiduku?sslmode=require");
+        assert_eq!(url, "postgres://kiduku@localhost/kiduku");
+        assert_eq!(params.sslmode.as_deref(), Some("require"));
+
+        let (url, params) = split_tls_params("postgres://kiduku@localhost/kiduku");
+        assert_eq!(url, "postgres://kiduku@localhost/kiduku");
+        assert_eq!(params, UrlTlsParams::default());
+    }
+
+    #[test]
+    fn splits_tls_params_from_key_values() {
+        let (url, params) = split_tls_params(
+            "host=db.example.com password='a b\\'c' sslmode=verify-ca sslrootcert='/etc/ssl/my ca.pem'",
+        );
+        assert_eq!(url, "host=db.example.com password='a b\\'c'");
+        assert_eq!(params.sslmode.as_deref(), Some("verify-ca"));
+        assert_eq!(
+            params.sslrootcert,
+            Some(PathBuf::from("/etc/ssl/my ca.pem"))
+        );
+        assert!(url.parse::<tokio_postgres::Config>().is_ok());
+    }
+
+    #[test]
+    fn rejects_missing_or_empty_ca_file() {
+        let missing = Path::new("/nonexistent/kiduku-ca.pem");
+        assert!(DatabaseTls::new(SslMode::VerifyFull, Some(missing)).is_err());
+
+        let empty =
+            std::env::temp_dir().join(format!("kiduku-empty-ca-{}.pem", std::process::id()));
+        std::fs::write(&empty, "").unwrap();
+        let result = DatabaseTls::new(SslMode::VerifyFull, Some(&empty));
+        std::fs::remove_file(&empty).ok();
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn builds_connector_for_every_mode() {
+        for mode in [
+            SslMode::Disable,
+            SslMode::Prefer,
+            SslMode::Require,
+            SslMode::VerifyCa,
+            SslMode::VerifyFull,
+        ] {
+            let tls = DatabaseTls::new(mode, None).unwrap();
+            assert!(tls.connector().is_ok(), "{mode:?}");
+        }
+    }
+
+    #[tokio::test]
+    async fn defers_invalid_host_name_until_tls_handshake() {
+        let mut connect = DatabaseTls::default().connector().expect("connector");
+        let future = MakeTlsConnect::<tokio::io::DuplexStream>::make_tls_connect(&mut connect, "")
+            .expect("unix socket hosts must not fail before negotiating TLS");
+
+        let (stream, _peer) = tokio::io::duplex(64);
+        let err = match future.connect(stream).await {
+            Ok(_) => panic!("expected handshake error"),
+            Err(err) => err,
+        };
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    }
+}
@@ -0,0 +1,52 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use ring::rand::{SecureRandom, SystemRandom};
+use tracing_subscriber::EnvFilter;
+
+use crate::infrastructure::config::LogFormat;
+
+/// 乱数が取れなかった場合に request id を一意にするための連番。
+static FALLBACK_SEQUENCE: AtomicU64 = AtomicU64::new(0);
+
+/// ログの出力先を初期化する。JSON ではスパンのフィールド（guild_id や request_id など）も出力する。
+pub fn init(env_filter: EnvFilter, format: LogFormat, dev_mode: bool) {
+    let builder = tracing_subscriber::fmt()
+        .with_env_filter(env_filter)
+        .with_target(dev_mode)
+        .with_file(dev_mode)
+        .with_line_number(dev_mode);
+    match format {
+        LogFormat::Text => builder.init(),
+        LogFormat::Json => builder
+            .json()
+            .flatten_event(true)
+            .with_current_span(false)
+            .with_span_list(true)
+            .init(),
+    }
+}
+
+/// イベントやバッチの実行ごとに付ける ID。同じ処理から出たログを結び付けるために使う。
+pub fn new_request_id() -> String {
+    let mut bytes = [0u8; 8];
+    let id = match SystemRandom::new().fill(&mut bytes) {
+        Ok(()) => u64::from_be_bytes(bytes),
+        Err(_) => FALLBACK_SEQUENCE.fetch_add(1, Ordering::Relaxed),
+    };
+    format!("{id:016x}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn request_ids_are_fixed_width_hex_and_distinct() {
+        let first = new_request_id();
+        let second = new_request_id();
+
+        assert_eq!(first.len(), 16);
+        assert!(first.chars().all(|c| c.is_ascii_hexdigit()));
+        assert_ne!(first, second);
+    }
+}
@@ -0,0 +1,322 @@
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Mutex;
+
+/// プロセス全体で共有するメトリクス。`/metrics` で Prometheus のテキスト形式で出力する。
+pub static METRICS: Metrics = Metrics::new();
+
+/// 所要時間の合計と回数。Prometheus の summary として出力する。
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+struct DurationSummary {
+    count: u64,
+    sum_secs: f64,
+}
+
+impl DurationSummary {
+    fn observe(&mut self, secs: f64) {
+        self.count += 1;
+        self.sum_secs += secs;
+    }
+}
+
+/// DB コネクションプールの使用状況。出力時に `Db` から取得する。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PoolUsage {
+    pub max_size: usize,
+    pub size: usize,
+    pub available: usize,
+    pub waiting: usize,
+}
+
+#[derive(Debug)]
+pub struct Metrics {
+    gateway_connected: AtomicBool,
+    mentions_tracked: AtomicU64,
+    reads_recorded: AtomicU64,
+    dones_recorded: AtomicU64,
+    dms_sent: AtomicU64,
+    dms_failed: AtomicU64,
+    discord_http_errors: Mutex<BTreeMap<u16, u64>>,
+    batch_durations: Mutex<BTreeMap<&'static str, DurationSummary>>,
+    command_durations: Mutex<BTreeMap<String, DurationSummary>>,
+}
+
+impl Metrics {
+    const fn new() -> Self {
+        Self {
+            gateway_connected: AtomicBool::new(false),
+            mentions_tracked: AtomicU64::new(0),
+            reads_recorded: AtomicU64::new(0),
+            dones_recorded: AtomicU64::new(0),
+            dms_sent: AtomicU64::new(0),
+            dms_failed: AtomicU64::new(0),
+            discord_http_errors: Mutex::new(BTreeMap::new()),
+            batch_durations: Mutex::new(BTreeMap::new()),
+            command_durations: Mutex::new(BTreeMap::new()),
+        }
+    }
+
+    pub fn set_gateway_connected(&self, connected: bool) {
+        self.gateway_connected.store(connected, Ordering::Relaxed);
+    }
+
+    pub fn gateway_connected(&self) -> bool {
+        self.gateway_connected.load(Ordering::Relaxed)
+    }
+
+    pub fn inc_mentions_tracked(&self) {
+        self.mentions_tracked.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn inc_reads_recorded(&self) {
+        self.reads_recorded.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn inc_dones_recorded(&self) {
+        self.dones_recorded.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// DM の送信結果を数える。
+    pub fn record_dm(&self, sent: bool) {
+        let counter = if sent {
+            &self.dms_sent
+        } else {
+            &self.dms_failed
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Discord API が返したエラーの HTTP ステータスを数える。
+    pub fn inc_discord_http_error(&self, status: u16) {
+        let mut errors = self
+            .discord_http_errors
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        *errors.entry(status).or_default() += 1;
+    }
+
+    pub fn observe_batch(&self, job: &'static str, secs: f64) {
+        let mut durations = self
+            .batch_durations
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        durations.entry(job).or_default().observe(secs);
+    }
+
+    pub fn observe_command(&self, command: &str, secs: f64) {
+        let mut durations = self
+            .command_durations
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        match durations.get_mut(command) {
+            Some(summary) => summary.observe(secs),
+            None => {
+                let mut summary = DurationSummary::default();
+                summary.observe(secs);
+                durations.insert(command.to_string(), summary);
+            }
+        }
+    }
+
+    /// Prometheus のテキスト形式で出力する。プールの状況が取れない場合は省く。
+    pub fn render(&self, pool: Option<PoolUsage>) -> String {
+        let mut out = String::new();
+
+        write_metric(
+            &mut out,
+            "kiduku_gateway_connected",
+            "gauge",
+            "Whether the Discord gateway connection is established (1) or not (0).",
+            &[(
+                String::new(),
+                u64::from(self.gateway_connected()).to_string(),
+            )],
+        );
+        write_counter(
+            &mut out,
+            "kiduku_mentions_tracked_total",
+            "Mentions recorded for tracking.",
+            self.mentions_tracked.load(Ordering::Relaxed),
+        );
+        write_metric(
+            &mut out,
+            "kiduku_reactions_recorded_total",
+            "counter",
+            "KIDOKU/DONE reactions recorded as reads or dones.",
+            &[
+                (
+                    label("kind", "read"),
+                    self.reads_recorded.load(Ordering::Relaxed).to_string(),
+                ),
+                (
+                    label("kind", "done"),
+                    self.dones_recorded.load(Ordering::Relaxed).to_string(),
+                ),
+            ],
+        );
+        write_counter(
+            &mut out,
+            "kiduku_dms_sent_total",
+            "Direct messages sent successfully.",
+            self.dms_sent.load(Ordering::Relaxed),
+        );
+        write_counter(
+            &mut out,
+            "kiduku_dms_failed_total",
+            "Direct messages that failed to send.",
+            self.dms_failed.load(Ordering::Relaxed),
+        );
+
+        let errors = self
+            .discord_http_errors
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .iter()
+            .map(|(status, count)| (label("status", &status.to_string()), count.to_string()))
+            .collect::<Vec<_>>();
+        write_metric(
+            &mut out,
+            "kiduku_discord_http_errors_total",
+            "counter",
+            "Discord HTTP API errors by status code.",
+            &errors,
+        );
+
+        let batches = self
+            .batch_durations
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .iter()
+            .map(|(job, summary)| (label("job", job), *summary))
+            .collect::<Vec<_>>();
+        write_summary(
+            &mut out,
+            "kiduku_batch_duration_seconds",
+            "Duration of batch job runs.",
+            &batches,
+        );
+
+        let commands = self
+            .command_durations
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .iter()
+            .map(|(command, summary)| (label("command", command), *summary))
+            .collect::<Vec<_>>();
+        write_summary(
+            &mut out,
+            "kiduku_command_duration_seconds",
+            "Duration of slash command handling.",
+            &commands,
+        );
+
+        if let Some(pool) = pool {
+            write_metric(
+                &mut out,
+                "kiduku_db_pool_connections",
+                "gauge",
+                "Database connection pool usage.",
+                &[
+                    (label("state", "max"), pool.max_size.to_string()),
+                    (label("state", "open"), pool.size.to_string()),
+                    (label("state", "idle"), pool.available.to_string()),
+                    (label("state", "waiting"), pool.waiting.to_string()),
+                ],
+            );
+        }
+        out
+    }
+}
+
+fn write_counter(out: &mut String, name: &str, help: &str, value: u64) {
+    write_metric(
+        out,
+        name,
+        "counter",
+        help,
+        &[(String::new(), value.to_string())],
+    );
+}
+
+fn write_metric(
+    out: &mut String,
+    name: &str,
+    kind: &str,
+    help: &str,
+    samples: &[(String, String)],
+) {
+    let _ = writeln!(out, "# HELP {name} {help}");
+    let _ = writeln!(out, "# TYPE {name} {kind}");
+    for (labels, value) in samples {
+        let _ = writeln!(out, "{name}{labels} {value}");
+    }
+}
+
+fn write_summary(out: &mut String, name: &str, help: &str, samples: &[(String, DurationSummary)]) {
+    let _ = writeln!(out, "# HELP {name} {help}");
+    let _ = writeln!(out, "# TYPE {name} summary");
+    for (labels, summary) in samples {
+        let _ = writeln!(out, "{name}_sum{labels} {}", summary.sum_secs);
+        let _ = writeln!(out, "{name}_count{labels} {}", summary.count);
+    }
+}
+
+fn label(key: &str, value: &str) -> String {
+    let escaped = value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n");
+    format!("{{{key}=\"{escaped}\"}}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_counters_and_summaries() {
+        let metrics = Metrics::new();
+        metrics.set_gateway_connected(true);
+        metrics.inc_mentions_tracked();
+        metrics.inc_reads_recorded();
+        metrics.record_dm(true);
+        metrics.record_dm(false);
+        metrics.inc_discord_http_error(403);
+        metrics.inc_discord_http_error(403);
+        metrics.observe_batch("weekly", 1.5);
+        metrics.observe_batch("weekly", 0.5);
+        metrics.observe_command("通知一覧", 0.25);
+
+        let out = metrics.render(Some(PoolUsage {
+            max_size: 16,
+            size: 2,
+            available: 1,
+            waiting: 0,
+        }));
+
+        assert!(out.contains("kiduku_gateway_connected 1\n"));
+        assert!(out.contains("kiduku_mentions_tracked_total 1\n"));
+        assert!(out.contains("kiduku_reactions_recorded_total{kind=\"read\"} 1\n"));
+        assert!(out.contains("kiduku_reactions_recorded_total{kind=\"done\"} 0\n"));
+        assert!(out.contains("kiduku_dms_sent_total 1\n"));
+        assert!(out.contains("kiduku_dms_failed_total 1\n"));
+        assert!(out.contains("kiduku_discord_http_errors_total{status=\"403\"} 2\n"));
+        assert!(out.contains("kiduku_batch_duration_seconds_sum{job=\"weekly\"} 2\n"));
+        assert!(out.contains("kiduku_batch_duration_seconds_count{job=\"weekly\"} 2\n"));
+        assert!(out.contains("kiduku_command_duration_seconds_count{command=\"通知一覧\"} 1\n"));
+        assert!(out.contains("kiduku_db_pool_connections{state=\"max\"} 16\n"));
+    }
+
+    #[test]
+    fn omits_pool_usage_when_unavailable() {
+        let out = Metrics::new().render(None);
+        assert!(!out.contains("kiduku_db_pool_connections"));
+        assert!(out.contains("# TYPE kiduku_batch_duration_seconds summary"));
+    }
+
+    #[test]
+    fn escapes_label_values() {
+        assert_eq!(label("command", "a\"b\\c"), "{command=\"a\\\"b\\\\c\"}");
+    }
+}
@@ -1,2 +1,8 @@
 pub mod config;
+pub mod content_cipher;
 pub mod db;
+pub mod db_tls;
+pub mod logging;
+pub mod metrics;
+pub mod monitoring;
+pub mod shutdown;
@@ -0,0 +1,177 @@
+use std::time::Duration;
+
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+use crate::infrastructure::db::Db;
+use crate::infrastructure::metrics::METRICS;
+
+/// リクエストを読み切るまでの待ち時間。応答しないクライアントで接続を占有させない。
+const READ_TIMEOUT: Duration = Duration::from_secs(5);
+/// 読み込むリクエストヘッダーの上限。
+const MAX_REQUEST_BYTES: usize = 8 * 1024;
+
+/// `/healthz` と `/metrics` を返す HTTP サーバー。監視用途のため最小限の HTTP/1.1 だけを扱う。
+pub async fn serve(listener: TcpListener, db: Db) {
+    loop {
+        let (stream, peer) = match listener.accept().await {
+            Ok(accepted) => accepted,
+            Err(err) => {
+                tracing::warn!("failed to accept monitoring connection: {:?}", err);
+                continue;
+            }
+        };
+        let db = db.clone();
+        tokio::spawn(async move {
+            if let Err(err) = handle_connection(stream, &db).await {
+                tracing::debug!("monitoring request from {} failed: {:?}", peer, err);
+            }
+        });
+    }
+}
+
+async fn handle_connection(mut stream: TcpStream, db: &Db) -> anyhow::Result<()> {
+    let head = tokio::time::timeout(READ_TIMEOUT, read_request_head(&mut stream)).await??;
+    let response = match parse_request_line(&head) {
+        Some(("GET", "/healthz")) => healthz(db).await,
+        Some(("GET", "/metrics")) => Response::ok(
+            "text/plain; version=0.0.4; charset=utf-8",
+            METRICS.render(Some(db.pool_usage())),
+        ),
+        Some(("GET", _)) => Response::text(404, "Not Found", "not found\n".to_string()),
+        Some(_) => Response::text(
+            405,
+            "Method Not Allowed",
+            "method not allowed\n".to_string(),
+        ),
+        None => Response::text(400, "Bad Request", "bad request\n".to_string()),
+    };
+    stream.write_all(&response.into_bytes()).await?;
+    stream.shutdown().await?;
+    Ok(())
+}
+
+/// Gateway に接続していて、DB に問い合わせできれば正常とみなす。
+async fn healthz(db: &Db) -> Response {
+    let gateway = METRICS.gateway_connected();
+    let database = match db.ping().await {
+        Ok(()) => true,
+        Err(err) => {
+            tracing::warn!("health check: database is unreachable: {:?}", err);
+            false
+        }
+    };
+    let body = format!(
+        "gateway: {}\ndatabase: {}\n",
+        status_word(gateway),
+        status_word(database)
+    );
+    if gateway && database {
+        Response::text(200, "OK", body)
+    } else {
+        Response::text(503, "Service Unavailable", body)
+    }
+}
+
+fn status_word(ok: bool) -> &'static str {
+    if ok {
+        "ok"
+    } else {
+        "down"
+    }
+}
+
+async fn read_request_head(stream: &mut TcpStream) -> anyhow::Result<String> {
+    let mut buf = Vec::new();
+    let mut chunk = [0u8; 1024];
+    while !buf.windows(4).any(|window| window == b"\r\n\r\n") {
+        let read = stream.read(&mut chunk).await?;
+        if read == 0 {
+            break;
+        }
+        buf.extend_from_slice(&chunk[..read]);
+        if buf.len() > MAX_REQUEST_BYTES {
+            anyhow::bail!("request header too large");
+        }
+    }
+    Ok(String::from_utf8_lossy(&buf).into_owned())
+}
+
+/// リクエスト行からメソッドとパスを取り出す。クエリ文字列は無視する。
+fn parse_request_line(head: &str) -> Option<(&str, &str)> {
+    let mut parts = head.lines().next()?.split_whitespace();
+    let method = parts.next()?;
+    let target = parts.next()?;
+    parts
+        .next()
+        .filter(|version| version.starts_with("HTTP/"))?;
+    let path = target.split('?').next().unwrap_or(target);
+    Some((method, path))
+}
+
+struct Response {
+    status: u16,
+    reason: &'static str,
+    content_type: &'static str,
+    body: String,
+}
+
+impl Response {
+    fn ok(content_type: &'static str, body: String) -> Self {
+        Self {
+            status: 200,
+            reason: "OK",
+            content_type,
+            body,
+        }
+    }
+
+    fn text(status: u16, reason: &'static str, body: String) -> Self {
+        Self {
+            status,
+            reason,
+            content_type: "text/plain; charset=utf-8",
+            body,
+        }
+    }
+
+    fn into_bytes(self) -> Vec<u8> {
+        format!(
+            "HTTP/1.1 {} {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            self.status,
+            self.reason,
+            self.content_type,
+            self.body.len(),
+            self.body
+        )
+        .into_bytes()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_method_and_path() {
+        assert_eq!(
+            parse_request_line("GET /metrics?x=1 HTTP/1.1\r\nHost: a\r\n\r\n"),
+            Some(("GET", "/metrics"))
+        );
+        assert_eq!(
+            parse_request_line("HEAD /healthz HTTP/1.0\r\n\r\n"),
+            Some(("HEAD", "/healthz"))
+        );
+        assert_eq!(parse_request_line("garbage\r\n\r\n"), None);
+        assert_eq!(parse_request_line(""), None);
+    }
+
+    #[test]
+    fn serializes_response_with_length() {
+        let bytes = Response::text(503, "Service Unavailable", "down\n".to_string()).into_bytes();
+        let text = String::from_utf8(bytes).unwrap();
+        assert!(text.starts_with("HTTP/1.1 503 Service Unavailable\r\n"));
+        assert!(text.contains("Content-Length: 5\r\n"));
+        assert!(text.ends_with("\r\n\r\ndown\n"));
+    }
+}
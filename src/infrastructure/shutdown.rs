@@ -0,0 +1,125 @@
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::sync::Notify;
+
+/// 停止の開始と、処理中のイベント・バッチを数える。
+/// 停止を始めたら新しい処理を受け付けず、処理中のものが終わるのを待てるようにする。
+#[derive(Debug, Clone, Default)]
+pub struct Shutdown {
+    inner: Arc<Inner>,
+}
+
+#[derive(Debug, Default)]
+struct Inner {
+    stopping: AtomicBool,
+    in_flight: AtomicUsize,
+    idle: Notify,
+}
+
+/// 処理中であることを示す。破棄すると処理中の数から外れる。
+#[derive(Debug)]
+pub struct InFlightGuard {
+    inner: Arc<Inner>,
+}
+
+impl Shutdown {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 停止を始める。以降の `track` は `None` を返す。
+    pub fn begin(&self) {
+        self.inner.stopping.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_stopping(&self) -> bool {
+        self.inner.stopping.load(Ordering::SeqCst)
+    }
+
+    /// 処理を始める。停止中なら `None` を返すので、呼び出し側は処理を始めない。
+    pub fn track(&self) -> Option<InFlightGuard> {
+        self.inner.in_flight.fetch_add(1, Ordering::SeqCst);
+        let guard = InFlightGuard {
+            inner: self.inner.clone(),
+        };
+        // 停止の開始と同時に始まった処理は、待つ側から見えるよう数えてから取り消す。
+        if self.is_stopping() {
+            return None;
+        }
+        Some(guard)
+    }
+
+    pub fn in_flight(&self) -> usize {
+        self.inner.in_flight.load(Ordering::SeqCst)
+    }
+
+    /// 処理中のものがすべて終わるまで待つ。`timeout` までに終われば `true` を返す。
+    pub async fn wait_idle(&self, timeout: Duration) -> bool {
+        tokio::time::timeout(timeout, async {
+            loop {
+                let notified = self.inner.idle.notified();
+                tokio::pin!(notified);
+                notified.as_mut().enable();
+                if self.in_flight() == 0 {
+                    return;
+                }
+                notified.await;
+            }
+        })
+        .await
+        .is_ok()
+    }
+}
+
+impl Drop for InFlightGuard {
+    fn drop(&mut self) {
+        if self.inner.in_flight.fetch_sub(1, Ordering::SeqCst) == 1 {
+            self.inner.idle.notify_waiters();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_new_work_after_begin() {
+        let shutdown = Shutdown::new();
+        let guard = shutdown.track();
+        assert!(guard.is_some());
+        assert_eq!(shutdown.in_flight(), 1);
+
+        shutdown.begin();
+        assert!(shutdown.track().is_none());
+        assert_eq!(shutdown.in_flight(), 1);
+
+        drop(guard);
+        assert_eq!(shutdown.in_flight(), 0);
+    }
+
+    #[tokio::test]
+    async fn waits_until_in_flight_work_finishes() {
+        let shutdown = Shutdown::new();
+        let guard = shutdown.track().unwrap();
+        shutdown.begin();
+
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            drop(guard);
+        });
+
+        assert!(shutdown.wait_idle(Duration::from_secs(5)).await);
+    }
+
+    #[tokio::test]
+    async fn gives_up_after_timeout() {
+        let shutdown = Shutdown::new();
+        let _guard = shutdown.track().unwrap();
+        shutdown.begin();
+
+        assert!(!shutdown.wait_idle(Duration::from_millis(20)).await);
+    }
+}
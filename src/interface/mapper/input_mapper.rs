@@ -1,7 +1,14 @@
-use serenity::model::prelude::Message;
+use std::collections::HashMap;
+
+use serenity::model::prelude::{
+    ActionRow, ActionRowComponent, CommandDataResolved, ComponentInteraction,
+    ComponentInteractionDataKind, Message, ModalInteraction, RoleId,
+};
 
 use crate::domain::model;
-use crate::usecase::dto::{MessageInput, MessageInputDto};
+use crate::usecase::dto::{
+    MessageInput, MessageInputDto, ModalSubmitInput, SelectMenuInput, SelectedValues,
+};
 
 pub fn from_message(message: &Message) -> MessageInput {
     MessageInput::new(message.content.clone(), message.channel_id.get())
@@ -35,3 +42,121 @@ pub fn to_domain_message(input: &MessageInputDto) -> model::Message {
         is_reply: input.is_reply,
     }
 }
+
+/// セレクトメニューのインタラクションを型付きの入力に変換する。
+/// ボタンなどセレクトメニュー以外のコンポーネントでは `None` を返す。
+pub fn from_component_to_select_menu_input(
+    interaction: &ComponentInteraction,
+) -> Option<SelectMenuInput> {
+    let values = to_selected_values(&interaction.data.kind, &interaction.data.resolved)?;
+    Some(SelectMenuInput {
+        custom_id: interaction.data.custom_id.clone(),
+        user_id: interaction.user.id.get(),
+        values,
+    })
+}
+
+fn to_selected_values(
+    kind: &ComponentInteractionDataKind,
+    resolved: &CommandDataResolved,
+) -> Option<SelectedValues> {
+    let values = match kind {
+        ComponentInteractionDataKind::StringSelect { values } => {
+            SelectedValues::Strings(values.clone())
+        }
+        ComponentInteractionDataKind::UserSelect { values } => {
+            SelectedValues::Users(values.iter().map(|id| id.get()).collect())
+        }
+        ComponentInteractionDataKind::RoleSelect { values } => {
+            SelectedValues::Roles(values.iter().map(|id| id.get()).collect())
+        }
+        ComponentInteractionDataKind::MentionableSelect { values } => {
+            // GenericId だけではユーザーかロールか区別できないため resolved を参照する。
+            let (roles, users): (Vec<u64>, Vec<u64>) = values
+                .iter()
+                .map(|id| id.get())
+                .partition(|id| resolved.roles.contains_key(&RoleId::new(*id)));
+            SelectedValues::Mentionables { users, roles }
+        }
+        ComponentInteractionDataKind::ChannelSelect { values } => {
+            SelectedValues::Channels(values.iter().map(|id| id.get()).collect())
+        }
+        _ => return None,
+    };
+    Some(values)
+}
+
+/// モーダル送信のインタラクションを、入力欄の custom_id で引ける入力に変換する。
+pub fn from_modal_to_modal_submit_input(interaction: &ModalInteraction) -> ModalSubmitInput {
+    ModalSubmitInput {
+        custom_id: interaction.data.custom_id.clone(),
+        user_id: interaction.user.id.get(),
+        values: to_modal_values(&interaction.data.components),
+    }
+}
+
+fn to_modal_values(rows: &[ActionRow]) -> HashMap<String, String> {
+    rows.iter()
+        .flat_map(|row| row.components.iter())
+        .filter_map(|component| match component {
+            ActionRowComponent::InputText(input) => Some((
+                input.custom_id.clone(),
+                input.value.clone().unwrap_or_default(),
+            )),
+            _ => None,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serenity::model::prelude::{ChannelId, GenericId, Role, UserId};
+
+    #[test]
+    fn maps_user_and_channel_selects_to_raw_ids() {
+        let resolved = CommandDataResolved::default();
+
+        let users = ComponentInteractionDataKind::UserSelect {
+            values: vec![UserId::new(1), UserId::new(2)],
+        };
+        assert_eq!(
+            to_selected_values(&users, &resolved),
+            Some(SelectedValues::Users(vec![1, 2]))
+        );
+
+        let channels = ComponentInteractionDataKind::ChannelSelect {
+            values: vec![ChannelId::new(10)],
+        };
+        assert_eq!(
+            to_selected_values(&channels, &resolved),
+            Some(SelectedValues::Channels(vec![10]))
+        );
+    }
+
+    #[test]
+    fn splits_mentionables_by_resolved_roles() {
+        let mut resolved = CommandDataResolved::default();
+        resolved.roles.insert(RoleId::new(20), Role::default());
+        let kind = ComponentInteractionDataKind::MentionableSelect {
+            values: vec![GenericId::new(10), GenericId::new(20), GenericId::new(30)],
+        };
+
+        assert_eq!(
+            to_selected_values(&kind, &resolved),
+            Some(SelectedValues::Mentionables {
+                users: vec![10, 30],
+                roles: vec![20],
+            })
+        );
+    }
+
+    #[test]
+    fn returns_none_for_buttons() {
+        let resolved = CommandDataResolved::default();
+        assert_eq!(
+            to_selected_values(&ComponentInteractionDataKind::Button, &resolved),
+            None
+        );
+    }
+}
@@ -1,27 +1,39 @@
+use std::time::Duration;
+
 use anyhow::Context as _;
 use serenity::prelude::*;
 
-use kiduku::infrastructure::config::{set_dev_mode, AppConfig};
+use kiduku::infrastructure::config::cli::CliArgs;
+use kiduku::infrastructure::config::{resolve_config_path, set_dev_mode, AppConfig};
 use kiduku::infrastructure::db::Db;
+use kiduku::infrastructure::logging;
+use kiduku::infrastructure::monitoring;
+use kiduku::infrastructure::shutdown::Shutdown;
 use kiduku::presentation::build_framework;
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     dotenvy::dotenv().ok();
     set_dev_mode(is_cargo_run());
-    let config = AppConfig::load()?;
+    let args = CliArgs::parse(std::env::args().skip(1))?;
+    let config_path = resolve_config_path(args.config_path);
+    let config = AppConfig::load(config_path.as_deref())?;
+    if args.check_config {
+        return check_config(&config).await;
+    }
     let AppConfig {
         discord_bot_token,
         database_url,
+        database_tls,
+        database_pool_size,
         env_filter,
+        log_format,
         dev_mode,
+        content_cipher,
+        metrics_addr,
+        bot,
     } = config;
-    tracing_subscriber::fmt()
-        .with_env_filter(env_filter)
-        .with_target(dev_mode)
-        .with_file(dev_mode)
-        .with_line_number(dev_mode)
-        .init();
+    logging::init(env_filter, log_format, dev_mode);
 
     let intents = GatewayIntents::GUILDS
         | GatewayIntents::GUILD_MESSAGES
@@ -29,21 +41,93 @@ async fn main() -> anyhow::Result<()> {
         | GatewayIntents::GUILD_MEMBERS
         | GatewayIntents::GUILD_MESSAGE_REACTIONS;
 
-    let db = Db::connect(&database_url).await?;
-    let framework = build_framework(db);
+    let db = Db::connect(&database_url, database_pool_size, &database_tls)
+        .await?
+        .with_content_cipher(content_cipher);
+    if let Some(addr) = metrics_addr {
+        let listener = tokio::net::TcpListener::bind(addr)
+            .await
+            .with_context(|| format!("failed to bind METRICS_ADDR {addr}"))?;
+        tracing::info!("serving /healthz and /metrics on {}", addr);
+        tokio::spawn(monitoring::serve(listener, db.clone()));
+    }
+
+    let shutdown = Shutdown::new();
+    let framework = build_framework(db.clone(), bot, shutdown.clone());
     let mut client = Client::builder(discord_bot_token, intents)
         .framework(framework)
         .await
         .context("failed to create Discord client")?;
 
-    if let Err(err) = client.start().await {
+    let shard_manager = client.shard_manager.clone();
+    tokio::spawn(async move {
+        wait_for_shutdown_signal().await;
+        tracing::info!(
+            "shutdown requested; waiting for {} in-flight tasks",
+            shutdown.in_flight()
+        );
+        shutdown.begin();
+        if !shutdown.wait_idle(SHUTDOWN_TIMEOUT).await {
+            tracing::warn!(
+                "shutdown timed out; {} tasks are still running",
+                shutdown.in_flight()
+            );
+        }
+        shard_manager.shutdown_all().await;
+    });
+
+    let result = client.start().await;
+    db.close();
+    if let Err(err) = result {
         tracing::error!("client error: {:?}", err);
         return Err(err).context("failed to start Discord client");
     }
 
+    tracing::info!("shutdown complete");
+    Ok(())
+}
+
+/// 設定を検証し、DB に接続できることを確かめて終了する。Discord には接続しない。
+async fn check_config(config: &AppConfig) -> anyhow::Result<()> {
+    let db = Db::connect(
+        &config.database_url,
+        config.database_pool_size,
+        &config.database_tls,
+    )
+    .await?;
+    db.ping().await.context("failed to query the database")?;
+    db.close();
+    println!("configuration OK");
     Ok(())
 }
 
+/// 停止時に処理中のイベントやバッチを待つ時間。systemd の TimeoutStopSec より短くする。
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(45);
+
+/// SIGTERM（systemd の停止）か Ctrl+C を待つ。
+async fn wait_for_shutdown_signal() {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+
+        match signal(SignalKind::terminate()) {
+            Ok(mut sigterm) => {
+                tokio::select! {
+                    _ = sigterm.recv() => {}
+                    _ = tokio::signal::ctrl_c() => {}
+                }
+                return;
+            }
+            Err(err) => tracing::warn!("failed to listen for SIGTERM: {:?}", err),
+        }
+    }
+
+    if let Err(err) = tokio::signal::ctrl_c().await {
+        tracing::error!("failed to listen for Ctrl+C: {:?}", err);
+        std::future::pending::<()>().await;
+    }
+}
+
 fn is_cargo_run() -> bool {
     std::env::var("CARGO").is_ok() && std::env::var("CARGO_BIN_NAME").is_ok()
 }
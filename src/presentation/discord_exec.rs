@@ -1,14 +1,25 @@
 use anyhow::{anyhow, bail, Context as _};
 use poise::serenity_prelude as serenity;
 
+use crate::infrastructure::metrics::METRICS;
+use crate::presentation::entry::on_error;
 use crate::presentation::{Context, Error};
 use crate::usecase::dto::{
-    ActionRowPayload, ButtonPayload, ButtonStylePayload, DeferPayload, DiscordExecPlan,
-    DiscordExecStep, EmbedPayload, MessagePayload, ModalPayload, SelectMenuPayload,
-    SelectOptionPayload, TextInputPayload, TextInputStylePayload,
+    ActionRowPayload, AllowedMentionsPayload, ButtonPayload, ButtonStylePayload,
+    ChannelTypePayload, DeferPayload, DiscordExecPlan, DiscordExecStep, EmbedPayload,
+    MessagePayload, ModalPayload, SelectMenuKindPayload, SelectMenuPayload, SelectOptionPayload,
+    TextInputPayload, TextInputStylePayload,
 };
 
 pub async fn execute(ctx: &serenity::Context, plan: DiscordExecPlan) -> Result<(), Error> {
+    let result = execute_steps(ctx, plan).await;
+    if let Err(err) = &result {
+        on_error::record_discord_http_error(err);
+    }
+    result
+}
+
+async fn execute_steps(ctx: &serenity::Context, plan: DiscordExecPlan) -> Result<(), Error> {
     for step in plan.into_steps() {
         match step {
             DiscordExecStep::Send {
@@ -21,6 +32,57 @@ pub async fn execute(ctx: &serenity::Context, plan: DiscordExecPlan) -> Result<(
                     .await
                     .context("failed to send message")?;
             }
+            DiscordExecStep::SendDm { user_id, payload } => {
+                let message = build_message_payload(payload)?;
+                let sent = send_dm(ctx, user_id, message).await;
+                METRICS.record_dm(sent.is_ok());
+                sent?;
+            }
+            DiscordExecStep::Reply {
+                channel_id,
+                message_id,
+                payload,
+            } => {
+                let channel_id = serenity::ChannelId::new(channel_id);
+                let message = build_message_payload(payload)?
+                    .reference_message((channel_id, serenity::MessageId::new(message_id)));
+                channel_id
+                    .send_message(&ctx.http, message)
+                    .await
+                    .context("failed to send reply")?;
+            }
+            DiscordExecStep::SendInThread {
+                channel_id,
+                message_id,
+                thread_name,
+                payload,
+            } => {
+                let channel_id = serenity::ChannelId::new(channel_id);
+                let message_id = serenity::MessageId::new(message_id);
+                let message = build_message_payload(payload)?;
+                match message_thread(ctx, channel_id, message_id, thread_name).await {
+                    Ok(thread_id) => {
+                        thread_id
+                            .send_message(&ctx.http, message)
+                            .await
+                            .context("failed to send thread message")?;
+                    }
+                    Err(err) => {
+                        // スレッド内のメッセージなど、スレッドを作れない場合は元メッセージへ返信する。
+                        tracing::warn!(
+                            "failed to open message thread, replying instead: {:?}",
+                            err
+                        );
+                        channel_id
+                            .send_message(
+                                &ctx.http,
+                                message.reference_message((channel_id, message_id)),
+                            )
+                            .await
+                            .context("failed to send reply")?;
+                    }
+                }
+            }
             _ => {
                 bail!("unsupported step");
             }
@@ -29,6 +91,47 @@ pub async fn execute(ctx: &serenity::Context, plan: DiscordExecPlan) -> Result<(
     Ok(())
 }
 
+/// メッセージのスレッドを返す。まだ無ければ作成する。
+async fn message_thread(
+    ctx: &serenity::Context,
+    channel_id: serenity::ChannelId,
+    message_id: serenity::MessageId,
+    thread_name: String,
+) -> Result<serenity::ChannelId, Error> {
+    let message = channel_id
+        .message(&ctx.http, message_id)
+        .await
+        .context("failed to fetch message for thread")?;
+    if let Some(thread) = message.thread {
+        return Ok(thread.id);
+    }
+    let thread = channel_id
+        .create_thread_from_message(
+            &ctx.http,
+            message_id,
+            serenity::CreateThread::new(thread_name),
+        )
+        .await
+        .context("failed to create thread")?;
+    Ok(thread.id)
+}
+
+async fn send_dm(
+    ctx: &serenity::Context,
+    user_id: u64,
+    message: serenity::CreateMessage,
+) -> Result<(), Error> {
+    let dm_channel = serenity::UserId::new(user_id)
+        .create_dm_channel(&ctx.http)
+        .await
+        .context("failed to create dm channel")?;
+    dm_channel
+        .send_message(&ctx.http, message)
+        .await
+        .context("failed to send dm")?;
+    Ok(())
+}
+
 pub async fn execute_from_interaction(
     ctx: Context<'_>,
     plan: DiscordExecPlan,
@@ -48,6 +151,59 @@ pub async fn execute_from_interaction(
     Ok(())
 }
 
+/// モーダル送信のインタラクションに対してプランを実行する。
+pub async fn execute_from_modal_submit(
+    ctx: &serenity::Context,
+    modal: &serenity::ModalInteraction,
+    plan: DiscordExecPlan,
+) -> Result<(), Error> {
+    for step in plan.into_steps() {
+        match step {
+            DiscordExecStep::Defer(spec) => {
+                if spec.ephemeral {
+                    modal.defer_ephemeral(&ctx.http).await?;
+                } else {
+                    modal.defer(&ctx.http).await?;
+                }
+            }
+            DiscordExecStep::Response(spec) => {
+                let response = build_interaction_payload(spec)?
+                    .to_slash_initial_response(serenity::CreateInteractionResponseMessage::new());
+                modal
+                    .create_response(
+                        &ctx.http,
+                        serenity::CreateInteractionResponse::Message(response),
+                    )
+                    .await
+                    .context("failed to respond to modal submit")?;
+            }
+            DiscordExecStep::EditOriginal(spec) => {
+                if spec.ephemeral == Some(true) {
+                    bail!("ephemeral is not supported for edit_original");
+                }
+                let edit = build_interaction_payload(spec)?
+                    .to_slash_initial_response_edit(serenity::EditInteractionResponse::new());
+                modal
+                    .edit_response(&ctx.http, edit)
+                    .await
+                    .context("failed to edit original modal response")?;
+            }
+            DiscordExecStep::FollowUp(spec) => {
+                let followup = build_interaction_payload(spec)?
+                    .to_slash_followup_response(serenity::CreateInteractionResponseFollowup::new());
+                modal
+                    .create_followup(&ctx.http, followup)
+                    .await
+                    .context("failed to create followup for modal submit")?;
+            }
+            _ => {
+                bail!("unsupported step");
+            }
+        }
+    }
+    Ok(())
+}
+
 async fn execute_defer(ctx: &Context<'_>, spec: DeferPayload) -> Result<(), Error> {
     if spec.ephemeral {
         ctx.defer_ephemeral().await?;
@@ -119,19 +275,24 @@ async fn execute_open_modal(ctx: &Context<'_>, spec: ModalPayload) -> Result<(),
     }
 }
 
-fn build_interaction_payload(message_payload: MessagePayload) -> Result<poise::CreateReply, Error> {
+pub fn build_interaction_payload(
+    message_payload: MessagePayload,
+) -> Result<poise::CreateReply, Error> {
     let mut builder = poise::CreateReply::default();
     if let Some(content) = message_payload.content {
         builder = builder.content(content);
     }
     if let Some(embeds) = message_payload.embeds {
         for embed in embeds {
-            builder = builder.embed(build_embed(embed));
+            builder = builder.embed(build_embed(embed)?);
         }
     }
     if let Some(components) = message_payload.components {
         builder = builder.components(build_action_rows(components)?);
     }
+    if let Some(allowed_mentions) = message_payload.allowed_mentions {
+        builder = builder.allowed_mentions(build_allowed_mentions(allowed_mentions));
+    }
     if let Some(ephemeral) = message_payload.ephemeral {
         builder = builder.ephemeral(ephemeral);
     }
@@ -150,16 +311,26 @@ fn build_message_payload(
     }
     if let Some(embeds) = message_payload.embeds {
         for embed in embeds {
-            message = message.add_embed(build_embed(embed));
+            message = message.add_embed(build_embed(embed)?);
         }
     }
     if let Some(components) = message_payload.components {
         message = message.components(build_action_rows(components)?);
     }
+    if let Some(allowed_mentions) = message_payload.allowed_mentions {
+        message = message.allowed_mentions(build_allowed_mentions(allowed_mentions));
+    }
     Ok(message)
 }
 
-fn build_embed(embed_payload: EmbedPayload) -> serenity::CreateEmbed {
+fn build_allowed_mentions(spec: AllowedMentionsPayload) -> serenity::CreateAllowedMentions {
+    serenity::CreateAllowedMentions::new()
+        .users(spec.user_ids.into_iter().map(serenity::UserId::new))
+        .roles(spec.role_ids.into_iter().map(serenity::RoleId::new))
+        .everyone(spec.everyone)
+}
+
+pub fn build_embed(embed_payload: EmbedPayload) -> Result<serenity::CreateEmbed, Error> {
     let mut embed = serenity::CreateEmbed::new();
     if let Some(title) = embed_payload.title {
         embed = embed.title(title);
@@ -170,10 +341,46 @@ fn build_embed(embed_payload: EmbedPayload) -> serenity::CreateEmbed {
     for field in embed_payload.fields {
         embed = embed.field(field.name, field.value, field.inline);
     }
-    embed
+    if let Some(color) = embed_payload.color {
+        embed = embed.color(color);
+    }
+    if let Some(url) = embed_payload.url {
+        embed = embed.url(url);
+    }
+    if let Some(footer) = embed_payload.footer {
+        let mut create_footer = serenity::CreateEmbedFooter::new(footer.text);
+        if let Some(icon_url) = footer.icon_url {
+            create_footer = create_footer.icon_url(icon_url);
+        }
+        embed = embed.footer(create_footer);
+    }
+    if let Some(author) = embed_payload.author {
+        let mut create_author = serenity::CreateEmbedAuthor::new(author.name);
+        if let Some(url) = author.url {
+            create_author = create_author.url(url);
+        }
+        if let Some(icon_url) = author.icon_url {
+            create_author = create_author.icon_url(icon_url);
+        }
+        embed = embed.author(create_author);
+    }
+    if let Some(unix) = embed_payload.timestamp {
+        let timestamp = serenity::Timestamp::from_unix_timestamp(unix)
+            .with_context(|| format!("invalid embed timestamp: {unix}"))?;
+        embed = embed.timestamp(timestamp);
+    }
+    if let Some(url) = embed_payload.thumbnail_url {
+        embed = embed.thumbnail(url);
+    }
+    if let Some(url) = embed_payload.image_url {
+        embed = embed.image(url);
+    }
+    Ok(embed)
 }
 
-fn build_action_rows(rows: Vec<ActionRowPayload>) -> Result<Vec<serenity::CreateActionRow>, Error> {
+pub fn build_action_rows(
+    rows: Vec<ActionRowPayload>,
+) -> Result<Vec<serenity::CreateActionRow>, Error> {
     rows.into_iter()
         .map(|row| match row {
             ActionRowPayload::Buttons(buttons) => {
@@ -229,12 +436,7 @@ fn map_button_style(style: &ButtonStylePayload) -> serenity::ButtonStyle {
 }
 
 fn build_select_menu(menu: SelectMenuPayload) -> Result<serenity::CreateSelectMenu, Error> {
-    let options = menu
-        .options
-        .into_iter()
-        .map(build_select_option)
-        .collect::<Vec<_>>();
-    let kind = serenity::CreateSelectMenuKind::String { options };
+    let kind = build_select_menu_kind(menu.kind);
     let mut select = serenity::CreateSelectMenu::new(menu.custom_id, kind);
     if let Some(placeholder) = menu.placeholder {
         select = select.placeholder(placeholder);
@@ -251,6 +453,60 @@ fn build_select_menu(menu: SelectMenuPayload) -> Result<serenity::CreateSelectMe
     Ok(select)
 }
 
+fn build_select_menu_kind(kind: SelectMenuKindPayload) -> serenity::CreateSelectMenuKind {
+    match kind {
+        SelectMenuKindPayload::String { options } => serenity::CreateSelectMenuKind::String {
+            options: options.into_iter().map(build_select_option).collect(),
+        },
+        SelectMenuKindPayload::User { default_users } => serenity::CreateSelectMenuKind::User {
+            default_users: non_empty_ids(default_users, serenity::UserId::new),
+        },
+        SelectMenuKindPayload::Role { default_roles } => serenity::CreateSelectMenuKind::Role {
+            default_roles: non_empty_ids(default_roles, serenity::RoleId::new),
+        },
+        SelectMenuKindPayload::Mentionable {
+            default_users,
+            default_roles,
+        } => serenity::CreateSelectMenuKind::Mentionable {
+            default_users: non_empty_ids(default_users, serenity::UserId::new),
+            default_roles: non_empty_ids(default_roles, serenity::RoleId::new),
+        },
+        SelectMenuKindPayload::Channel {
+            channel_types,
+            default_channels,
+        } => serenity::CreateSelectMenuKind::Channel {
+            channel_types: if channel_types.is_empty() {
+                None
+            } else {
+                Some(channel_types.into_iter().map(map_channel_type).collect())
+            },
+            default_channels: non_empty_ids(default_channels, serenity::ChannelId::new),
+        },
+    }
+}
+
+fn non_empty_ids<T>(ids: Vec<u64>, to_id: fn(u64) -> T) -> Option<Vec<T>> {
+    if ids.is_empty() {
+        None
+    } else {
+        Some(ids.into_iter().map(to_id).collect())
+    }
+}
+
+fn map_channel_type(channel_type: ChannelTypePayload) -> serenity::ChannelType {
+    match channel_type {
+        ChannelTypePayload::Text => serenity::ChannelType::Text,
+        ChannelTypePayload::Voice => serenity::ChannelType::Voice,
+        ChannelTypePayload::Category => serenity::ChannelType::Category,
+        ChannelTypePayload::News => serenity::ChannelType::News,
+        ChannelTypePayload::Stage => serenity::ChannelType::Stage,
+        ChannelTypePayload::Forum => serenity::ChannelType::Forum,
+        ChannelTypePayload::PublicThread => serenity::ChannelType::PublicThread,
+        ChannelTypePayload::PrivateThread => serenity::ChannelType::PrivateThread,
+        ChannelTypePayload::NewsThread => serenity::ChannelType::NewsThread,
+    }
+}
+
 fn build_select_option(option: SelectOptionPayload) -> serenity::CreateSelectMenuOption {
     let mut opt = serenity::CreateSelectMenuOption::new(option.label, option.value);
     if let Some(description) = option.description {
@@ -262,7 +518,7 @@ fn build_select_option(option: SelectOptionPayload) -> serenity::CreateSelectMen
     opt
 }
 
-fn build_modal(spec: ModalPayload) -> Result<serenity::CreateModal, Error> {
+pub fn build_modal(spec: ModalPayload) -> Result<serenity::CreateModal, Error> {
     let rows = spec
         .inputs
         .into_iter()
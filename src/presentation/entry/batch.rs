@@ -1,31 +1,250 @@
 use std::collections::HashMap;
+use std::future::Future;
+use std::time::Instant;
 
 use chrono::{Datelike, Duration, NaiveTime, TimeZone, Utc, Weekday};
 use poise::serenity_prelude as serenity;
+use tracing::Instrument as _;
 
+use crate::infrastructure::config::BotConfig;
 use crate::infrastructure::db::Db;
+use crate::infrastructure::logging::new_request_id;
+use crate::infrastructure::metrics::METRICS;
+use crate::infrastructure::shutdown::Shutdown;
+use crate::presentation::discord_exec;
+use crate::presentation::entry::on_error;
 use crate::presentation::entry::util::{current_unix_timestamp, truncate};
+use crate::usecase::batch::retention::{self, RetentionInput};
+use crate::usecase::batch::team_report::{self, TeamReportInput};
+use crate::usecase::batch::{due_reminder, escalation};
+use crate::usecase::dto::repository::{DueReminderKind, EscalationKind};
 
 const JST_OFFSET_SECS: i64 = 9 * 3600;
-const BATCH_HOUR_JST: u32 = 8;
 const ONE_MONTH_SECS: i64 = 30 * 24 * 3600;
+const DEPARTED_GUILD_PURGE_INTERVAL: std::time::Duration =
+    std::time::Duration::from_secs(24 * 3600);
+
+/// 設定で有効になっているバッチだけを起動する。
+/// 退出したギルドの記録の削除は、保持期間の設定に関わらず常に起動する。
+pub fn start(ctx: serenity::Context, db: Db, config: BotConfig, shutdown: Shutdown) {
+    let scheduler = config.scheduler;
+    let features = config.features;
+    start_departed_guild_purge(db.clone(), shutdown.clone());
+    if features.escalation {
+        start_escalation(
+            ctx.clone(),
+            db.clone(),
+            scheduler.escalation_interval,
+            shutdown.clone(),
+        );
+    }
+    if features.due_reminders {
+        start_due_reminders(
+            ctx.clone(),
+            db.clone(),
+            scheduler.due_reminder_interval,
+            shutdown.clone(),
+        );
+    }
+    if features.retention {
+        start_retention(
+            db.clone(),
+            config.retention_days,
+            scheduler.retention_interval,
+            shutdown.clone(),
+        );
+    }
+    if !(features.weekly_digest || features.monthly_digest || features.team_report) {
+        return;
+    }
 
-pub fn start(ctx: serenity::Context, db: Db) {
     tokio::spawn(async move {
         loop {
-            let sleep_duration = duration_until_next_monday_8am_jst();
+            let sleep_duration = duration_until_next_weekly_run(scheduler.weekly_hour_jst);
             tokio::time::sleep(sleep_duration).await;
 
             let now_unix = current_unix_timestamp();
 
-            if is_first_monday_of_month_jst(now_unix) {
-                run_monthly_batch(&ctx, &db, now_unix).await;
+            if features.monthly_digest && is_first_monday_of_month_jst(now_unix) {
+                run_job(&shutdown, "monthly", run_monthly_batch(&ctx, &db, now_unix)).await;
+            }
+            if features.weekly_digest {
+                run_job(&shutdown, "weekly", run_weekly_batch(&ctx, &db)).await;
+            }
+            if features.team_report {
+                run_job(
+                    &shutdown,
+                    "team_report",
+                    run_team_report(&ctx, &db, now_unix),
+                )
+                .await;
             }
-            run_weekly_batch(&ctx, &db).await;
         }
     });
 }
 
+/// 停止中でなければバッチを実行し、所要時間をメトリクスに記録する。
+/// 実行中のバッチは停止時に終わるまで待たれる。実行ごとに request_id 付きのスパンを張る。
+async fn run_job(shutdown: &Shutdown, job: &'static str, run: impl Future<Output = ()>) {
+    let Some(_in_flight) = shutdown.track() else {
+        return;
+    };
+    let span = tracing::info_span!("batch", job, request_id = %new_request_id());
+    let started = Instant::now();
+    run.instrument(span).await;
+    METRICS.observe_batch(job, started.elapsed().as_secs_f64());
+}
+
+fn start_escalation(
+    ctx: serenity::Context,
+    db: Db,
+    every: std::time::Duration,
+    shutdown: Shutdown,
+) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(every);
+        loop {
+            interval.tick().await;
+            let now_unix = current_unix_timestamp();
+            run_job(&shutdown, "escalation", async {
+                for kind in [EscalationKind::AuthorDm, EscalationKind::Reping] {
+                    run_escalation(&ctx, &db, kind, now_unix).await;
+                }
+            })
+            .await;
+        }
+    });
+}
+
+async fn run_escalation(ctx: &serenity::Context, db: &Db, kind: EscalationKind, now_unix: i64) {
+    let plans = match escalation::execute(db, kind, now_unix).await {
+        Ok(plans) => plans,
+        Err(err) => {
+            tracing::error!("エスカレーション({})失敗: {:?}", kind.as_str(), err);
+            return;
+        }
+    };
+
+    if plans.is_empty() {
+        return;
+    }
+
+    let count = plans.len();
+    for plan in plans {
+        if let Err(err) = discord_exec::execute(ctx, plan).await {
+            tracing::error!("エスカレーション({})送信失敗: {:?}", kind.as_str(), err);
+        }
+    }
+
+    tracing::info!("エスカレーション({})完了: {}件", kind.as_str(), count);
+}
+
+fn start_due_reminders(
+    ctx: serenity::Context,
+    db: Db,
+    every: std::time::Duration,
+    shutdown: Shutdown,
+) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(every);
+        loop {
+            interval.tick().await;
+            let now_unix = current_unix_timestamp();
+            run_job(&shutdown, "due_reminder", async {
+                for kind in [
+                    DueReminderKind::DueSoon,
+                    DueReminderKind::Overdue,
+                    DueReminderKind::UrgentUnread,
+                ] {
+                    run_due_reminder(&ctx, &db, kind, now_unix).await;
+                }
+            })
+            .await;
+        }
+    });
+}
+
+async fn run_due_reminder(ctx: &serenity::Context, db: &Db, kind: DueReminderKind, now_unix: i64) {
+    let plans = match due_reminder::execute(db, kind, now_unix).await {
+        Ok(plans) => plans,
+        Err(err) => {
+            tracing::error!("期限リマインダー({})失敗: {:?}", kind.as_str(), err);
+            return;
+        }
+    };
+
+    if plans.is_empty() {
+        return;
+    }
+
+    let count = plans.len();
+    for plan in plans {
+        if let Err(err) = discord_exec::execute(ctx, plan).await {
+            tracing::error!("期限リマインダー({})送信失敗: {:?}", kind.as_str(), err);
+        }
+    }
+
+    tracing::info!(
+        "期限リマインダー({})完了: {}ユーザーに通知",
+        kind.as_str(),
+        count
+    );
+}
+
+fn start_retention(
+    db: Db,
+    default_days: Option<u32>,
+    every: std::time::Duration,
+    shutdown: Shutdown,
+) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(every);
+        loop {
+            interval.tick().await;
+            run_job(&shutdown, "retention", run_retention(&db, default_days)).await;
+        }
+    });
+}
+
+async fn run_retention(db: &Db, default_days: Option<u32>) {
+    let now_unix = current_unix_timestamp();
+    let input = RetentionInput {
+        default_days,
+        now_unix,
+    };
+    match retention::execute(db, input).await {
+        Ok(0) => {}
+        Ok(deleted) => tracing::info!("保持期間バッチ完了: {}件のメンションを削除", deleted),
+        Err(err) => tracing::error!("保持期間バッチ失敗: {:?}", err),
+    }
+}
+
+fn start_departed_guild_purge(db: Db, shutdown: Shutdown) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(DEPARTED_GUILD_PURGE_INTERVAL);
+        loop {
+            interval.tick().await;
+            run_job(
+                &shutdown,
+                "departed_guild_purge",
+                run_departed_guild_purge(&db),
+            )
+            .await;
+        }
+    });
+}
+
+async fn run_departed_guild_purge(db: &Db) {
+    match retention::purge_departed_guilds(db, current_unix_timestamp()).await {
+        Ok(0) => {}
+        Ok(deleted) => tracing::info!(
+            "退出したギルドの記録を削除: {}件のメンションを削除",
+            deleted
+        ),
+        Err(err) => tracing::error!("退出したギルドの記録の削除に失敗: {:?}", err),
+    }
+}
+
 async fn run_weekly_batch(ctx: &serenity::Context, db: &Db) {
     tracing::info!("週次バッチ開始");
 
@@ -49,7 +268,10 @@ async fn run_weekly_batch(ctx: &serenity::Context, db: &Db) {
     }
 
     for (user_id, mention_ids) in &by_user {
-        if let Err(err) = send_weekly_dm(ctx, *user_id, mention_ids).await {
+        let sent = send_weekly_dm(ctx, *user_id, mention_ids).await;
+        METRICS.record_dm(sent.is_ok());
+        if let Err(err) = sent {
+            on_error::record_discord_http_error(&err);
             tracing::error!("週次DM送信失敗 user={}: {:?}", user_id, err);
         }
     }
@@ -57,6 +279,36 @@ async fn run_weekly_batch(ctx: &serenity::Context, db: &Db) {
     tracing::info!("週次バッチ完了: {}ユーザーに通知", by_user.len());
 }
 
+async fn run_team_report(ctx: &serenity::Context, db: &Db, now_unix: i64) {
+    tracing::info!("チームレポート開始");
+
+    let settings = match db.fetch_report_settings().await {
+        Ok(settings) => settings,
+        Err(err) => {
+            tracing::error!("チームレポート: 設定取得失敗: {:?}", err);
+            return;
+        }
+    };
+
+    let guild_count = settings.len();
+    for settings in settings {
+        let guild_id = settings.guild_id;
+        let input = TeamReportInput { settings, now_unix };
+        let plan = match team_report::execute(db, input).await {
+            Ok(plan) => plan,
+            Err(err) => {
+                tracing::error!("チームレポート作成失敗 guild={}: {:?}", guild_id, err);
+                continue;
+            }
+        };
+        if let Err(err) = discord_exec::execute(ctx, plan).await {
+            tracing::error!("チームレポート投稿失敗 guild={}: {:?}", guild_id, err);
+        }
+    }
+
+    tracing::info!("チームレポート完了: {}ギルドに投稿", guild_count);
+}
+
 async fn send_weekly_dm(
     ctx: &serenity::Context,
     user_id: u64,
@@ -108,7 +360,10 @@ async fn run_monthly_batch(ctx: &serenity::Context, db: &Db, now_unix: i64) {
     }
 
     for (user_id, items) in &by_user {
-        if let Err(err) = send_monthly_dm(ctx, *user_id, items).await {
+        let sent = send_monthly_dm(ctx, *user_id, items).await;
+        METRICS.record_dm(sent.is_ok());
+        if let Err(err) = sent {
+            on_error::record_discord_http_error(&err);
             tracing::error!("月次DM送信失敗 user={}: {:?}", user_id, err);
         }
     }
@@ -119,7 +374,7 @@ async fn run_monthly_batch(ctx: &serenity::Context, db: &Db, now_unix: i64) {
 async fn send_monthly_dm(
     ctx: &serenity::Context,
     user_id: u64,
-    items: &[crate::infrastructure::db::MentionForTarget],
+    items: &[crate::usecase::dto::repository::MentionForTarget],
 ) -> anyhow::Result<()> {
     let dm_channel = serenity::UserId::new(user_id)
         .create_dm_channel(&ctx.http)
@@ -176,7 +431,8 @@ async fn send_monthly_dm(
     Ok(())
 }
 
-fn duration_until_next_monday_8am_jst() -> std::time::Duration {
+/// 次の月曜日の `hour_jst` 時（JST）までの時間。
+fn duration_until_next_weekly_run(hour_jst: u32) -> std::time::Duration {
     let now_unix = current_unix_timestamp();
     let now_utc = Utc
         .timestamp_opt(now_unix, 0)
@@ -199,7 +455,7 @@ fn duration_until_next_monday_8am_jst() -> std::time::Duration {
     };
 
     let next_monday = today + Duration::days(days_until_monday as i64);
-    let batch_time = NaiveTime::from_hms_opt(BATCH_HOUR_JST, 0, 0).unwrap();
+    let batch_time = NaiveTime::from_hms_opt(hour_jst, 0, 0).unwrap_or(NaiveTime::MIN);
     let next_run_naive = next_monday.and_time(batch_time);
 
     // JST → UTC (UTC = JST - 9h)
@@ -207,7 +463,7 @@ fn duration_until_next_monday_8am_jst() -> std::time::Duration {
 
     let sleep_secs = (next_run_unix - now_unix).max(0) as u64;
 
-    // 既に今日が月曜かつ8時を過ぎている場合は来週に
+    // 既に今日が月曜かつ実行時刻を過ぎている場合は来週に
     if sleep_secs == 0 {
         let next_run_unix = next_run_unix + 7 * 24 * 3600;
         let sleep_secs = (next_run_unix - now_unix).max(1) as u64;
@@ -1,8 +1,11 @@
 pub mod batch;
 pub mod on_component;
 pub mod on_error;
+pub mod on_guild_lifecycle;
 pub mod on_message;
 pub mod on_message_delete;
+pub mod on_modal_submit;
 pub mod on_reaction_add;
+pub mod on_ready;
 pub mod slash_commands;
 pub mod util;
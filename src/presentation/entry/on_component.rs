@@ -1,8 +1,15 @@
 use poise::serenity_prelude as serenity;
 
+use crate::interface::mapper::input_mapper;
+use crate::presentation::discord_exec;
+use crate::presentation::entry::on_message;
 use crate::presentation::entry::slash_commands::my_mentions;
-use crate::presentation::entry::util::current_unix_timestamp;
-use crate::presentation::Data;
+use crate::presentation::entry::util::{current_unix_timestamp, done_reaction, kidoku_reaction};
+use crate::presentation::{Data, Error};
+use crate::usecase::dto::{MessagePayload, SelectedValues};
+use crate::usecase::on_modal_submit::done_with_comment;
+use crate::usecase::slash_commands::my_mentions as my_mentions_usecase;
+use crate::usecase::slash_commands::track_message::{self, StartTrackingInput};
 
 pub async fn handle(ctx: &serenity::Context, data: &Data, comp: &serenity::ComponentInteraction) {
     let id = comp.data.custom_id.as_str();
@@ -12,6 +19,10 @@ pub async fn handle(ctx: &serenity::Context, data: &Data, comp: &serenity::Compo
         handle_extend(ctx, data, comp).await;
     } else if id.starts_with("mm:ignore:") {
         handle_ignore(ctx, data, comp).await;
+    } else if id.starts_with(my_mentions_usecase::DONE_SELECT_CUSTOM_ID_PREFIX) {
+        handle_done_select(ctx, comp).await;
+    } else if id.starts_with(track_message::TRACK_SELECT_CUSTOM_ID_PREFIX) {
+        handle_track_select(ctx, data, comp).await;
     }
 }
 
@@ -46,7 +57,7 @@ async fn handle_pagination(
     data: &Data,
     comp: &serenity::ComponentInteraction,
 ) {
-    // custom_id format: "mm:p:{page}:{show_done_01}:{user_id}"
+    // custom_id format: "mm:p:{page}:{flags}:{user_id}" (flags は ListOptions::to_flags)
     let parts: Vec<&str> = comp.data.custom_id.splitn(5, ':').collect();
     if parts.len() != 5 {
         tracing::warn!("unexpected pagination custom_id: {}", comp.data.custom_id);
@@ -56,7 +67,10 @@ async fn handle_pagination(
         Ok(v) => v,
         Err(_) => return,
     };
-    let show_done: bool = parts[3] == "1";
+    let options = match parts[3].parse() {
+        Ok(flags) => my_mentions_usecase::ListOptions::from_flags(flags),
+        Err(_) => return,
+    };
     let owner_user_id: u64 = match parts[4].parse() {
         Ok(v) => v,
         Err(_) => return,
@@ -76,7 +90,7 @@ async fn handle_pagination(
         return;
     }
 
-    let items = match my_mentions::fetch_page(&data.db, ctx, owner_user_id, page, show_done).await {
+    let items = match my_mentions::fetch_page(&data.db, ctx, owner_user_id, page, options).await {
         Ok(items) => items,
         Err(err) => {
             tracing::error!("failed to fetch page for pagination: {:?}", err);
@@ -100,27 +114,47 @@ async fn handle_pagination(
         return;
     }
 
-    let has_next = items.len() > 5;
-    let page_items = &items[..items.len().min(5)];
-    let guild_id = comp.guild_id;
+    let has_next = items.len() > my_mentions::PAGE_SIZE;
+    let page_items = &items[..items.len().min(my_mentions::PAGE_SIZE)];
+    let guild_id = comp.guild_id.map(|g| g.get());
 
-    let embeds = my_mentions::build_embeds(page_items, guild_id);
-    let components =
-        my_mentions::build_nav_buttons(page, show_done, owner_user_id, page > 0, has_next);
+    let payload = my_mentions_usecase::build_page(
+        page_items,
+        guild_id,
+        page,
+        options,
+        owner_user_id,
+        has_next,
+        current_unix_timestamp(),
+    );
+    let edit = match build_edit_response(payload) {
+        Ok(edit) => edit,
+        Err(err) => {
+            tracing::error!("failed to build pagination message: {:?}", err);
+            return;
+        }
+    };
 
-    if let Err(err) = comp
-        .edit_response(
-            &ctx.http,
-            serenity::EditInteractionResponse::new()
-                .embeds(embeds)
-                .components(components),
-        )
-        .await
-    {
+    if let Err(err) = comp.edit_response(&ctx.http, edit).await {
         tracing::error!("failed to update pagination message: {:?}", err);
     }
 }
 
+fn build_edit_response(
+    payload: MessagePayload,
+) -> Result<serenity::EditInteractionResponse, Error> {
+    let embeds = payload
+        .embeds
+        .unwrap_or_default()
+        .into_iter()
+        .map(discord_exec::build_embed)
+        .collect::<Result<Vec<_>, _>>()?;
+    let components = discord_exec::build_action_rows(payload.components.unwrap_or_default())?;
+    Ok(serenity::EditInteractionResponse::new()
+        .embeds(embeds)
+        .components(components))
+}
+
 async fn handle_extend(
     ctx: &serenity::Context,
     data: &Data,
@@ -219,3 +253,178 @@ async fn handle_ignore(
         tracing::error!("failed to update ignore message: {:?}", err);
     }
 }
+
+async fn handle_done_select(ctx: &serenity::Context, comp: &serenity::ComponentInteraction) {
+    // custom_id format: "mm:done_select:{user_id}"
+    let owner_user_id: u64 = match comp
+        .data
+        .custom_id
+        .strip_prefix(my_mentions_usecase::DONE_SELECT_CUSTOM_ID_PREFIX)
+        .and_then(|rest| rest.parse().ok())
+    {
+        Some(v) => v,
+        None => {
+            tracing::warn!("unexpected done select custom_id: {}", comp.data.custom_id);
+            return;
+        }
+    };
+
+    if reject_if_unauthorized(ctx, comp, owner_user_id).await {
+        return;
+    }
+
+    let message_id: u64 =
+        match input_mapper::from_component_to_select_menu_input(comp).map(|input| input.values) {
+            Some(SelectedValues::Strings(values)) => match values.first().map(|v| v.parse()) {
+                Some(Ok(v)) => v,
+                _ => return,
+            },
+            _ => return,
+        };
+
+    let modal = match discord_exec::build_modal(done_with_comment::build_modal(
+        message_id,
+        owner_user_id,
+    )) {
+        Ok(modal) => modal,
+        Err(err) => {
+            tracing::error!("failed to build done modal: {:?}", err);
+            return;
+        }
+    };
+
+    if let Err(err) = comp
+        .create_response(&ctx.http, serenity::CreateInteractionResponse::Modal(modal))
+        .await
+    {
+        tracing::error!("failed to open done modal: {:?}", err);
+    }
+}
+
+async fn handle_track_select(
+    ctx: &serenity::Context,
+    data: &Data,
+    comp: &serenity::ComponentInteraction,
+) {
+    let Some((channel_id, message_id, owner_user_id)) =
+        track_message::parse_track_select_custom_id(&comp.data.custom_id)
+    else {
+        tracing::warn!("unexpected track select custom_id: {}", comp.data.custom_id);
+        return;
+    };
+    let Some(guild_id) = comp.guild_id else {
+        return;
+    };
+
+    if reject_if_unauthorized(ctx, comp, owner_user_id).await {
+        return;
+    }
+
+    // メンバー一覧の取得で 3秒を超えることがあるため、先に Acknowledge する。
+    if let Err(err) = comp
+        .create_response(&ctx.http, serenity::CreateInteractionResponse::Acknowledge)
+        .await
+    {
+        tracing::error!("failed to acknowledge track select interaction: {:?}", err);
+        return;
+    }
+
+    let content = match start_tracking(ctx, data, comp, guild_id, channel_id, message_id).await {
+        Ok(content) => content,
+        Err(err) => {
+            tracing::error!("failed to start tracking: {:?}", err);
+            "追跡の開始に失敗しました。".to_string()
+        }
+    };
+
+    if let Err(err) = comp
+        .edit_response(
+            &ctx.http,
+            serenity::EditInteractionResponse::new()
+                .content(content)
+                .components(vec![]),
+        )
+        .await
+    {
+        tracing::error!("failed to update track select message: {:?}", err);
+    }
+}
+
+async fn start_tracking(
+    ctx: &serenity::Context,
+    data: &Data,
+    comp: &serenity::ComponentInteraction,
+    guild_id: serenity::GuildId,
+    channel_id: u64,
+    message_id: u64,
+) -> Result<String, Error> {
+    let (user_ids, role_ids) =
+        match input_mapper::from_component_to_select_menu_input(comp).map(|input| input.values) {
+            Some(SelectedValues::Mentionables { users, roles }) => (users, roles),
+            _ => return Ok("追跡する対象を選択してください。".to_string()),
+        };
+
+    let message = serenity::ChannelId::new(channel_id)
+        .message(&ctx.http, serenity::MessageId::new(message_id))
+        .await?;
+
+    let bot_id = ctx.cache.current_user().id;
+    let members = on_message::fetch_guild_members(ctx, guild_id).await?;
+    let bot_ids = members
+        .iter()
+        .filter(|member| member.user.bot)
+        .map(|member| member.user.id.get())
+        .collect::<Vec<_>>();
+    let role_members = on_message::build_role_members_map(&members);
+    let role_ids = role_ids
+        .into_iter()
+        .map(serenity::RoleId::new)
+        .collect::<Vec<_>>();
+
+    let mut target_user_ids = user_ids;
+    target_user_ids.extend(
+        on_message::expand_role_members(&role_members, &role_ids)
+            .into_iter()
+            .map(|user_id| user_id.get()),
+    );
+    target_user_ids.retain(|user_id| *user_id != bot_id.get() && !bot_ids.contains(user_id));
+    let opted_out = data
+        .db
+        .fetch_tracking_opt_out_user_ids(&target_user_ids)
+        .await?;
+    target_user_ids.retain(|user_id| !opted_out.contains(user_id));
+
+    let channel_default_priority = match data.db.fetch_channel_default_priority(channel_id).await {
+        Ok(priority) => priority,
+        Err(err) => {
+            tracing::warn!("failed to fetch channel default priority: {:?}", err);
+            None
+        }
+    };
+
+    let input = StartTrackingInput {
+        guild_id: guild_id.get(),
+        channel_id,
+        message_id,
+        author_id: message.author.id.get(),
+        content: message.content.clone(),
+        created_at_unix: message.timestamp.unix_timestamp(),
+        target_user_ids,
+        channel_default_priority,
+        content_storage: data.db.fetch_content_storage_mode(guild_id.get()).await?,
+    };
+    let output = track_message::start(&data.db, input).await?;
+
+    if let Some(priority) = output.priority {
+        if let Err(err) = message.react(&ctx.http, kidoku_reaction()).await {
+            tracing::warn!("failed to add read reaction on start tracking: {:?}", err);
+        }
+        if priority.requires_action() {
+            if let Err(err) = message.react(&ctx.http, done_reaction()).await {
+                tracing::warn!("failed to add done reaction on start tracking: {:?}", err);
+            }
+        }
+    }
+
+    Ok(output.content)
+}
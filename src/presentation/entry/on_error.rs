@@ -1,9 +1,27 @@
+use poise::serenity_prelude as serenity;
+
+use crate::infrastructure::metrics::METRICS;
 use crate::presentation::{Data, Error};
 
 pub async fn handle_framework_error(error: poise::FrameworkError<'_, Data, Error>) {
+    if let poise::FrameworkError::Command { error, .. } = &error {
+        record_discord_http_error(error);
+    }
     let _ = poise::builtins::on_error(error).await;
 }
 
 pub fn handle_exec_error(error: Error) {
+    record_discord_http_error(&error);
     tracing::error!("failed to execute discord plan: {:?}", error);
 }
+
+/// Discord API のエラー応答なら、ステータスコードごとに数える。
+pub fn record_discord_http_error(error: &Error) {
+    let status = error.chain().find_map(|cause| match cause.downcast_ref() {
+        Some(serenity::Error::Http(http_error)) => http_error.status_code(),
+        _ => None,
+    });
+    if let Some(status) = status {
+        METRICS.inc_discord_http_error(status.as_u16());
+    }
+}
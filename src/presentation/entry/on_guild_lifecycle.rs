@@ -0,0 +1,77 @@
+use poise::serenity_prelude as serenity;
+
+use crate::presentation::entry::util::current_unix_timestamp;
+use crate::presentation::Data;
+
+/// 起動時や再招待で届く。退出記録があれば消して、猶予期間中のメンションをそのまま使う。
+pub async fn handle_guild_create(data: &Data, guild_id: serenity::GuildId) {
+    match data.db.clear_guild_departure(guild_id.get()).await {
+        Ok(false) => {}
+        Ok(true) => {
+            tracing::info!(
+                "rejoined guild {}, restored mention records",
+                guild_id.get()
+            );
+        }
+        Err(err) => {
+            tracing::error!(
+                "failed to clear departure record for guild {}: {:?}",
+                guild_id.get(),
+                err
+            );
+        }
+    }
+}
+
+/// 退出・キックされたギルドを記録する。メンションは猶予期間を過ぎてから保持期間バッチで削除する。
+pub async fn handle_guild_delete(data: &Data, guild: &serenity::UnavailableGuild) {
+    // 障害で一時的に利用できないだけなら、退出扱いにしない。
+    if guild.unavailable {
+        return;
+    }
+
+    match data
+        .db
+        .mark_guild_departed(guild.id.get(), current_unix_timestamp())
+        .await
+    {
+        Ok(()) => {
+            tracing::info!(
+                "left guild {}, scheduled mention records for purge",
+                guild.id.get()
+            );
+        }
+        Err(err) => {
+            tracing::error!(
+                "failed to record departure from guild {}: {:?}",
+                guild.id.get(),
+                err
+            );
+        }
+    }
+}
+
+/// 削除されたチャンネル・スレッドのメンションはリンク先が無くなるため、すぐに削除する。
+pub async fn handle_channel_delete(data: &Data, channel_id: serenity::ChannelId) {
+    match data
+        .db
+        .delete_mentions_by_channel_id(channel_id.get())
+        .await
+    {
+        Ok(0) => {}
+        Ok(deleted) => {
+            tracing::info!(
+                "deleted {} mention records for deleted channel {}",
+                deleted,
+                channel_id.get()
+            );
+        }
+        Err(err) => {
+            tracing::error!(
+                "failed to delete mention records for channel {}: {:?}",
+                channel_id.get(),
+                err
+            );
+        }
+    }
+}
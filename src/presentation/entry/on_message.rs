@@ -4,48 +4,68 @@ use anyhow::Context as _;
 use poise::serenity_prelude as serenity;
 use serenity::model::prelude::{ChannelType, Member, RoleId, UserId};
 
-use crate::infrastructure::db::NewMention;
+use crate::domain::model::{ContentStorageMode, MentionPriority};
+use crate::domain::policy::mention_detection::ScopeDecision;
+use crate::infrastructure::metrics::METRICS;
 use crate::interface::mapper::input_mapper;
 use crate::presentation::entry::on_error;
-use crate::presentation::entry::util::{
-    DONE_EMOJI_ID, DONE_EMOJI_NAME, KIDOKU_EMOJI_ID, KIDOKU_EMOJI_NAME,
-};
+use crate::presentation::entry::util::{done_reaction, kidoku_reaction};
 use crate::presentation::{Data, Error};
-use crate::usecase::on_message::auto_add_read_reaction;
+use crate::usecase::dto::repository::NewMention;
+use crate::usecase::dto::MessageInputDto;
+use crate::usecase::on_message::{
+    auto_add_read_reaction, classify_priority, evaluate_scope, resolve_due_at,
+};
 
 pub async fn handle(ctx: &serenity::Context, data: &Data, message: &serenity::Message) {
+    if let Some(priority) = track(ctx, data, message).await {
+        add_reactions(ctx, message, priority).await;
+    }
+}
+
+/// 追跡対象のメンションなら記録し、付けるリアクションを決める優先度を返す。
+pub async fn track(
+    ctx: &serenity::Context,
+    data: &Data,
+    message: &serenity::Message,
+) -> Option<MentionPriority> {
     if message.author.bot {
-        return;
+        return None;
     }
 
     match message.kind {
         serenity::MessageType::Regular | serenity::MessageType::InlineReply => {}
-        _ => return,
+        _ => return None,
     }
 
     let input = input_mapper::from_message_to_message_input_dto(message);
-    let output = match auto_add_read_reaction::execute(input) {
+    let output = match auto_add_read_reaction::execute(input.clone()) {
         Ok(output) => output,
         Err(err) => {
             tracing::error!("usecase error: {:?}", err);
-            return;
+            return None;
         }
     };
 
     if !output.should_add_reaction {
-        return;
+        return None;
     }
 
     let guild_id = match message.guild_id {
         Some(guild_id) => guild_id,
         None => {
             tracing::warn!("message without guild_id cannot be stored");
-            return;
+            return None;
         }
     };
 
+    let scope = evaluate_tracking_scope(ctx, data, guild_id, message, &input).await;
+    if !scope.track {
+        return None;
+    }
+
     let bot_id = ctx.cache.current_user().id;
-    let targets = match collect_targets(ctx, guild_id, message, bot_id).await {
+    let targets = match collect_targets(ctx, data, guild_id, message, bot_id, &scope).await {
         Ok(targets) => targets,
         Err(err) => {
             on_error::handle_exec_error(err);
@@ -53,6 +73,28 @@ pub async fn handle(ctx: &serenity::Context, data: &Data, message: &serenity::Me
         }
     };
 
+    let channel_default = match data
+        .db
+        .fetch_channel_default_priority(message.channel_id.get())
+        .await
+    {
+        Ok(priority) => priority,
+        Err(err) => {
+            on_error::handle_exec_error(err);
+            None
+        }
+    };
+    let priority = classify_priority::execute(&message.content, channel_default);
+    let content_storage = match data.db.fetch_content_storage_mode(guild_id.get()).await {
+        Ok(mode) => mode,
+        Err(err) => {
+            // 方式が分からないときは本文を残さない。
+            on_error::handle_exec_error(err);
+            ContentStorageMode::None
+        }
+    };
+
+    let created_at_unix = message.timestamp.unix_timestamp();
     let mention = NewMention {
         guild_id: guild_id.get(),
         channel_id: message.channel_id.get(),
@@ -60,40 +102,105 @@ pub async fn handle(ctx: &serenity::Context, data: &Data, message: &serenity::Me
         author_id: message.author.id.get(),
         content: message.content.clone(),
         mention_everyone: message.mention_everyone,
-        created_at_unix: message.timestamp.unix_timestamp(),
+        created_at_unix,
+        due_at_unix: resolve_due_at::execute(&message.content, created_at_unix),
+        priority,
+        content_storage,
         targets,
     };
 
     if mention.targets.is_empty() {
         tracing::warn!("mention detected but targets were empty; skipping DB insert");
-    } else if let Err(err) = data.db.insert_mention(mention).await {
-        on_error::handle_exec_error(err);
+    } else {
+        match data.db.insert_mention(mention).await {
+            Ok(()) => METRICS.inc_mentions_tracked(),
+            Err(err) => on_error::handle_exec_error(err),
+        }
     }
 
-    let kidoku_reaction = serenity::ReactionType::Custom {
-        animated: false,
-        id: serenity::EmojiId::new(KIDOKU_EMOJI_ID),
-        name: Some(KIDOKU_EMOJI_NAME.to_string()),
-    };
-    if let Err(err) = message.react(&ctx.http, kidoku_reaction).await {
+    Some(priority)
+}
+
+/// KIDOKU と、解決を求める場合は DONE のリアクションを付ける。
+pub async fn add_reactions(
+    ctx: &serenity::Context,
+    message: &serenity::Message,
+    priority: MentionPriority,
+) {
+    if let Err(err) = message.react(&ctx.http, kidoku_reaction()).await {
         on_error::handle_exec_error(err.into());
     }
 
-    let done_reaction = serenity::ReactionType::Custom {
-        animated: false,
-        id: serenity::EmojiId::new(DONE_EMOJI_ID),
-        name: Some(DONE_EMOJI_NAME.to_string()),
-    };
-    if let Err(err) = message.react(&ctx.http, done_reaction).await {
+    // FYI は解決を求めないため DONE リアクションを付けない。
+    if !priority.requires_action() {
+        return;
+    }
+
+    if let Err(err) = message.react(&ctx.http, done_reaction()).await {
         on_error::handle_exec_error(err.into());
     }
 }
 
+/// 追跡範囲ルールを評価する。ルールの取得に失敗した場合はルールなしとして扱う。
+async fn evaluate_tracking_scope(
+    ctx: &serenity::Context,
+    data: &Data,
+    guild_id: serenity::GuildId,
+    message: &serenity::Message,
+    input: &MessageInputDto,
+) -> ScopeDecision {
+    let rules = match data.db.fetch_tracking_scope_rules(guild_id.get()).await {
+        Ok(rules) => rules,
+        Err(err) => {
+            on_error::handle_exec_error(err);
+            Vec::new()
+        }
+    };
+    // ルールが無ければ親チャンネルを調べる必要はない。
+    let location = if rules.is_empty() {
+        vec![message.channel_id.get()]
+    } else {
+        resolve_scope_location(ctx, message.channel_id).await
+    };
+    evaluate_scope::execute(input, &rules, &location)
+}
+
+/// チャンネルと、その親（スレッドの親チャンネル、カテゴリ）の ID を返す。
+async fn resolve_scope_location(
+    ctx: &serenity::Context,
+    channel_id: serenity::ChannelId,
+) -> Vec<u64> {
+    let mut location = vec![channel_id.get()];
+    let mut current = channel_id;
+    // スレッド → 親チャンネル → カテゴリ の最大2段をたどる。
+    for _ in 0..2 {
+        let parent_id = match current.to_channel(&ctx.http).await {
+            Ok(serenity::Channel::Guild(channel)) => channel.parent_id,
+            Ok(_) => None,
+            Err(err) => {
+                tracing::warn!(
+                    "failed to resolve parent channel for tracking scope: {:?}",
+                    err
+                );
+                None
+            }
+        };
+        let Some(parent_id) = parent_id else {
+            break;
+        };
+        location.push(parent_id.get());
+        current = parent_id;
+    }
+    location
+}
+
 async fn collect_targets(
     ctx: &serenity::Context,
+    data: &Data,
     guild_id: serenity::GuildId,
     message: &serenity::Message,
     bot_id: UserId,
+    scope: &ScopeDecision,
 ) -> Result<Vec<u64>, Error> {
     if !has_mentions(message) {
         return Ok(Vec::new());
@@ -101,7 +208,6 @@ async fn collect_targets(
 
     let members = fetch_guild_members(ctx, guild_id).await?;
     let role_members = build_role_members_map(&members);
-    let everyone_members = resolve_everyone_targets(ctx, message, &members, bot_id).await;
 
     let mut targets = Vec::new();
     targets.extend(
@@ -112,17 +218,22 @@ async fn collect_targets(
             .map(|user| user.id.get()),
     );
     targets.extend(
-        expand_role_members(&role_members, &message.mention_roles)
+        expand_role_members(&role_members, &scope.allowed_roles)
             .into_iter()
             .filter(|user_id| *user_id != bot_id)
             .map(|user_id| user_id.get()),
     );
-    if message.mention_everyone {
+    if message.mention_everyone && scope.allow_everyone {
+        let everyone_members = resolve_everyone_targets(ctx, message, &members, bot_id).await;
         targets.extend(everyone_members.into_iter().map(|user_id| user_id.get()));
     }
 
     targets.sort_unstable();
     targets.dedup();
+
+    // 追跡を拒否したユーザーは対象者として記録しない。
+    let opted_out = data.db.fetch_tracking_opt_out_user_ids(&targets).await?;
+    targets.retain(|user_id| !opted_out.contains(user_id));
     Ok(targets)
 }
 
@@ -130,7 +241,7 @@ fn has_mentions(message: &serenity::Message) -> bool {
     !message.mentions.is_empty() || !message.mention_roles.is_empty() || message.mention_everyone
 }
 
-async fn fetch_guild_members(
+pub async fn fetch_guild_members(
     ctx: &serenity::Context,
     guild_id: serenity::GuildId,
 ) -> Result<Vec<Member>, Error> {
@@ -199,7 +310,7 @@ async fn is_thread_message(ctx: &serenity::Context, message: &serenity::Message)
     }
 }
 
-fn build_role_members_map(members: &[Member]) -> HashMap<RoleId, Vec<UserId>> {
+pub fn build_role_members_map(members: &[Member]) -> HashMap<RoleId, Vec<UserId>> {
     let mut map: HashMap<RoleId, Vec<UserId>> = HashMap::new();
     for member in members {
         if member.user.bot {
@@ -212,7 +323,7 @@ fn build_role_members_map(members: &[Member]) -> HashMap<RoleId, Vec<UserId>> {
     map
 }
 
-fn expand_role_members(
+pub fn expand_role_members(
     role_members: &HashMap<RoleId, Vec<UserId>>,
     role_mentions: &[RoleId],
 ) -> Vec<UserId> {
@@ -0,0 +1,61 @@
+use poise::serenity_prelude as serenity;
+
+use crate::interface::mapper::input_mapper;
+use crate::presentation::discord_exec;
+use crate::presentation::entry::util::current_unix_timestamp;
+use crate::presentation::Data;
+use crate::usecase::dto::ModalSubmitInput;
+use crate::usecase::on_modal_submit::done_with_comment::{self, DoneWithCommentInput};
+
+pub async fn handle(ctx: &serenity::Context, data: &Data, modal: &serenity::ModalInteraction) {
+    let input = input_mapper::from_modal_to_modal_submit_input(modal);
+    if input
+        .custom_id
+        .starts_with(done_with_comment::MODAL_CUSTOM_ID_PREFIX)
+    {
+        handle_done_with_comment(ctx, data, modal, &input).await;
+    }
+}
+
+async fn handle_done_with_comment(
+    ctx: &serenity::Context,
+    data: &Data,
+    modal: &serenity::ModalInteraction,
+    input: &ModalSubmitInput,
+) {
+    let input = match DoneWithCommentInput::from_modal_submit(input, current_unix_timestamp()) {
+        Some(input) => input,
+        None => {
+            tracing::warn!("unexpected done modal submit: {}", input.custom_id);
+            return;
+        }
+    };
+
+    let output = match done_with_comment::execute(&data.db, input).await {
+        Ok(output) => output,
+        Err(err) => {
+            tracing::error!("failed to mark mention done from modal: {:?}", err);
+            respond_error(ctx, modal, "解決済みにできませんでした。").await;
+            return;
+        }
+    };
+
+    // インタラクションの3秒タイムアウトを避けるため、返信の投稿より先に応答する。
+    if let Err(err) = discord_exec::execute_from_modal_submit(ctx, modal, output.response).await {
+        tracing::error!("failed to respond to done modal: {:?}", err);
+    }
+    if let Err(err) = discord_exec::execute(ctx, output.reply).await {
+        tracing::error!("failed to post done comment: {:?}", err);
+    }
+}
+
+async fn respond_error(ctx: &serenity::Context, modal: &serenity::ModalInteraction, content: &str) {
+    let response = serenity::CreateInteractionResponse::Message(
+        serenity::CreateInteractionResponseMessage::new()
+            .content(content)
+            .ephemeral(true),
+    );
+    if let Err(err) = modal.create_response(&ctx.http, response).await {
+        tracing::error!("failed to respond to done modal error: {:?}", err);
+    }
+}
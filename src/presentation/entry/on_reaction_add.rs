@@ -1,9 +1,15 @@
 use poise::serenity_prelude as serenity;
 
-use crate::presentation::entry::util::{current_unix_timestamp, DONE_EMOJI_ID, KIDOKU_EMOJI_ID};
+use crate::infrastructure::metrics::METRICS;
+use crate::presentation::discord_exec;
+use crate::presentation::entry::util::{current_unix_timestamp, reaction_emojis};
 use crate::presentation::Data;
+use crate::usecase::on_reaction_add::notify_completion::{
+    self, CompletionEvent, NotifyCompletionInput,
+};
 
-enum ReactionKind {
+#[derive(Debug, Clone, Copy)]
+pub enum ReactionKind {
     Read,
     Done,
 }
@@ -22,30 +28,113 @@ pub async fn handle(ctx: &serenity::Context, data: &Data, reaction: &serenity::R
         None => return,
     };
 
+    record(ctx, data, reaction.message_id.get(), user_id.get(), kind).await;
+}
+
+/// 既読・解決を記録し、新たに記録できた場合は送信者への完了通知を判定する。
+pub async fn record(
+    ctx: &serenity::Context,
+    data: &Data,
+    message_id: u64,
+    user_id: u64,
+    kind: ReactionKind,
+) {
     let now_unix = current_unix_timestamp();
-    let message_id = reaction.message_id.get();
-    let user_id_raw = user_id.get();
+    let (recorded, event) = match kind {
+        ReactionKind::Read => (
+            data.db
+                .record_read(message_id, user_id, now_unix, false)
+                .await,
+            CompletionEvent::Read,
+        ),
+        ReactionKind::Done => (
+            data.db
+                .record_done(message_id, user_id, now_unix, false)
+                .await,
+            CompletionEvent::Done,
+        ),
+    };
+    match recorded {
+        Ok(true) => {
+            count_recorded(kind);
+            notify_author_if_completed(ctx, data, message_id, user_id, event, now_unix).await;
+        }
+        Ok(false) => {}
+        Err(err) => tracing::error!("failed to record {:?} reaction: {:?}", event, err),
+    }
+}
 
-    match kind {
+/// 停止中や導入前に付いたリアクションを記録する。新たに記録した場合は `true` を返す。
+/// 付けた時刻は取得できないため記録した時刻で残し、統計の経過時間には含めない。
+/// 完了通知は送らない。届くのが遅れた通知に誤った所要時間を載せないため。
+pub async fn record_past(data: &Data, message_id: u64, user_id: u64, kind: ReactionKind) -> bool {
+    let now_unix = current_unix_timestamp();
+    let recorded = match kind {
         ReactionKind::Read => {
-            if let Err(err) = data.db.record_read(message_id, user_id_raw, now_unix).await {
-                tracing::error!("failed to record read reaction: {:?}", err);
-            }
+            data.db
+                .record_read(message_id, user_id, now_unix, true)
+                .await
         }
         ReactionKind::Done => {
-            if let Err(err) = data.db.record_done(message_id, user_id_raw, now_unix).await {
-                tracing::error!("failed to record done reaction: {:?}", err);
-            }
+            data.db
+                .record_done(message_id, user_id, now_unix, true)
+                .await
+        }
+    };
+    match recorded {
+        Ok(true) => {
+            count_recorded(kind);
+            true
+        }
+        Ok(false) => false,
+        Err(err) => {
+            tracing::error!("failed to record past {:?} reaction: {:?}", kind, err);
+            false
         }
     }
 }
 
-fn reaction_kind(emoji: &serenity::ReactionType) -> Option<ReactionKind> {
+/// 記録した既読・解決をメトリクスに数える。
+pub fn count_recorded(kind: ReactionKind) {
+    match kind {
+        ReactionKind::Read => METRICS.inc_reads_recorded(),
+        ReactionKind::Done => METRICS.inc_dones_recorded(),
+    }
+}
+
+async fn notify_author_if_completed(
+    ctx: &serenity::Context,
+    data: &Data,
+    message_id: u64,
+    user_id: u64,
+    event: CompletionEvent,
+    now_unix: i64,
+) {
+    let input = NotifyCompletionInput {
+        message_id,
+        user_id,
+        event,
+        now_unix,
+    };
+    let plan = match notify_completion::execute(&data.db, input).await {
+        Ok(plan) => plan,
+        Err(err) => {
+            tracing::error!("failed to build completion notification: {:?}", err);
+            return;
+        }
+    };
+    if let Err(err) = discord_exec::execute(ctx, plan).await {
+        tracing::error!("failed to send completion notification: {:?}", err);
+    }
+}
+
+pub fn reaction_kind(emoji: &serenity::ReactionType) -> Option<ReactionKind> {
+    let emojis = reaction_emojis();
     match emoji {
-        serenity::ReactionType::Custom { id, .. } if id.get() == KIDOKU_EMOJI_ID => {
+        serenity::ReactionType::Custom { id, .. } if id.get() == emojis.kidoku_id => {
             Some(ReactionKind::Read)
         }
-        serenity::ReactionType::Custom { id, .. } if id.get() == DONE_EMOJI_ID => {
+        serenity::ReactionType::Custom { id, .. } if id.get() == emojis.done_id => {
             Some(ReactionKind::Done)
         }
         _ => None,
@@ -0,0 +1,137 @@
+use poise::serenity_prelude as serenity;
+use tracing::Instrument as _;
+
+use crate::infrastructure::db::ChannelCursor;
+use crate::presentation::entry::on_message;
+use crate::presentation::entry::on_reaction_add::{self, ReactionKind};
+use crate::presentation::entry::util::{
+    current_unix_timestamp, done_reaction, fetch_history_page, fetch_reaction_users,
+    kidoku_reaction,
+};
+use crate::presentation::Data;
+use crate::usecase::batch::reconcile::{
+    self, MAX_BACKFILL_AGE_SECS, MAX_BACKFILL_MESSAGES_PER_CHANNEL, MAX_RECONCILE_MENTIONS,
+};
+
+/// 停止中に取りこぼしたメンションとリアクションを補完する。
+/// 再接続のたびに届くため、イベント処理を止めないよう別タスクで実行する。
+pub fn handle(ctx: &serenity::Context, data: &Data) {
+    if !data.config.features.startup_reconcile {
+        return;
+    }
+    let ctx = ctx.clone();
+    let data = data.clone();
+    // Ready イベントのスパンを引き継ぎ、補完中のログを起動時の処理として追えるようにする。
+    tokio::spawn(
+        async move {
+            let Some(_in_flight) = data.shutdown.track() else {
+                return;
+            };
+            let since_unix = current_unix_timestamp() - MAX_BACKFILL_AGE_SECS;
+            backfill_messages(&ctx, &data).await;
+            reconcile_reactions(&ctx, &data, since_unix).await;
+        }
+        .in_current_span(),
+    );
+}
+
+/// メンションを記録したチャンネルごとに、最後に記録したメッセージより新しいものを取り込む。
+/// 取り込む範囲は `reconcile::backfill_after` で直近に限る。
+async fn backfill_messages(ctx: &serenity::Context, data: &Data) {
+    let cursors = match data.db.fetch_channel_cursors().await {
+        Ok(cursors) => cursors,
+        Err(err) => {
+            tracing::error!("failed to fetch channel cursors for backfill: {:?}", err);
+            return;
+        }
+    };
+
+    let mut total = 0;
+    for cursor in cursors {
+        // 停止中ならチャンネルの区切りで止める。残りは次回起動時に補完する。
+        if data.shutdown.is_stopping() {
+            break;
+        }
+        total += backfill_channel(ctx, data, cursor).await;
+    }
+    if total > 0 {
+        tracing::info!("backfilled {} messages sent while offline", total);
+    }
+}
+
+async fn backfill_channel(ctx: &serenity::Context, data: &Data, cursor: ChannelCursor) -> usize {
+    let channel_id = serenity::ChannelId::new(cursor.channel_id);
+    let guild_id = serenity::GuildId::new(cursor.guild_id);
+    let mut after = reconcile::backfill_after(cursor.last_message_id, current_unix_timestamp());
+    let mut processed = 0;
+
+    while processed < MAX_BACKFILL_MESSAGES_PER_CHANNEL {
+        let page = match fetch_history_page(
+            ctx,
+            &data.db,
+            guild_id,
+            channel_id,
+            serenity::MessageId::new(after),
+        )
+        .await
+        {
+            Ok(page) => page,
+            Err(err) => {
+                tracing::warn!(
+                    "failed to fetch messages for backfill in channel {}: {:?}",
+                    cursor.channel_id,
+                    err
+                );
+                break;
+            }
+        };
+        if let Some(last_id) = page.last_id {
+            after = after.max(last_id.get());
+        }
+
+        for message in page.messages {
+            on_message::handle(ctx, data, &message).await;
+            processed += 1;
+        }
+        if page.is_last {
+            break;
+        }
+    }
+    processed
+}
+
+/// 未解決メンションに付いている KIDOKU・DONE リアクションを読み直し、既読・解決の記録に反映する。
+/// 付いた時刻は分からないため、統計の経過時間に含めず、完了通知も送らない。
+async fn reconcile_reactions(ctx: &serenity::Context, data: &Data, since_unix: i64) {
+    let mentions = match data
+        .db
+        .fetch_open_mention_messages(since_unix, MAX_RECONCILE_MENTIONS)
+        .await
+    {
+        Ok(mentions) => mentions,
+        Err(err) => {
+            tracing::error!(
+                "failed to fetch open mentions for reconciliation: {:?}",
+                err
+            );
+            return;
+        }
+    };
+
+    for mention in mentions {
+        if data.shutdown.is_stopping() {
+            break;
+        }
+        let channel_id = serenity::ChannelId::new(mention.channel_id);
+        let message_id = serenity::MessageId::new(mention.message_id);
+        for (reaction, kind) in [
+            (kidoku_reaction(), ReactionKind::Read),
+            (done_reaction(), ReactionKind::Done),
+        ] {
+            let users = fetch_reaction_users(ctx, channel_id, message_id, reaction).await;
+            for user_id in users {
+                on_reaction_add::record_past(data, mention.message_id, user_id.get(), kind).await;
+            }
+        }
+    }
+}
@@ -0,0 +1,27 @@
+use crate::presentation::{Context, Error};
+
+/// サーバー管理者向けの設定をまとめたコマンド。
+///
+/// 優先度はチャンネル管理権限で扱えるため、表示はチャンネル管理権限に合わせ、
+/// それ以外はサブコマンドごとの `required_permissions` でサーバー管理権限を確認する。
+#[poise::command(
+    slash_command,
+    guild_only,
+    rename = "管理設定",
+    subcommands(
+        "super::report_settings::main",
+        "super::completion_notify::server",
+        "super::escalation_settings::main",
+        "super::priority_settings::main",
+        "super::tracking_scope::main",
+        "super::retention_settings::main",
+        "super::content_storage_settings::main",
+        "super::personal_data::admin",
+        "super::import::main"
+    ),
+    subcommand_required,
+    default_member_permissions = "MANAGE_CHANNELS"
+)]
+pub async fn main(_ctx: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
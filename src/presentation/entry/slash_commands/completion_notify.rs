@@ -0,0 +1,94 @@
+use crate::presentation::entry::util::reply_ephemeral;
+use crate::presentation::{Context, Error};
+use crate::usecase::dto::repository::AllReadNotifyMode;
+
+#[derive(Debug, Clone, Copy, poise::ChoiceParameter)]
+pub enum ModeChoice {
+    #[name = "オフ"]
+    Off,
+    #[name = "DM"]
+    Dm,
+    #[name = "元メッセージへ返信"]
+    Reply,
+}
+
+#[derive(Debug, Clone, Copy, poise::ChoiceParameter)]
+pub enum UserModeChoice {
+    #[name = "サーバーの既定に従う"]
+    Default,
+    #[name = "オフ"]
+    Off,
+    #[name = "DM"]
+    Dm,
+    #[name = "元メッセージへ返信"]
+    Reply,
+}
+
+impl From<ModeChoice> for AllReadNotifyMode {
+    fn from(choice: ModeChoice) -> Self {
+        match choice {
+            ModeChoice::Off => AllReadNotifyMode::Off,
+            ModeChoice::Dm => AllReadNotifyMode::Dm,
+            ModeChoice::Reply => AllReadNotifyMode::Reply,
+        }
+    }
+}
+
+/// 自分が送ったメンションを全員が既読・解決したときの通知方法を設定する。
+#[poise::command(slash_command, rename = "完了通知")]
+pub async fn me(
+    ctx: Context<'_>,
+    #[description = "通知方法"] mode: UserModeChoice,
+) -> Result<(), Error> {
+    let mode = match mode {
+        UserModeChoice::Default => None,
+        UserModeChoice::Off => Some(AllReadNotifyMode::Off),
+        UserModeChoice::Dm => Some(AllReadNotifyMode::Dm),
+        UserModeChoice::Reply => Some(AllReadNotifyMode::Reply),
+    };
+    ctx.data()
+        .db
+        .upsert_user_all_read_notify(ctx.author().id.get(), mode)
+        .await?;
+
+    let content = match mode {
+        None => "完了通知はサーバーの既定に従います。".to_string(),
+        Some(mode) => format!("完了通知を「{}」に設定しました。", mode_label(mode)),
+    };
+    reply_ephemeral(ctx, content).await
+}
+
+/// サーバー内の送信者が個別に設定していない場合の既定値を設定する。
+#[poise::command(
+    slash_command,
+    guild_only,
+    rename = "完了通知",
+    required_permissions = "MANAGE_GUILD"
+)]
+pub async fn server(
+    ctx: Context<'_>,
+    #[description = "通知方法"] mode: ModeChoice,
+) -> Result<(), Error> {
+    let Some(guild_id) = ctx.guild_id() else {
+        return Ok(());
+    };
+    let mode = AllReadNotifyMode::from(mode);
+    ctx.data()
+        .db
+        .upsert_guild_all_read_notify(guild_id.get(), mode)
+        .await?;
+
+    let content = format!(
+        "このサーバーの完了通知の既定を「{}」に設定しました。",
+        mode_label(mode)
+    );
+    reply_ephemeral(ctx, content).await
+}
+
+fn mode_label(mode: AllReadNotifyMode) -> &'static str {
+    match mode {
+        AllReadNotifyMode::Off => "オフ",
+        AllReadNotifyMode::Dm => "DM",
+        AllReadNotifyMode::Reply => "元メッセージへ返信",
+    }
+}
@@ -0,0 +1,72 @@
+use crate::domain::model::ContentStorageMode;
+use crate::presentation::entry::util::reply_ephemeral;
+use crate::presentation::{Context, Error};
+
+#[derive(Debug, Clone, Copy, poise::ChoiceParameter)]
+pub enum ModeChoice {
+    #[name = "全文を保存"]
+    Full,
+    #[name = "先頭100文字だけ保存"]
+    Snippet,
+    #[name = "保存しない（表示時に取得）"]
+    None,
+    #[name = "暗号化して保存"]
+    Encrypted,
+}
+
+impl From<ModeChoice> for ContentStorageMode {
+    fn from(choice: ModeChoice) -> Self {
+        match choice {
+            ModeChoice::Full => ContentStorageMode::Full,
+            ModeChoice::Snippet => ContentStorageMode::Snippet,
+            ModeChoice::None => ContentStorageMode::None,
+            ModeChoice::Encrypted => ContentStorageMode::Encrypted,
+        }
+    }
+}
+
+/// メンションを記録するときにメッセージ本文をどう保存するかを設定する。
+#[poise::command(
+    slash_command,
+    guild_only,
+    rename = "本文保存",
+    required_permissions = "MANAGE_GUILD"
+)]
+pub async fn main(
+    ctx: Context<'_>,
+    #[description = "本文の保存方式"] mode: ModeChoice,
+) -> Result<(), Error> {
+    let Some(guild_id) = ctx.guild_id() else {
+        return Ok(());
+    };
+
+    let mode = ContentStorageMode::from(mode);
+    if mode == ContentStorageMode::Encrypted && !ctx.data().db.has_content_cipher() {
+        return reply_ephemeral(
+            ctx,
+            "暗号鍵（CONTENT_ENCRYPTION_KEY）が設定されていないため、暗号化を選べません。Bot の管理者に確認してください。"
+                .to_string(),
+        )
+        .await;
+    }
+
+    ctx.data()
+        .db
+        .upsert_content_storage_mode(guild_id.get(), mode)
+        .await?;
+
+    let content = format!(
+        "本文の保存方式を「{}」に設定しました。これから記録するメンションに適用され、記録済みの本文は変わりません。",
+        label(mode)
+    );
+    reply_ephemeral(ctx, content).await
+}
+
+fn label(mode: ContentStorageMode) -> &'static str {
+    match mode {
+        ContentStorageMode::Full => "全文を保存",
+        ContentStorageMode::Snippet => "先頭100文字だけ保存",
+        ContentStorageMode::None => "保存しない",
+        ContentStorageMode::Encrypted => "暗号化して保存",
+    }
+}
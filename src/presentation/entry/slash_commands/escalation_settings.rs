@@ -0,0 +1,44 @@
+use crate::presentation::entry::util::reply_ephemeral;
+use crate::presentation::{Context, Error};
+
+/// 未読が続くメンションを送信者へ知らせ、未読者へ再メンションするまでの時間を設定する。
+#[poise::command(
+    slash_command,
+    guild_only,
+    rename = "エスカレーション",
+    required_permissions = "MANAGE_GUILD"
+)]
+pub async fn main(
+    ctx: Context<'_>,
+    #[description = "未読が続いたら送信者へDMするまでの時間（省略で無効）"]
+    #[min = 1]
+    #[max = 720]
+    author_after_hours: Option<i32>,
+    #[description = "未読者を元メッセージのスレッドで再メンションするまでの時間（省略で無効）"]
+    #[min = 1]
+    #[max = 720]
+    reping_after_hours: Option<i32>,
+) -> Result<(), Error> {
+    let Some(guild_id) = ctx.guild_id() else {
+        return Ok(());
+    };
+
+    ctx.data()
+        .db
+        .upsert_escalation_settings(guild_id.get(), author_after_hours, reping_after_hours)
+        .await?;
+
+    let content = format!(
+        "エスカレーションを設定しました。\n送信者へのDM: {}\n未読者への再メンション: {}",
+        format_hours(author_after_hours),
+        format_hours(reping_after_hours)
+    );
+    reply_ephemeral(ctx, content).await
+}
+
+fn format_hours(hours: Option<i32>) -> String {
+    match hours {
+        Some(hours) => format!("{}時間後", hours),
+        None => "無効".to_string(),
+    }
+}
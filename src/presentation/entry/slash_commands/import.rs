@@ -0,0 +1,184 @@
+use std::time::Duration;
+
+use poise::serenity_prelude as serenity;
+
+use crate::presentation::entry::on_message;
+use crate::presentation::entry::on_reaction_add::{self, reaction_kind, ReactionKind};
+use crate::presentation::entry::util::{
+    current_unix_timestamp, fetch_history_page, fetch_reaction_users, reply_ephemeral,
+};
+use crate::presentation::{Context, Error};
+use crate::usecase::batch::reconcile::snowflake_at;
+use crate::usecase::slash_commands::import::{
+    self as import_usecase, ImportProgress, ImportStatus,
+};
+
+/// ページの間に空ける時間。ライブのイベント処理とレート制限を分け合うため間隔を置く。
+const PAGE_INTERVAL: Duration = Duration::from_secs(1);
+
+/// チャンネルの過去のメッセージを読み込み、メンションと既存の KIDOKU・DONE リアクションを記録する。
+#[poise::command(
+    slash_command,
+    guild_only,
+    rename = "インポート",
+    required_permissions = "MANAGE_GUILD"
+)]
+pub async fn main(
+    ctx: Context<'_>,
+    #[description = "取り込むチャンネル"] channel: serenity::GuildChannel,
+    #[description = "この日以降のメッセージを取り込む YYYY-MM-DD"] since: String,
+    #[description = "取り込んだメッセージに KIDOKU・DONE を付ける（既定: 付けない）"]
+    add_reactions: Option<bool>,
+) -> Result<(), Error> {
+    let Some(guild_id) = ctx.guild_id() else {
+        return Ok(());
+    };
+    let since_unix = match import_usecase::parse_since(&since, current_unix_timestamp()) {
+        Ok(since_unix) => since_unix,
+        Err(message) => return reply_ephemeral(ctx, message.to_string()).await,
+    };
+
+    reply_ephemeral(
+        ctx,
+        "取り込みを開始しました。進み具合はこのチャンネルのメッセージで確認できます。".to_string(),
+    )
+    .await?;
+
+    let http = ctx.serenity_context().http.clone();
+    let mut progress = ImportProgress::default();
+    let mut status = ctx
+        .channel_id()
+        .send_message(
+            &http,
+            serenity::CreateMessage::new().content(import_usecase::format_progress(
+                channel.id.get(),
+                ImportStatus::Running,
+                &progress,
+            )),
+        )
+        .await?;
+
+    // コマンド実行後のメッセージはライブのイベントで記録されるため、実行時点で止める。
+    let until = serenity::MessageId::new(ctx.id());
+    let mut after = serenity::MessageId::new(snowflake_at(since_unix));
+    let final_status = loop {
+        // 停止中ならページの区切りで止める。取り込み済みの分は再実行しても重複しない。
+        if ctx.data().shutdown.is_stopping() {
+            break ImportStatus::Interrupted;
+        }
+        let page = match fetch_history_page(
+            ctx.serenity_context(),
+            &ctx.data().db,
+            guild_id,
+            channel.id,
+            after,
+        )
+        .await
+        {
+            Ok(page) => page,
+            Err(err) => {
+                tracing::warn!(
+                    "failed to fetch messages for import in channel {}: {:?}",
+                    channel.id.get(),
+                    err
+                );
+                break ImportStatus::Aborted;
+            }
+        };
+        let reached_until = page.last_id.is_some_and(|last_id| last_id >= until);
+        if let Some(last_id) = page.last_id {
+            after = last_id;
+        }
+
+        for message in page.messages.iter().filter(|message| message.id < until) {
+            import_message(ctx, message, add_reactions.unwrap_or(false), &mut progress).await;
+        }
+
+        if page.is_last || reached_until {
+            break ImportStatus::Finished;
+        }
+        edit_status(
+            &http,
+            &mut status,
+            channel.id,
+            ImportStatus::Running,
+            &progress,
+        )
+        .await;
+        tokio::time::sleep(PAGE_INTERVAL).await;
+    };
+
+    edit_status(&http, &mut status, channel.id, final_status, &progress).await;
+    tracing::info!(
+        "インポート終了({:?}): channel={} scanned={} imported={} by={}",
+        final_status,
+        channel.id,
+        progress.scanned,
+        progress.imported,
+        ctx.author().id
+    );
+    Ok(())
+}
+
+async fn import_message(
+    ctx: Context<'_>,
+    message: &serenity::Message,
+    add_reactions: bool,
+    progress: &mut ImportProgress,
+) {
+    progress.scanned += 1;
+    progress.last_message_unix = Some(message.timestamp.unix_timestamp());
+
+    let serenity_ctx = ctx.serenity_context();
+    let Some(priority) = on_message::track(serenity_ctx, ctx.data(), message).await else {
+        return;
+    };
+    progress.imported += 1;
+    if add_reactions {
+        on_message::add_reactions(serenity_ctx, message, priority).await;
+    }
+
+    // 付いていないリアクションのユーザーは取得しない。
+    for reaction in message
+        .reactions
+        .iter()
+        .filter(|reaction| !reaction.me || reaction.count > 1)
+    {
+        let Some(kind) = reaction_kind(&reaction.reaction_type) else {
+            continue;
+        };
+        let users = fetch_reaction_users(
+            serenity_ctx,
+            message.channel_id,
+            message.id,
+            reaction.reaction_type.clone(),
+        )
+        .await;
+        for user_id in users {
+            let recorded =
+                on_reaction_add::record_past(ctx.data(), message.id.get(), user_id.get(), kind)
+                    .await;
+            match (recorded, kind) {
+                (true, ReactionKind::Read) => progress.reads += 1,
+                (true, ReactionKind::Done) => progress.dones += 1,
+                (false, _) => {}
+            }
+        }
+    }
+}
+
+async fn edit_status(
+    http: &serenity::Http,
+    status: &mut serenity::Message,
+    channel_id: serenity::ChannelId,
+    import_status: ImportStatus,
+    progress: &ImportProgress,
+) {
+    let content = import_usecase::format_progress(channel_id.get(), import_status, progress);
+    if let Err(err) = status
+        .edit(http, serenity::EditMessage::new().content(content))
+        .await
+    {
+        tracing::warn!("failed to update import status message: {:?}", err);
+    }
+}
@@ -1,9 +1,33 @@
+pub mod admin_settings;
+pub mod completion_notify;
+pub mod content_storage_settings;
+pub mod escalation_settings;
 pub mod help;
+pub mod import;
 pub mod my_mentions;
+pub mod notify_settings;
+pub mod personal_data;
+pub mod priority_settings;
+pub mod report_settings;
+pub mod retention_settings;
+pub mod stats;
+pub mod track_message;
+pub mod tracking_scope;
 pub mod view_read_status;
 
 use crate::presentation::{Data, Error};
 
 pub fn all() -> Vec<poise::Command<Data, Error>> {
-    vec![help::main(), view_read_status::main(), my_mentions::main()]
+    vec![
+        help::main(),
+        view_read_status::main(),
+        track_message::start(),
+        track_message::stop(),
+        my_mentions::main(),
+        stats::main(),
+        completion_notify::me(),
+        notify_settings::main(),
+        personal_data::main(),
+        admin_settings::main(),
+    ]
 }
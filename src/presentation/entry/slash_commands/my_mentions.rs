@@ -1,11 +1,13 @@
-use chrono::{DateTime, Utc};
 use poise::serenity_prelude as serenity;
 
-use crate::infrastructure::db::{Db, MentionForTarget};
-use crate::presentation::entry::util::truncate;
+use crate::infrastructure::db::Db;
+use crate::presentation::discord_exec;
+use crate::presentation::entry::util::current_unix_timestamp;
 use crate::presentation::{Context, Error};
+use crate::usecase::dto::repository::MentionForTarget;
+use crate::usecase::slash_commands::my_mentions::{self as my_mentions_usecase, ListOptions};
 
-const PAGE_SIZE: usize = 5;
+pub const PAGE_SIZE: usize = 5;
 const UNKNOWN_CHANNEL_CODE: isize = 10003;
 const UNKNOWN_MESSAGE_CODE: isize = 10008;
 
@@ -13,8 +15,12 @@ const UNKNOWN_MESSAGE_CODE: isize = 10008;
 pub async fn main(
     ctx: Context<'_>,
     #[description = "解決済みも表示する"] show_done: Option<bool>,
+    #[description = "期限が近い順に並べる"] sort_by_due: Option<bool>,
 ) -> Result<(), Error> {
-    let show_done = show_done.unwrap_or(false);
+    let options = ListOptions {
+        show_done: show_done.unwrap_or(false),
+        sort_by_due: sort_by_due.unwrap_or(false),
+    };
     let is_ephemeral = ctx.guild_id().is_some();
     let user_id = ctx.author().id;
 
@@ -31,7 +37,7 @@ pub async fn main(
         ctx.serenity_context(),
         user_id.get(),
         0,
-        show_done,
+        options,
     )
     .await?;
 
@@ -47,16 +53,20 @@ pub async fn main(
 
     let has_next = items.len() > PAGE_SIZE;
     let page_items = &items[..items.len().min(PAGE_SIZE)];
-    let guild_id = ctx.guild_id();
+    let guild_id = ctx.guild_id().map(|g| g.get());
 
-    let embeds = build_embeds(page_items, guild_id);
-    let components = build_nav_buttons(0, show_done, user_id.get(), false, has_next);
-
-    let mut reply = poise::CreateReply::default()
-        .components(components)
-        .ephemeral(is_ephemeral);
-    reply.embeds = embeds;
-    ctx.send(reply).await?;
+    let mut payload = my_mentions_usecase::build_page(
+        page_items,
+        guild_id,
+        0,
+        options,
+        user_id.get(),
+        has_next,
+        current_unix_timestamp(),
+    );
+    payload.ephemeral = Some(is_ephemeral);
+    ctx.send(discord_exec::build_interaction_payload(payload)?)
+        .await?;
     Ok(())
 }
 
@@ -65,7 +75,7 @@ pub async fn fetch_page(
     serenity_ctx: &serenity::Context,
     user_id: u64,
     page: usize,
-    show_done: bool,
+    options: ListOptions,
 ) -> Result<Vec<MentionForTarget>, Error> {
     let mut offset = (page * PAGE_SIZE) as i64;
     let fetch_limit = (PAGE_SIZE + 1) as i64;
@@ -73,7 +83,13 @@ pub async fn fetch_page(
 
     loop {
         let fetched = db
-            .fetch_mentions_for_target(user_id, offset, fetch_limit, show_done)
+            .fetch_mentions_for_target(
+                user_id,
+                offset,
+                fetch_limit,
+                options.show_done,
+                options.sort_by_due,
+            )
             .await?;
 
         if fetched.is_empty() {
@@ -83,8 +99,8 @@ pub async fn fetch_page(
         let fetched_len = fetched.len() as i64;
         offset += fetched_len;
 
-        for item in fetched {
-            if should_skip_and_cleanup_item(db, serenity_ctx, user_id, &item).await {
+        for mut item in fetched {
+            if should_skip_and_cleanup_item(db, serenity_ctx, user_id, &mut item).await {
                 continue;
             }
             valid_items.push(item);
@@ -105,7 +121,7 @@ async fn should_skip_and_cleanup_item(
     db: &Db,
     serenity_ctx: &serenity::Context,
     user_id: u64,
-    item: &MentionForTarget,
+    item: &mut MentionForTarget,
 ) -> bool {
     if is_deleted_message_item(serenity_ctx, item).await {
         match db.delete_mention_by_message_id(item.message_id).await {
@@ -155,14 +171,20 @@ async fn should_skip_and_cleanup_item(
     false
 }
 
+/// 本文を保存しない設定のギルドでは、取得したメッセージの本文を表示用に埋める。
 async fn is_deleted_message_item(
     serenity_ctx: &serenity::Context,
-    item: &MentionForTarget,
+    item: &mut MentionForTarget,
 ) -> bool {
     let channel_id = serenity::ChannelId::new(item.channel_id);
     let message_id = serenity::MessageId::new(item.message_id);
     match channel_id.message(serenity_ctx, message_id).await {
-        Ok(_) => false,
+        Ok(message) => {
+            if item.content.is_empty() {
+                item.content = message.content;
+            }
+            false
+        }
         Err(err) if is_unknown_message_or_channel_error(&err) => true,
         Err(err) => {
             tracing::warn!(
@@ -223,71 +245,3 @@ fn is_unknown_message_or_channel_error(err: &serenity::Error) -> bool {
         _ => false,
     }
 }
-
-pub fn build_embeds(
-    items: &[MentionForTarget],
-    guild_id: Option<serenity::GuildId>,
-) -> Vec<serenity::CreateEmbed> {
-    items
-        .iter()
-        .map(|item| {
-            let date = DateTime::<Utc>::from_timestamp(item.created_at_unix, 0)
-                .map(|dt| dt.format("%Y-%m-%d").to_string())
-                .unwrap_or_else(|| "不明".to_string());
-
-            let snippet = truncate(&item.content, 100);
-
-            let guild_id_val = guild_id.map(|g| g.get()).unwrap_or(item.guild_id);
-            let message_link = format!(
-                "https://discord.com/channels/{}/{}/{}",
-                guild_id_val, item.channel_id, item.message_id
-            );
-
-            let status = if item.is_done {
-                "解決済み 🔒"
-            } else if item.is_read {
-                "既読 ✅"
-            } else {
-                "未読 ❌"
-            };
-
-            serenity::CreateEmbed::new()
-                .title(format!("メッセージ ({})", date))
-                .description(snippet)
-                .field("送信者", format!("<@{}>", item.author_id), true)
-                .field("状態", status, true)
-                .field("リンク", format!("[開く]({})", message_link), true)
-        })
-        .collect()
-}
-
-pub fn build_nav_buttons(
-    page: usize,
-    show_done: bool,
-    user_id: u64,
-    has_prev: bool,
-    has_next: bool,
-) -> Vec<serenity::CreateActionRow> {
-    let show_done_flag = if show_done { 1u8 } else { 0u8 };
-
-    let prev_button = serenity::CreateButton::new(format!(
-        "mm:p:{}:{}:{}",
-        page.saturating_sub(1),
-        show_done_flag,
-        user_id
-    ))
-    .label("◀ 前へ")
-    .style(serenity::ButtonStyle::Secondary)
-    .disabled(!has_prev);
-
-    let next_button =
-        serenity::CreateButton::new(format!("mm:p:{}:{}:{}", page + 1, show_done_flag, user_id))
-            .label("次へ ▶")
-            .style(serenity::ButtonStyle::Secondary)
-            .disabled(!has_next);
-
-    vec![serenity::CreateActionRow::Buttons(vec![
-        prev_button,
-        next_button,
-    ])]
-}
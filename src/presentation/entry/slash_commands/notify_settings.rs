@@ -0,0 +1,171 @@
+use poise::serenity_prelude as serenity;
+
+use crate::presentation::entry::util::reply_ephemeral;
+use crate::presentation::{Context, Error};
+use crate::usecase::dto::repository::{UserMute, UserNotifySettings};
+
+#[derive(Debug, Clone, Copy, poise::ChoiceParameter)]
+pub enum DigestChoice {
+    #[name = "受け取る"]
+    Enabled,
+    #[name = "受け取らない"]
+    Disabled,
+}
+
+#[derive(Debug, Clone, Copy, poise::ChoiceParameter)]
+pub enum TrackingChoice {
+    #[name = "追跡する"]
+    Track,
+    #[name = "追跡しない"]
+    OptOut,
+}
+
+#[derive(Debug, Clone, Copy, poise::ChoiceParameter)]
+pub enum MuteChoice {
+    #[name = "ミュート"]
+    Mute,
+    #[name = "解除"]
+    Unmute,
+}
+
+#[poise::command(
+    slash_command,
+    rename = "通知設定",
+    subcommands("digest", "tracking", "mute_channel", "mute_author", "show"),
+    subcommand_required
+)]
+pub async fn main(_ctx: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// 週次の未読通知と月次の期限切れ通知の DM を受け取るかどうかを設定する。
+#[poise::command(slash_command, rename = "定期dm")]
+pub async fn digest(
+    ctx: Context<'_>,
+    #[description = "週次・月次の DM"] mode: DigestChoice,
+) -> Result<(), Error> {
+    let disabled = matches!(mode, DigestChoice::Disabled);
+    ctx.data()
+        .db
+        .update_user_digest_dm_disabled(ctx.author().id.get(), disabled)
+        .await?;
+
+    let content = if disabled {
+        "週次・月次の DM を停止しました。"
+    } else {
+        "週次・月次の DM を再開しました。"
+    };
+    reply_ephemeral(ctx, content.to_string()).await
+}
+
+/// 自分をメンションの追跡対象に含めるかどうかを設定する。
+#[poise::command(slash_command, rename = "追跡")]
+pub async fn tracking(
+    ctx: Context<'_>,
+    #[description = "自分宛てのメンションを追跡するか"] mode: TrackingChoice,
+) -> Result<(), Error> {
+    let opt_out = matches!(mode, TrackingChoice::OptOut);
+    ctx.data()
+        .db
+        .update_user_tracking_opt_out(ctx.author().id.get(), opt_out)
+        .await?;
+
+    let content = if opt_out {
+        "今後あなた宛てのメンションは記録せず、既読一覧やリマインダーにも表示しません。"
+    } else {
+        "あなた宛てのメンションの追跡を再開しました。"
+    };
+    reply_ephemeral(ctx, content.to_string()).await
+}
+
+/// チャンネルのメンションについて DM・リマインダー・一覧表示を止める。
+#[poise::command(slash_command, guild_only, rename = "チャンネルミュート")]
+pub async fn mute_channel(
+    ctx: Context<'_>,
+    #[description = "対象のチャンネル"] channel: serenity::GuildChannel,
+    #[description = "ミュートするか解除するか"] action: MuteChoice,
+) -> Result<(), Error> {
+    let content = apply_mute(ctx, UserMute::Channel(channel.id.get()), action).await?;
+    reply_ephemeral(ctx, content).await
+}
+
+/// 特定の送信者からのメンションについて DM・リマインダー・一覧表示を止める。
+#[poise::command(slash_command, rename = "送信者ミュート")]
+pub async fn mute_author(
+    ctx: Context<'_>,
+    #[description = "対象の送信者"] user: serenity::User,
+    #[description = "ミュートするか解除するか"] action: MuteChoice,
+) -> Result<(), Error> {
+    let content = apply_mute(ctx, UserMute::Author(user.id.get()), action).await?;
+    reply_ephemeral(ctx, content).await
+}
+
+/// 現在の通知設定を表示する。
+#[poise::command(slash_command, rename = "確認")]
+pub async fn show(ctx: Context<'_>) -> Result<(), Error> {
+    let settings = ctx
+        .data()
+        .db
+        .fetch_user_notify_settings(ctx.author().id.get())
+        .await?;
+    reply_ephemeral(ctx, format_settings(&settings)).await
+}
+
+async fn apply_mute(ctx: Context<'_>, mute: UserMute, action: MuteChoice) -> Result<String, Error> {
+    let db = &ctx.data().db;
+    let user_id = ctx.author().id.get();
+    let content = match action {
+        MuteChoice::Mute => {
+            if db.insert_user_mute(user_id, mute).await? {
+                format!("{} をミュートしました。", mute_label(mute))
+            } else {
+                format!("{} は既にミュートしています。", mute_label(mute))
+            }
+        }
+        MuteChoice::Unmute => {
+            if db.delete_user_mute(user_id, mute).await? > 0 {
+                format!("{} のミュートを解除しました。", mute_label(mute))
+            } else {
+                format!("{} はミュートしていません。", mute_label(mute))
+            }
+        }
+    };
+    Ok(content)
+}
+
+fn format_settings(settings: &UserNotifySettings) -> String {
+    let mutes = if settings.mutes.is_empty() {
+        "なし".to_string()
+    } else {
+        settings
+            .mutes
+            .iter()
+            .map(|mute| mute_label(*mute))
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+    format!(
+        "**通知設定**\n\
+         - 週次・月次の DM: {}\n\
+         - 追跡: {}\n\
+         - ミュート: {}",
+        if settings.digest_dm_disabled {
+            "受け取らない"
+        } else {
+            "受け取る"
+        },
+        if settings.tracking_opt_out {
+            "追跡しない"
+        } else {
+            "追跡する"
+        },
+        mutes
+    )
+}
+
+fn mute_label(mute: UserMute) -> String {
+    match mute {
+        UserMute::Channel(id) => format!("<#{}>", id),
+        UserMute::Author(id) => format!("<@{}>", id),
+    }
+}
@@ -0,0 +1,174 @@
+use poise::serenity_prelude as serenity;
+
+use crate::presentation::entry::util::{current_unix_timestamp, reply_ephemeral};
+use crate::presentation::{Context, Error};
+use crate::usecase::slash_commands::personal_data::{
+    self as personal_data_usecase, DeleteInput, ExportFormat, ExportInput,
+};
+
+#[derive(Debug, Clone, Copy, poise::ChoiceParameter)]
+pub enum FormatChoice {
+    #[name = "JSON"]
+    Json,
+    #[name = "CSV"]
+    Csv,
+}
+
+impl From<FormatChoice> for ExportFormat {
+    fn from(choice: FormatChoice) -> Self {
+        match choice {
+            FormatChoice::Json => ExportFormat::Json,
+            FormatChoice::Csv => ExportFormat::Csv,
+        }
+    }
+}
+
+#[poise::command(
+    slash_command,
+    rename = "データ",
+    subcommands("export", "delete"),
+    subcommand_required
+)]
+pub async fn main(_ctx: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// 自分について保存しているデータをファイルで受け取る。
+#[poise::command(slash_command)]
+pub async fn export(
+    ctx: Context<'_>,
+    #[description = "ファイル形式（既定: JSON）"] format: Option<FormatChoice>,
+) -> Result<(), Error> {
+    send_export(ctx, ctx.author().id.get(), None, format).await
+}
+
+/// 自分について保存しているデータを削除する。追跡の停止とミュートの設定は残す。
+#[poise::command(slash_command)]
+pub async fn delete(
+    ctx: Context<'_>,
+    #[description = "削除すると元に戻せません。True を選んで確定します"] confirm: bool,
+) -> Result<(), Error> {
+    run_delete(ctx, ctx.author().id.get(), None, confirm).await
+}
+
+/// メンバーについてこのサーバーで保存しているデータを扱う。
+#[poise::command(
+    slash_command,
+    guild_only,
+    rename = "データ",
+    subcommands("admin_export", "admin_delete"),
+    subcommand_required,
+    required_permissions = "MANAGE_GUILD"
+)]
+pub async fn admin(_ctx: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// メンバーについてこのサーバーで保存しているデータを書き出す。脱退したメンバーは ID で指定する。
+#[poise::command(
+    slash_command,
+    guild_only,
+    rename = "export",
+    required_permissions = "MANAGE_GUILD"
+)]
+pub async fn admin_export(
+    ctx: Context<'_>,
+    #[description = "対象のユーザー ID"] user_id: String,
+    #[description = "ファイル形式（既定: JSON）"] format: Option<FormatChoice>,
+) -> Result<(), Error> {
+    let Some(guild_id) = ctx.guild_id() else {
+        return Ok(());
+    };
+    let Some(user_id) = parse_user_id(&user_id) else {
+        return reply_ephemeral(ctx, "ユーザー ID を数字で指定してください。".to_string()).await;
+    };
+    send_export(ctx, user_id, Some(guild_id), format).await
+}
+
+/// メンバーについてこのサーバーで保存しているデータを削除する。脱退したメンバーは ID で指定する。
+#[poise::command(
+    slash_command,
+    guild_only,
+    rename = "delete",
+    required_permissions = "MANAGE_GUILD"
+)]
+pub async fn admin_delete(
+    ctx: Context<'_>,
+    #[description = "対象のユーザー ID"] user_id: String,
+    #[description = "削除すると元に戻せません。True を選んで確定します"] confirm: bool,
+) -> Result<(), Error> {
+    let Some(guild_id) = ctx.guild_id() else {
+        return Ok(());
+    };
+    let Some(user_id) = parse_user_id(&user_id) else {
+        return reply_ephemeral(ctx, "ユーザー ID を数字で指定してください。".to_string()).await;
+    };
+    run_delete(ctx, user_id, Some(guild_id), confirm).await
+}
+
+async fn send_export(
+    ctx: Context<'_>,
+    user_id: u64,
+    guild_id: Option<serenity::GuildId>,
+    format: Option<FormatChoice>,
+) -> Result<(), Error> {
+    ctx.defer_ephemeral().await?;
+
+    let input = ExportInput {
+        user_id,
+        guild_id: guild_id.map(|guild_id| guild_id.get()),
+        format: format.unwrap_or(FormatChoice::Json).into(),
+        now_unix: current_unix_timestamp(),
+    };
+    let output = personal_data_usecase::export(&ctx.data().db, input).await?;
+
+    ctx.send(
+        poise::CreateReply::default()
+            .content(output.content)
+            .attachment(serenity::CreateAttachment::bytes(
+                output.body.into_bytes(),
+                output.filename,
+            ))
+            .ephemeral(true),
+    )
+    .await?;
+    Ok(())
+}
+
+async fn run_delete(
+    ctx: Context<'_>,
+    user_id: u64,
+    guild_id: Option<serenity::GuildId>,
+    confirm: bool,
+) -> Result<(), Error> {
+    if !confirm {
+        return reply_ephemeral(
+            ctx,
+            "削除を中止しました。実行するには confirm に True を指定してください。".to_string(),
+        )
+        .await;
+    }
+
+    let input = DeleteInput {
+        user_id,
+        guild_id: guild_id.map(|guild_id| guild_id.get()),
+    };
+    let content = personal_data_usecase::delete(&ctx.data().db, input).await?;
+    tracing::info!(
+        "個人データ削除: user={} guild={:?} by={}",
+        user_id,
+        guild_id,
+        ctx.author().id
+    );
+    reply_ephemeral(ctx, content).await
+}
+
+fn parse_user_id(value: &str) -> Option<u64> {
+    value
+        .trim()
+        .trim_start_matches("<@")
+        .trim_start_matches('!')
+        .trim_end_matches('>')
+        .parse()
+        .ok()
+}
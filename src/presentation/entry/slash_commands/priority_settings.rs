@@ -0,0 +1,63 @@
+use poise::serenity_prelude as serenity;
+
+use crate::domain::model::MentionPriority;
+use crate::presentation::entry::util::reply_ephemeral;
+use crate::presentation::{Context, Error};
+
+#[derive(Debug, Clone, Copy, poise::ChoiceParameter)]
+pub enum PriorityChoice {
+    #[name = "通常"]
+    Normal,
+    #[name = "至急"]
+    Urgent,
+    #[name = "FYI（共有のみ）"]
+    Fyi,
+    #[name = "解除"]
+    Unset,
+}
+
+/// チャンネルで優先度マーカーのないメンションに使う既定の優先度を設定する。
+#[poise::command(
+    slash_command,
+    guild_only,
+    rename = "優先度",
+    required_permissions = "MANAGE_CHANNELS"
+)]
+pub async fn main(
+    ctx: Context<'_>,
+    #[description = "設定するチャンネル"] channel: serenity::GuildChannel,
+    #[description = "マーカーのないメンションの優先度"] priority: PriorityChoice,
+) -> Result<(), Error> {
+    let Some(guild_id) = ctx.guild_id() else {
+        return Ok(());
+    };
+
+    let priority = match priority {
+        PriorityChoice::Normal => Some(MentionPriority::Normal),
+        PriorityChoice::Urgent => Some(MentionPriority::Urgent),
+        PriorityChoice::Fyi => Some(MentionPriority::Fyi),
+        PriorityChoice::Unset => None,
+    };
+    ctx.data()
+        .db
+        .upsert_channel_default_priority(guild_id.get(), channel.id.get(), priority)
+        .await?;
+
+    let content = match priority {
+        Some(priority) => format!(
+            "<#{}> の既定の優先度を「{}」に設定しました。",
+            channel.id.get(),
+            label(priority)
+        ),
+        None => format!("<#{}> の既定の優先度を解除しました。", channel.id.get()),
+    };
+    reply_ephemeral(ctx, content).await
+}
+
+fn label(priority: MentionPriority) -> &'static str {
+    match priority {
+        MentionPriority::Normal => "通常",
+        MentionPriority::Urgent => "至急",
+        MentionPriority::Fyi => "FYI",
+    }
+}
@@ -0,0 +1,38 @@
+use poise::serenity_prelude as serenity;
+
+use crate::presentation::entry::util::reply_ephemeral;
+use crate::presentation::{Context, Error};
+
+/// 毎週のチームレポートの投稿先と、個人名を載せるかを設定する。
+#[poise::command(
+    slash_command,
+    guild_only,
+    rename = "レポート",
+    required_permissions = "MANAGE_GUILD"
+)]
+pub async fn main(
+    ctx: Context<'_>,
+    #[description = "レポートの投稿先（省略で停止）"] channel: Option<serenity::GuildChannel>,
+    #[description = "個人名を載せる（既定: true）"] name_users: Option<bool>,
+) -> Result<(), Error> {
+    let Some(guild_id) = ctx.guild_id() else {
+        return Ok(());
+    };
+    let name_users = name_users.unwrap_or(true);
+    let channel_id = channel.as_ref().map(|channel| channel.id.get());
+
+    ctx.data()
+        .db
+        .upsert_report_settings(guild_id.get(), channel_id, name_users)
+        .await?;
+
+    let content = match channel_id {
+        Some(channel_id) => format!(
+            "毎週月曜に <#{}> へチームレポートを投稿します。（個人名: {}）",
+            channel_id,
+            if name_users { "表示" } else { "非表示" }
+        ),
+        None => "チームレポートの投稿を停止しました。".to_string(),
+    };
+    reply_ephemeral(ctx, content).await
+}
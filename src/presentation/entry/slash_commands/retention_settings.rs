@@ -0,0 +1,94 @@
+use crate::presentation::entry::util::{current_unix_timestamp, reply_ephemeral};
+use crate::presentation::{Context, Error};
+use crate::usecase::batch::retention;
+
+/// 解決済みのメンションを保持する期間を設定する。
+#[poise::command(
+    slash_command,
+    guild_only,
+    rename = "保持期間",
+    subcommands("set", "reset", "preview"),
+    subcommand_required,
+    required_permissions = "MANAGE_GUILD"
+)]
+pub async fn main(_ctx: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// 全員が解決または無視したメンションを保持する日数を設定する。
+#[poise::command(
+    slash_command,
+    guild_only,
+    rename = "設定",
+    required_permissions = "MANAGE_GUILD"
+)]
+pub async fn set(
+    ctx: Context<'_>,
+    #[description = "保持する日数（0 で削除しない）"]
+    #[max = 3650]
+    days: u32,
+) -> Result<(), Error> {
+    let Some(guild_id) = ctx.guild_id() else {
+        return Ok(());
+    };
+    ctx.data()
+        .db
+        .upsert_retention_days(guild_id.get(), Some(days))
+        .await?;
+
+    let content = if days == 0 {
+        "このサーバーのメンションは保持期間で削除しないように設定しました。".to_string()
+    } else {
+        format!("全員が解決または無視したメンションを{days}日後に削除するように設定しました。")
+    };
+    reply_ephemeral(ctx, content).await
+}
+
+/// このサーバーの保持期間の設定を解除し、既定値に従う。
+#[poise::command(
+    slash_command,
+    guild_only,
+    rename = "既定に戻す",
+    required_permissions = "MANAGE_GUILD"
+)]
+pub async fn reset(ctx: Context<'_>) -> Result<(), Error> {
+    let Some(guild_id) = ctx.guild_id() else {
+        return Ok(());
+    };
+    ctx.data()
+        .db
+        .upsert_retention_days(guild_id.get(), None)
+        .await?;
+
+    let content = match ctx.data().config.retention_days {
+        Some(days) => format!("保持期間を既定値（{days}日）に戻しました。"),
+        None => "保持期間を既定値（無期限）に戻しました。".to_string(),
+    };
+    reply_ephemeral(ctx, content).await
+}
+
+/// 現在の保持期間と、次回の削除で消えるメンションを確認する（削除はしない）。
+#[poise::command(
+    slash_command,
+    guild_only,
+    rename = "確認",
+    required_permissions = "MANAGE_GUILD"
+)]
+pub async fn preview(ctx: Context<'_>) -> Result<(), Error> {
+    let Some(guild_id) = ctx.guild_id() else {
+        return Ok(());
+    };
+    let data = ctx.data();
+    let guild_days = data.db.fetch_retention_days(guild_id.get()).await?;
+    let preview = data
+        .db
+        .preview_purge(
+            data.config.retention_days,
+            current_unix_timestamp(),
+            Some(guild_id.get()),
+        )
+        .await?;
+
+    let content = retention::format_preview(guild_days, data.config.retention_days, preview);
+    reply_ephemeral(ctx, content).await
+}
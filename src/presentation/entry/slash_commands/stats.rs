@@ -0,0 +1,116 @@
+use anyhow::Context as _;
+use poise::serenity_prelude as serenity;
+
+use crate::presentation::discord_exec;
+use crate::presentation::entry::util::{current_unix_timestamp, reply_ephemeral};
+use crate::presentation::{Context, Error};
+use crate::usecase::slash_commands::stats::{
+    self as stats_usecase, StatsInput, StatsPeriod, StatsScope,
+};
+
+#[derive(Debug, Clone, Copy, poise::ChoiceParameter)]
+pub enum ScopeChoice {
+    #[name = "自分"]
+    Me,
+    #[name = "ユーザー"]
+    User,
+    #[name = "ロール"]
+    Role,
+    #[name = "チャンネル"]
+    Channel,
+    #[name = "サーバー全体"]
+    Guild,
+}
+
+#[derive(Debug, Clone, Copy, poise::ChoiceParameter)]
+pub enum PeriodChoice {
+    #[name = "直近1週間"]
+    Week,
+    #[name = "直近1ヶ月"]
+    Month,
+    #[name = "期間指定"]
+    Custom,
+}
+
+#[allow(clippy::too_many_arguments)]
+#[poise::command(slash_command, guild_only, rename = "統計")]
+pub async fn main(
+    ctx: Context<'_>,
+    #[description = "集計対象（既定: 自分）"] scope: Option<ScopeChoice>,
+    #[description = "集計期間（既定: 直近1週間）"] period: Option<PeriodChoice>,
+    #[description = "対象ユーザー（範囲: ユーザー）"] user: Option<serenity::User>,
+    #[description = "対象ロール（範囲: ロール）"] role: Option<serenity::Role>,
+    #[description = "対象チャンネル（範囲: チャンネル）"] channel: Option<serenity::GuildChannel>,
+    #[description = "開始日 YYYY-MM-DD（期間指定）"] from: Option<String>,
+    #[description = "終了日 YYYY-MM-DD（期間指定、既定: 今日）"] to: Option<String>,
+) -> Result<(), Error> {
+    let Some(guild_id) = ctx.guild_id() else {
+        return Ok(());
+    };
+    // ロールのメンバー取得と集計で 3秒を超えることがあるため、先に応答を保留する。
+    ctx.defer_ephemeral().await?;
+
+    let scope = match scope.unwrap_or(ScopeChoice::Me) {
+        ScopeChoice::Me => StatsScope::User(ctx.author().id.get()),
+        ScopeChoice::User => match user {
+            Some(user) => StatsScope::User(user.id.get()),
+            None => return reply_ephemeral(ctx, "ユーザーを指定してください。").await,
+        },
+        ScopeChoice::Role => match role {
+            Some(role) => StatsScope::Role {
+                role_id: role.id.get(),
+                member_ids: fetch_role_member_ids(&ctx, guild_id, role.id).await?,
+            },
+            None => return reply_ephemeral(ctx, "ロールを指定してください。").await,
+        },
+        ScopeChoice::Channel => match channel {
+            Some(channel) => StatsScope::Channel(channel.id.get()),
+            None => return reply_ephemeral(ctx, "チャンネルを指定してください。").await,
+        },
+        ScopeChoice::Guild => StatsScope::Guild,
+    };
+
+    let period = match period.unwrap_or(PeriodChoice::Week) {
+        PeriodChoice::Week => StatsPeriod::Week,
+        PeriodChoice::Month => StatsPeriod::Month,
+        PeriodChoice::Custom => StatsPeriod::Custom { from, to },
+    };
+
+    let input = StatsInput {
+        guild_id: guild_id.get(),
+        scope,
+        period,
+        now_unix: current_unix_timestamp(),
+    };
+    let plan = stats_usecase::execute(&ctx.data().db, input).await?;
+    discord_exec::execute_from_interaction(ctx, plan).await
+}
+
+/// 一度に取得できるメンバー数の上限。
+const MEMBERS_PAGE_SIZE: u64 = 1000;
+
+async fn fetch_role_member_ids(
+    ctx: &Context<'_>,
+    guild_id: serenity::GuildId,
+    role_id: serenity::RoleId,
+) -> Result<Vec<u64>, Error> {
+    let mut member_ids = Vec::new();
+    let mut after = None;
+    loop {
+        let members = guild_id
+            .members(&ctx.serenity_context().http, Some(MEMBERS_PAGE_SIZE), after)
+            .await
+            .context("failed to fetch guild members. enable the GUILD_MEMBERS intent")?;
+        let page_len = members.len() as u64;
+        after = members.last().map(|member| member.user.id);
+        member_ids.extend(
+            members
+                .into_iter()
+                .filter(|member| member.roles.contains(&role_id))
+                .map(|member| member.user.id.get()),
+        );
+        if page_len < MEMBERS_PAGE_SIZE || after.is_none() {
+            return Ok(member_ids);
+        }
+    }
+}
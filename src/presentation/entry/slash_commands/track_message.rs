@@ -0,0 +1,69 @@
+use poise::serenity_prelude as serenity;
+
+use crate::presentation::discord_exec;
+use crate::presentation::entry::util::{current_unix_timestamp, done_reaction, kidoku_reaction};
+use crate::presentation::{Context, Error};
+use crate::usecase::slash_commands::track_message::{
+    self as track_message_usecase, StopTrackingInput,
+};
+
+#[poise::command(context_menu_command = "追跡を開始", guild_only)]
+pub async fn start(ctx: Context<'_>, msg: serenity::Message) -> Result<(), Error> {
+    let can_manage = track_message_usecase::can_manage_tracking(
+        ctx.author().id.get(),
+        msg.author.id.get(),
+        is_moderator(&ctx),
+    );
+    let plan = track_message_usecase::build_start_prompt(
+        msg.channel_id.get(),
+        msg.id.get(),
+        ctx.author().id.get(),
+        can_manage,
+    )?;
+    discord_exec::execute_from_interaction(ctx, plan).await
+}
+
+#[poise::command(context_menu_command = "追跡を停止", guild_only)]
+pub async fn stop(ctx: Context<'_>, msg: serenity::Message) -> Result<(), Error> {
+    let Some(guild_id) = ctx.guild_id() else {
+        return Ok(());
+    };
+    let input = StopTrackingInput {
+        guild_id: guild_id.get(),
+        message_id: msg.id.get(),
+        can_manage: track_message_usecase::can_manage_tracking(
+            ctx.author().id.get(),
+            msg.author.id.get(),
+            is_moderator(&ctx),
+        ),
+        now_unix: current_unix_timestamp(),
+    };
+    let output = track_message_usecase::stop(&ctx.data().db, input).await?;
+
+    if output.remove_reactions {
+        for reaction in [kidoku_reaction(), done_reaction()] {
+            if let Err(err) = msg.delete_reaction(ctx.http(), None, reaction).await {
+                tracing::warn!(
+                    "failed to remove reaction on stop tracking: message_id={}, err={:?}",
+                    msg.id,
+                    err
+                );
+            }
+        }
+    }
+
+    discord_exec::execute_from_interaction(ctx, output.response).await
+}
+
+/// メッセージの管理権限を持つメンバーをモデレーターとみなす。
+fn is_moderator(ctx: &Context<'_>) -> bool {
+    let Context::Application(app_ctx) = ctx else {
+        return false;
+    };
+    app_ctx
+        .interaction
+        .member
+        .as_ref()
+        .and_then(|member| member.permissions)
+        .is_some_and(|permissions| permissions.manage_messages())
+}
@@ -0,0 +1,169 @@
+use poise::serenity_prelude as serenity;
+
+use crate::domain::model::{ScopeMode, ScopeRule, ScopeTarget};
+use crate::presentation::entry::util::reply_ephemeral;
+use crate::presentation::{Context, Error};
+
+#[derive(Debug, Clone, Copy, poise::ChoiceParameter)]
+pub enum ChannelModeChoice {
+    #[name = "含める"]
+    Include,
+    #[name = "除外"]
+    Exclude,
+    #[name = "@everyoneのみ除外"]
+    ExcludeEveryone,
+    #[name = "解除"]
+    Unset,
+}
+
+#[derive(Debug, Clone, Copy, poise::ChoiceParameter)]
+pub enum RoleModeChoice {
+    #[name = "含める"]
+    Include,
+    #[name = "除外"]
+    Exclude,
+    #[name = "解除"]
+    Unset,
+}
+
+/// 追跡するチャンネル・カテゴリ・ロールを設定する。
+#[poise::command(
+    slash_command,
+    guild_only,
+    rename = "追跡範囲",
+    subcommands("channel", "role", "list"),
+    subcommand_required,
+    required_permissions = "MANAGE_GUILD"
+)]
+pub async fn main(_ctx: Context<'_>) -> Result<(), Error> {
+    Ok(())
+}
+
+/// チャンネルまたはカテゴリを追跡範囲に含める・除外する。
+#[poise::command(
+    slash_command,
+    guild_only,
+    rename = "チャンネル",
+    required_permissions = "MANAGE_GUILD"
+)]
+pub async fn channel(
+    ctx: Context<'_>,
+    #[description = "対象のチャンネルまたはカテゴリ"] channel: serenity::GuildChannel,
+    #[description = "扱い"] mode: ChannelModeChoice,
+) -> Result<(), Error> {
+    let Some(guild_id) = ctx.guild_id() else {
+        return Ok(());
+    };
+
+    let target = ScopeTarget::Channel(channel.id.get());
+    let mode = match mode {
+        ChannelModeChoice::Include => Some(ScopeMode::Include),
+        ChannelModeChoice::Exclude => Some(ScopeMode::Exclude),
+        ChannelModeChoice::ExcludeEveryone => Some(ScopeMode::ExcludeEveryone),
+        ChannelModeChoice::Unset => None,
+    };
+    let content = apply_rule(ctx, guild_id, target, mode).await?;
+    reply_ephemeral(ctx, content).await
+}
+
+/// ロールメンションを追跡対象に含める・除外する。
+#[poise::command(
+    slash_command,
+    guild_only,
+    rename = "ロール",
+    required_permissions = "MANAGE_GUILD"
+)]
+pub async fn role(
+    ctx: Context<'_>,
+    #[description = "対象のロール"] role: serenity::Role,
+    #[description = "扱い"] mode: RoleModeChoice,
+) -> Result<(), Error> {
+    let Some(guild_id) = ctx.guild_id() else {
+        return Ok(());
+    };
+
+    let target = ScopeTarget::Role(role.id.get());
+    let mode = match mode {
+        RoleModeChoice::Include => Some(ScopeMode::Include),
+        RoleModeChoice::Exclude => Some(ScopeMode::Exclude),
+        RoleModeChoice::Unset => None,
+    };
+    let content = apply_rule(ctx, guild_id, target, mode).await?;
+    reply_ephemeral(ctx, content).await
+}
+
+/// 現在の追跡範囲ルールを表示する。
+#[poise::command(
+    slash_command,
+    guild_only,
+    rename = "一覧",
+    required_permissions = "MANAGE_GUILD"
+)]
+pub async fn list(ctx: Context<'_>) -> Result<(), Error> {
+    let Some(guild_id) = ctx.guild_id() else {
+        return Ok(());
+    };
+
+    let rules = ctx
+        .data()
+        .db
+        .fetch_tracking_scope_rules(guild_id.get())
+        .await?;
+    let content = if rules.is_empty() {
+        "追跡範囲のルールはありません。すべてのチャンネル・ロールのメンションを追跡します。"
+            .to_string()
+    } else {
+        let lines = rules
+            .iter()
+            .map(|rule| format!("- {}: {}", mention(rule.target), mode_label(rule.mode)))
+            .collect::<Vec<_>>();
+        format!("**追跡範囲のルール**\n{}", lines.join("\n"))
+    };
+    reply_ephemeral(ctx, content).await
+}
+
+async fn apply_rule(
+    ctx: Context<'_>,
+    guild_id: serenity::GuildId,
+    target: ScopeTarget,
+    mode: Option<ScopeMode>,
+) -> Result<String, Error> {
+    let db = &ctx.data().db;
+    let content = match mode {
+        Some(mode) => {
+            db.upsert_tracking_scope_rule(guild_id.get(), ScopeRule { target, mode })
+                .await?;
+            format!(
+                "{} を「{}」に設定しました。",
+                mention(target),
+                mode_label(mode)
+            )
+        }
+        None => {
+            let deleted = db
+                .delete_tracking_scope_rule(guild_id.get(), target)
+                .await?;
+            if deleted == 0 {
+                format!("{} にはルールが設定されていません。", mention(target))
+            } else {
+                format!("{} のルールを解除しました。", mention(target))
+            }
+        }
+    };
+    Ok(content)
+}
+
+fn mention(target: ScopeTarget) -> String {
+    match target {
+        ScopeTarget::Channel(id) => format!("<#{}>", id),
+        ScopeTarget::Role(id) => format!("<@&{}>", id),
+    }
+}
+
+fn mode_label(mode: ScopeMode) -> &'static str {
+    match mode {
+        ScopeMode::Include => "含める",
+        ScopeMode::Exclude => "除外",
+        ScopeMode::ExcludeEveryone => "@everyoneのみ除外",
+    }
+}
@@ -1,9 +1,70 @@
+use std::sync::OnceLock;
 use std::time::{SystemTime, UNIX_EPOCH};
 
-pub const KIDOKU_EMOJI_ID: u64 = 1475281418400698633;
-pub const KIDOKU_EMOJI_NAME: &str = "KIDOKU";
-pub const DONE_EMOJI_ID: u64 = 1475281416370524414;
-pub const DONE_EMOJI_NAME: &str = "DONE";
+use anyhow::Context as _;
+use poise::serenity_prelude as serenity;
+
+use crate::infrastructure::config::ReactionEmojis;
+use crate::infrastructure::db::Db;
+use crate::presentation::discord_exec;
+use crate::presentation::{Context, Error};
+pub use crate::usecase::dto::output::text::truncate;
+use crate::usecase::dto::{DiscordExecPlan, DiscordExecStep, MessagePayload};
+
+/// 既読・解決に使う絵文字。起動時に設定から一度だけ決める。
+static REACTION_EMOJIS: OnceLock<ReactionEmojis> = OnceLock::new();
+/// リアクションを付けたユーザーを一度に取得できる件数の上限。
+const REACTION_USERS_PAGE_SIZE: u8 = 100;
+/// チャンネルの履歴を一度に取得できる件数の上限。
+const HISTORY_PAGE_SIZE: u8 = 100;
+
+/// チャンネルの履歴の1ページ。
+pub struct HistoryPage {
+    /// 古い順に並べ、「追跡を停止」したメッセージを除いたもの。
+    pub messages: Vec<serenity::Message>,
+    /// 除いたものも含めた、このページで最も新しいメッセージ。次のページはこれより後から取得する。
+    pub last_id: Option<serenity::MessageId>,
+    /// これより新しいメッセージが無い。
+    pub is_last: bool,
+}
+
+/// 設定した絵文字を使うようにする。2回目以降の呼び出しは無視する。
+pub fn set_reaction_emojis(emojis: ReactionEmojis) {
+    let _ = REACTION_EMOJIS.set(emojis);
+}
+
+/// 設定された絵文字。未設定なら既定の絵文字を使う。
+pub fn reaction_emojis() -> &'static ReactionEmojis {
+    REACTION_EMOJIS.get_or_init(ReactionEmojis::default)
+}
+
+pub fn kidoku_reaction() -> serenity::ReactionType {
+    let emojis = reaction_emojis();
+    custom_reaction(emojis.kidoku_id, &emojis.kidoku_name)
+}
+
+pub fn done_reaction() -> serenity::ReactionType {
+    let emojis = reaction_emojis();
+    custom_reaction(emojis.done_id, &emojis.done_name)
+}
+
+fn custom_reaction(id: u64, name: &str) -> serenity::ReactionType {
+    serenity::ReactionType::Custom {
+        animated: false,
+        id: serenity::EmojiId::new(id),
+        name: Some(name.to_string()),
+    }
+}
+
+/// コマンドの実行者にだけ見えるメッセージで応答する。
+pub async fn reply_ephemeral(ctx: Context<'_>, content: impl Into<String>) -> Result<(), Error> {
+    let plan = DiscordExecPlan::new(vec![DiscordExecStep::Response(MessagePayload {
+        content: Some(content.into()),
+        ephemeral: Some(true),
+        ..Default::default()
+    })]);
+    discord_exec::execute_from_interaction(ctx, plan).await
+}
 
 pub fn current_unix_timestamp() -> i64 {
     SystemTime::now()
@@ -12,10 +73,86 @@ pub fn current_unix_timestamp() -> i64 {
         .as_secs() as i64
 }
 
-pub fn truncate(content: &str, max_chars: usize) -> String {
-    let mut truncated = content.chars().take(max_chars).collect::<String>();
-    if content.chars().count() > max_chars {
-        truncated.push('…');
+/// メッセージに指定のリアクションを付けたユーザーをすべて取得する。取得に失敗した場合は途中までを返す。
+pub async fn fetch_reaction_users(
+    ctx: &serenity::Context,
+    channel_id: serenity::ChannelId,
+    message_id: serenity::MessageId,
+    reaction: serenity::ReactionType,
+) -> Vec<serenity::UserId> {
+    // Bot 自身が付けたリアクションも含まれるため、Bot は除く。
+    let mut user_ids = Vec::new();
+    let mut after = None;
+    loop {
+        let users = match channel_id
+            .reaction_users(
+                &ctx.http,
+                message_id,
+                reaction.clone(),
+                Some(REACTION_USERS_PAGE_SIZE),
+                after,
+            )
+            .await
+        {
+            Ok(users) => users,
+            Err(err) => {
+                tracing::warn!(
+                    "failed to fetch reaction users for message {}: {:?}",
+                    message_id.get(),
+                    err
+                );
+                break;
+            }
+        };
+        let page_len = users.len();
+        after = users.last().map(|user| user.id);
+        user_ids.extend(
+            users
+                .into_iter()
+                .filter(|user| !user.bot)
+                .map(|user| user.id),
+        );
+        if page_len < REACTION_USERS_PAGE_SIZE as usize {
+            break;
+        }
     }
-    truncated
+    user_ids
+}
+
+/// `after` より新しいメッセージを1ページ取得する。起動時の補完と `/管理設定 インポート` で使う。
+/// 「追跡を停止」したメッセージは記録し直さないよう取り除く。
+pub async fn fetch_history_page(
+    ctx: &serenity::Context,
+    db: &Db,
+    guild_id: serenity::GuildId,
+    channel_id: serenity::ChannelId,
+    after: serenity::MessageId,
+) -> Result<HistoryPage, Error> {
+    let builder = serenity::GetMessages::new()
+        .after(after)
+        .limit(HISTORY_PAGE_SIZE);
+    let mut messages = channel_id
+        .messages(&ctx.http, builder)
+        .await
+        .context("failed to fetch channel history")?;
+    let is_last = messages.len() < HISTORY_PAGE_SIZE as usize;
+    messages.sort_by_key(|message| message.id);
+    let last_id = messages.last().map(|message| message.id);
+
+    let message_ids = messages
+        .iter()
+        .map(|message| message.id.get())
+        .collect::<Vec<_>>();
+    let untracked = db.fetch_untracked_message_ids(&message_ids).await?;
+    messages.retain(|message| !untracked.contains(&message.id.get()));
+    for message in &mut messages {
+        // REST で取得したメッセージには guild_id が含まれない。
+        message.guild_id = Some(guild_id);
+    }
+
+    Ok(HistoryPage {
+        messages,
+        last_id,
+        is_last,
+    })
 }
@@ -0,0 +1,211 @@
+use poise::serenity_prelude as serenity;
+use tracing::field::Empty;
+use tracing::Instrument as _;
+
+use crate::infrastructure::logging::new_request_id;
+use crate::presentation::{Data, Error};
+
+/// poise のフレームワークを包み、Gateway イベントとインタラクションごとにスパンを張る。
+/// スパンの中で呼ばれる DB 操作やコマンドのログにも同じ request_id と ID が付く。
+pub struct TracedFramework {
+    inner: poise::Framework<Data, Error>,
+}
+
+impl TracedFramework {
+    pub fn new(inner: poise::Framework<Data, Error>) -> Self {
+        Self { inner }
+    }
+}
+
+#[async_trait::async_trait]
+impl serenity::Framework for TracedFramework {
+    async fn init(&mut self, client: &serenity::Client) {
+        self.inner.init(client).await;
+    }
+
+    async fn dispatch(&self, ctx: serenity::Context, event: serenity::FullEvent) {
+        let span = event_span(&event);
+        self.inner.dispatch(ctx, event).instrument(span).await;
+    }
+}
+
+/// イベントに含まれる ID。無いものは `None`。
+#[derive(Debug, Default, PartialEq, Eq)]
+struct EventIds {
+    guild_id: Option<serenity::GuildId>,
+    channel_id: Option<serenity::ChannelId>,
+    message_id: Option<serenity::MessageId>,
+    user_id: Option<serenity::UserId>,
+    command: Option<String>,
+}
+
+fn event_span(event: &serenity::FullEvent) -> tracing::Span {
+    let span = tracing::info_span!(
+        "event",
+        kind = event.snake_case_name(),
+        request_id = %new_request_id(),
+        guild_id = Empty,
+        channel_id = Empty,
+        message_id = Empty,
+        user_id = Empty,
+        command = Empty,
+    );
+    let ids = event_ids(event);
+    if let Some(guild_id) = ids.guild_id {
+        span.record("guild_id", guild_id.get());
+    }
+    if let Some(channel_id) = ids.channel_id {
+        span.record("channel_id", channel_id.get());
+    }
+    if let Some(message_id) = ids.message_id {
+        span.record("message_id", message_id.get());
+    }
+    if let Some(user_id) = ids.user_id {
+        span.record("user_id", user_id.get());
+    }
+    if let Some(command) = ids.command {
+        span.record("command", command.as_str());
+    }
+    span
+}
+
+fn event_ids(event: &serenity::FullEvent) -> EventIds {
+    match event {
+        serenity::FullEvent::Message { new_message } => message_ids(new_message),
+        serenity::FullEvent::MessageUpdate { event, .. } => EventIds {
+            guild_id: event.guild_id,
+            channel_id: Some(event.channel_id),
+            message_id: Some(event.id),
+            user_id: event.author.as_ref().map(|author| author.id),
+            command: None,
+        },
+        serenity::FullEvent::MessageDelete {
+            channel_id,
+            deleted_message_id,
+            guild_id,
+        } => EventIds {
+            guild_id: *guild_id,
+            channel_id: Some(*channel_id),
+            message_id: Some(*deleted_message_id),
+            ..EventIds::default()
+        },
+        serenity::FullEvent::MessageDeleteBulk {
+            channel_id,
+            guild_id,
+            ..
+        } => EventIds {
+            guild_id: *guild_id,
+            channel_id: Some(*channel_id),
+            ..EventIds::default()
+        },
+        serenity::FullEvent::ReactionAdd { add_reaction } => reaction_ids(add_reaction),
+        serenity::FullEvent::ReactionRemove { removed_reaction } => reaction_ids(removed_reaction),
+        serenity::FullEvent::GuildCreate { guild, .. } => EventIds {
+            guild_id: Some(guild.id),
+            ..EventIds::default()
+        },
+        serenity::FullEvent::GuildDelete { incomplete, .. } => EventIds {
+            guild_id: Some(incomplete.id),
+            ..EventIds::default()
+        },
+        serenity::FullEvent::ChannelDelete { channel, .. } => EventIds {
+            guild_id: Some(channel.guild_id),
+            channel_id: Some(channel.id),
+            ..EventIds::default()
+        },
+        serenity::FullEvent::ThreadDelete { thread, .. } => EventIds {
+            guild_id: Some(thread.guild_id),
+            channel_id: Some(thread.id),
+            ..EventIds::default()
+        },
+        serenity::FullEvent::InteractionCreate { interaction } => interaction_ids(interaction),
+        _ => EventIds::default(),
+    }
+}
+
+fn message_ids(message: &serenity::Message) -> EventIds {
+    EventIds {
+        guild_id: message.guild_id,
+        channel_id: Some(message.channel_id),
+        message_id: Some(message.id),
+        user_id: Some(message.author.id),
+        command: None,
+    }
+}
+
+fn reaction_ids(reaction: &serenity::Reaction) -> EventIds {
+    EventIds {
+        guild_id: reaction.guild_id,
+        channel_id: Some(reaction.channel_id),
+        message_id: Some(reaction.message_id),
+        user_id: reaction.user_id,
+        command: None,
+    }
+}
+
+fn interaction_ids(interaction: &serenity::Interaction) -> EventIds {
+    match interaction {
+        serenity::Interaction::Command(command) | serenity::Interaction::Autocomplete(command) => {
+            EventIds {
+                guild_id: command.guild_id,
+                channel_id: Some(command.channel_id),
+                message_id: None,
+                user_id: Some(command.user.id),
+                command: Some(command.data.name.clone()),
+            }
+        }
+        serenity::Interaction::Component(component) => EventIds {
+            guild_id: component.guild_id,
+            channel_id: Some(component.channel_id),
+            message_id: Some(component.message.id),
+            user_id: Some(component.user.id),
+            command: None,
+        },
+        serenity::Interaction::Modal(modal) => EventIds {
+            guild_id: modal.guild_id,
+            channel_id: Some(modal.channel_id),
+            message_id: modal.message.as_ref().map(|message| message.id),
+            user_id: Some(modal.user.id),
+            command: None,
+        },
+        _ => EventIds::default(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn collects_ids_from_message_delete() {
+        let event = serenity::FullEvent::MessageDelete {
+            channel_id: serenity::ChannelId::new(2),
+            deleted_message_id: serenity::MessageId::new(3),
+            guild_id: Some(serenity::GuildId::new(1)),
+        };
+
+        assert_eq!(
+            event_ids(&event),
+            EventIds {
+                guild_id: Some(serenity::GuildId::new(1)),
+                channel_id: Some(serenity::ChannelId::new(2)),
+                message_id: Some(serenity::MessageId::new(3)),
+                ..EventIds::default()
+            }
+        );
+    }
+
+    #[test]
+    fn leaves_missing_ids_empty() {
+        let event = serenity::FullEvent::MessageDeleteBulk {
+            channel_id: serenity::ChannelId::new(2),
+            multiple_deleted_messages_ids: vec![serenity::MessageId::new(3)],
+            guild_id: None,
+        };
+
+        let ids = event_ids(&event);
+        assert_eq!(ids.channel_id, Some(serenity::ChannelId::new(2)));
+        assert_eq!(ids.guild_id, None);
+        assert_eq!(ids.message_id, None);
+    }
+}
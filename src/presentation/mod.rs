@@ -1,24 +1,78 @@
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use poise::serenity_prelude as serenity;
+use tracing::Instrument as _;
 
+use crate::infrastructure::config::BotConfig;
 use crate::infrastructure::db::Db;
+use crate::infrastructure::metrics::METRICS;
+use crate::infrastructure::shutdown::{InFlightGuard, Shutdown};
+use crate::presentation::event_span::TracedFramework;
 
 pub mod discord_exec;
 pub mod entry;
+pub mod event_span;
 
 #[derive(Clone)]
 pub struct Data {
     pub db: Db,
+    /// 保持期間の既定値やバッチの設定など、起動時に読み込んだ設定。
+    pub config: BotConfig,
+    /// 停止の開始と処理中の数。停止中は新しいイベントやコマンドを受け付けない。
+    pub shutdown: Shutdown,
+}
+
+/// コマンドの実行中だけ保持する情報。
+struct CommandInvocation {
+    started: Instant,
+    _in_flight: Option<InFlightGuard>,
 }
 
 pub type Error = anyhow::Error;
 pub type Context<'a> = poise::Context<'a, Data, Error>;
 
-pub fn build_framework(db: Db) -> poise::Framework<Data, Error> {
+pub fn build_framework(db: Db, config: BotConfig, shutdown: Shutdown) -> TracedFramework {
+    entry::util::set_reaction_emojis(config.emojis.clone());
     let options = poise::FrameworkOptions {
         commands: entry::slash_commands::all(),
         on_error: |error| Box::pin(entry::on_error::handle_framework_error(error)),
+        command_check: Some(|ctx| {
+            Box::pin(async move {
+                if !ctx.data().shutdown.is_stopping() {
+                    return Ok(true);
+                }
+                ctx.send(
+                    poise::CreateReply::default()
+                        .content("Bot を停止しています。再起動後にもう一度実行してください。")
+                        .ephemeral(true),
+                )
+                .await?;
+                Ok(false)
+            })
+        }),
+        pre_command: |ctx| {
+            Box::pin(async move {
+                let invocation = CommandInvocation {
+                    started: Instant::now(),
+                    _in_flight: ctx.data().shutdown.track(),
+                };
+                ctx.set_invocation_data(invocation).await;
+            })
+        },
+        post_command: |ctx| {
+            Box::pin(async move {
+                let started = ctx
+                    .invocation_data::<CommandInvocation>()
+                    .await
+                    .map(|invocation| invocation.started);
+                if let Some(started) = started {
+                    METRICS.observe_command(
+                        &ctx.command().qualified_name,
+                        started.elapsed().as_secs_f64(),
+                    );
+                }
+            })
+        },
         event_handler: |ctx, event, _framework, data| {
             Box::pin(async move {
                 handle_event(ctx, event, data).await;
@@ -28,28 +82,64 @@ pub fn build_framework(db: Db) -> poise::Framework<Data, Error> {
         ..Default::default()
     };
 
-    poise::Framework::builder()
+    let framework = poise::Framework::builder()
         .options(options)
         .setup(move |ctx, ready, framework| {
             let db = db.clone();
+            let config = config.clone();
+            let shutdown = shutdown.clone();
             Box::pin(async move {
                 tracing::info!("logged in as {}", ready.user.name);
                 let commands =
                     poise::builtins::create_application_commands(&framework.options().commands);
                 let ctx_clone = ctx.clone();
-                tokio::spawn(async move {
-                    register_commands_with_retry(ctx_clone, commands).await;
-                });
+                tokio::spawn(
+                    async move {
+                        register_commands_with_retry(ctx_clone, commands).await;
+                    }
+                    .in_current_span(),
+                );
                 let db_for_batch = db.clone();
                 let ctx_for_batch = ctx.clone();
-                entry::batch::start(ctx_for_batch, db_for_batch);
-                Ok(Data { db })
+                entry::batch::start(
+                    ctx_for_batch,
+                    db_for_batch,
+                    config.clone(),
+                    shutdown.clone(),
+                );
+                Ok(Data {
+                    db,
+                    config,
+                    shutdown,
+                })
             })
         })
-        .build()
+        .build();
+    TracedFramework::new(framework)
 }
 
 async fn handle_event(ctx: &serenity::Context, event: &serenity::FullEvent, data: &Data) {
+    // 停止中に届いたイベントは捨てる。取りこぼした分は次回起動時に補完する。
+    let Some(_in_flight) = data.shutdown.track() else {
+        return;
+    };
+
+    if let serenity::FullEvent::Ready { .. } = event {
+        METRICS.set_gateway_connected(true);
+        entry::on_ready::handle(ctx, data);
+        return;
+    }
+
+    if let serenity::FullEvent::Resume { .. } = event {
+        METRICS.set_gateway_connected(true);
+        return;
+    }
+
+    if let serenity::FullEvent::ShardStageUpdate { event } = event {
+        METRICS.set_gateway_connected(event.new == serenity::ConnectionStage::Connected);
+        return;
+    }
+
     if let serenity::FullEvent::Message { new_message } = event {
         entry::on_message::handle(ctx, data, new_message).await;
         return;
@@ -77,11 +167,39 @@ async fn handle_event(ctx: &serenity::Context, event: &serenity::FullEvent, data
         return;
     }
 
+    if let serenity::FullEvent::GuildCreate { guild, .. } = event {
+        entry::on_guild_lifecycle::handle_guild_create(data, guild.id).await;
+        return;
+    }
+
+    if let serenity::FullEvent::GuildDelete { incomplete, .. } = event {
+        entry::on_guild_lifecycle::handle_guild_delete(data, incomplete).await;
+        return;
+    }
+
+    if let serenity::FullEvent::ChannelDelete { channel, .. } = event {
+        entry::on_guild_lifecycle::handle_channel_delete(data, channel.id).await;
+        return;
+    }
+
+    if let serenity::FullEvent::ThreadDelete { thread, .. } = event {
+        entry::on_guild_lifecycle::handle_channel_delete(data, thread.id).await;
+        return;
+    }
+
     if let serenity::FullEvent::InteractionCreate {
         interaction: serenity::Interaction::Component(comp),
     } = event
     {
         entry::on_component::handle(ctx, data, comp).await;
+        return;
+    }
+
+    if let serenity::FullEvent::InteractionCreate {
+        interaction: serenity::Interaction::Modal(modal),
+    } = event
+    {
+        entry::on_modal_submit::handle(ctx, data, modal).await;
     }
 }
 
@@ -0,0 +1,207 @@
+use std::collections::BTreeMap;
+
+use validate_macro::async_validate_return;
+
+use crate::usecase::dto::output::discord_exec::validate_plan;
+use crate::usecase::dto::output::text::truncate;
+use crate::usecase::dto::repository::{DueReminderCandidate, DueReminderKind, MentionForTarget};
+use crate::usecase::dto::{DiscordExecPlan, DiscordExecStep, MessagePayload, PlanValidationError};
+use crate::usecase::ports::DueReminderRepository;
+
+/// 送信時刻を過ぎてからこの期間内のものだけを送る。
+/// 停止中に溜まったリマインダーを再開時に一斉に送らないための上限。
+pub const DUE_REMINDER_LOOKBACK_SECS: i64 = 24 * 3600;
+/// 1通の DM に並べるメンションの上限。Discord の本文上限に収めるため。
+const MAX_ITEMS_PER_DM: usize = 10;
+
+/// 期限を基準としたリマインダーを記録し、対象者ごとの DM プランを返す。
+/// 送信前に記録するため、送信に失敗しても同じリマインダーは二度と送らない。
+#[async_validate_return(validate_plans)]
+pub async fn execute<R: DueReminderRepository>(
+    repo: &R,
+    kind: DueReminderKind,
+    now_unix: i64,
+) -> anyhow::Result<Vec<DiscordExecPlan>> {
+    let candidates = repo
+        .fetch_due_reminder_candidates(kind, now_unix, DUE_REMINDER_LOOKBACK_SECS)
+        .await?;
+
+    let mut by_user: BTreeMap<u64, Vec<MentionForTarget>> = BTreeMap::new();
+    for DueReminderCandidate { user_id, mention } in candidates {
+        if repo
+            .record_due_reminder(mention.mention_id, user_id, kind, now_unix)
+            .await?
+        {
+            by_user.entry(user_id).or_default().push(mention);
+        }
+    }
+
+    let plans = by_user
+        .into_iter()
+        .map(|(user_id, items)| build_plan(kind, user_id, &items))
+        .collect::<Vec<_>>();
+    Ok(plans)
+}
+
+fn validate_plans(plans: &[DiscordExecPlan]) -> Result<(), PlanValidationError> {
+    plans.iter().try_for_each(validate_plan)
+}
+
+fn build_plan(kind: DueReminderKind, user_id: u64, items: &[MentionForTarget]) -> DiscordExecPlan {
+    let header = match kind {
+        DueReminderKind::DueSoon => "⏰ **期限が近いメンションがあります**",
+        DueReminderKind::Overdue => "⚠️ **期限を過ぎたメンションがあります**",
+        DueReminderKind::UrgentUnread => "🚨 **至急のメンションが未読です**",
+    };
+    let mut lines = vec![header.to_string()];
+    lines.extend(items.iter().take(MAX_ITEMS_PER_DM).map(format_item));
+    if items.len() > MAX_ITEMS_PER_DM {
+        lines.push(format!("…他{}件", items.len() - MAX_ITEMS_PER_DM));
+    }
+    lines.push("`/通知一覧 sort_by_due:true` で期限順に確認できます。".to_string());
+
+    DiscordExecPlan::new(vec![DiscordExecStep::SendDm {
+        user_id,
+        payload: MessagePayload {
+            content: Some(lines.join("\n")),
+            ..Default::default()
+        },
+    }])
+}
+
+fn format_item(item: &MentionForTarget) -> String {
+    let message_link = format!(
+        "https://discord.com/channels/{}/{}/{}",
+        item.guild_id, item.channel_id, item.message_id
+    );
+    let due = item
+        .due_at_unix
+        .map(|due_at| format!("<t:{due_at}:f>（<t:{due_at}:R>）"))
+        .unwrap_or_default();
+    format!("- {} {} {}", due, message_link, truncate(&item.content, 60))
+        .trim_end()
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use super::*;
+    use crate::domain::model::MentionPriority;
+
+    struct FakeRepository {
+        candidates: Vec<DueReminderCandidate>,
+        recorded: Mutex<Vec<(i64, u64)>>,
+    }
+
+    impl DueReminderRepository for FakeRepository {
+        async fn fetch_due_reminder_candidates(
+            &self,
+            _kind: DueReminderKind,
+            _now_unix: i64,
+            _lookback_secs: i64,
+        ) -> anyhow::Result<Vec<DueReminderCandidate>> {
+            Ok(self.candidates.clone())
+        }
+
+        async fn record_due_reminder(
+            &self,
+            mention_id: i64,
+            user_id: u64,
+            _kind: DueReminderKind,
+            _sent_at_unix: i64,
+        ) -> anyhow::Result<bool> {
+            let mut recorded = self.recorded.lock().unwrap();
+            if recorded.contains(&(mention_id, user_id)) {
+                return Ok(false);
+            }
+            recorded.push((mention_id, user_id));
+            Ok(true)
+        }
+    }
+
+    fn candidate(mention_id: i64, user_id: u64) -> DueReminderCandidate {
+        DueReminderCandidate {
+            user_id,
+            mention: MentionForTarget {
+                mention_id,
+                guild_id: 2,
+                channel_id: 3,
+                message_id: 100 + mention_id as u64,
+                author_id: 1,
+                content: "<@10> 金曜までに確認お願いします".into(),
+                mention_everyone: false,
+                created_at_unix: 0,
+                due_at_unix: Some(86_400),
+                priority: MentionPriority::Normal,
+                is_read: false,
+                is_done: false,
+                extended_until: None,
+            },
+        }
+    }
+
+    fn repo(candidates: Vec<DueReminderCandidate>) -> FakeRepository {
+        FakeRepository {
+            candidates,
+            recorded: Mutex::new(Vec::new()),
+        }
+    }
+
+    fn dm(plan: &DiscordExecPlan) -> (u64, &str) {
+        match plan.steps() {
+            [DiscordExecStep::SendDm { user_id, payload }] => {
+                (*user_id, payload.content.as_deref().unwrap_or_default())
+            }
+            other => panic!("unexpected steps: {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn sends_one_dm_per_user() {
+        let repo = repo(vec![candidate(7, 10), candidate(8, 10), candidate(7, 11)]);
+        let plans = execute(&repo, DueReminderKind::DueSoon, 100)
+            .await
+            .expect("expected plans");
+
+        assert_eq!(plans.len(), 2);
+        let (user_id, content) = dm(&plans[0]);
+        assert_eq!(user_id, 10);
+        assert!(content.starts_with("⏰ **期限が近いメンションがあります**"));
+        assert!(content.contains("https://discord.com/channels/2/3/107"));
+        assert!(content.contains("https://discord.com/channels/2/3/108"));
+        assert!(content.contains("<t:86400:f>"));
+        assert_eq!(dm(&plans[1]).0, 11);
+    }
+
+    #[tokio::test]
+    async fn skips_reminders_already_sent() {
+        let repo = repo(vec![candidate(7, 10), candidate(8, 11)]);
+        repo.recorded.lock().unwrap().push((8, 11));
+
+        let plans = execute(&repo, DueReminderKind::Overdue, 100)
+            .await
+            .expect("expected plans");
+
+        assert_eq!(plans.len(), 1);
+        let (user_id, content) = dm(&plans[0]);
+        assert_eq!(user_id, 10);
+        assert!(content.starts_with("⚠️ **期限を過ぎたメンションがあります**"));
+    }
+
+    #[tokio::test]
+    async fn caps_items_per_dm() {
+        let candidates = (0..12).map(|id| candidate(id, 10)).collect();
+        let plans = execute(&repo(candidates), DueReminderKind::DueSoon, 100)
+            .await
+            .expect("expected plans");
+
+        let (_, content) = dm(&plans[0]);
+        assert_eq!(
+            content.matches("https://discord.com").count(),
+            MAX_ITEMS_PER_DM
+        );
+        assert!(content.contains("…他2件"));
+    }
+}
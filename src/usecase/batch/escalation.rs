@@ -0,0 +1,241 @@
+use serenity::model::prelude::UserId;
+use validate_macro::async_validate_return;
+
+use crate::domain::policy::read_status_calc;
+use crate::usecase::dto::output::discord_exec::validate_plan;
+use crate::usecase::dto::output::text::truncate;
+use crate::usecase::dto::repository::{EscalationCandidate, EscalationKind, StoredMention};
+use crate::usecase::dto::{DiscordExecPlan, DiscordExecStep, MessagePayload, PlanValidationError};
+use crate::usecase::ports::EscalationRepository;
+
+/// 設定した時間を過ぎてからこの期間内のメンションだけをエスカレーションする。
+/// 設定を有効にした直後に過去のメンションへ一斉に送らないための上限。
+pub const ESCALATION_LOOKBACK_SECS: i64 = 7 * 24 * 3600;
+/// 再メンションの本文に並べる未読者の上限。Discord の本文上限に収めるため。
+const MAX_REPING_USERS: usize = 50;
+/// 元メッセージにスレッドが無い場合に作成するスレッドの名前。
+const REPING_THREAD_NAME: &str = "未読の確認";
+
+/// 期限を過ぎたメンションのエスカレーションを記録し、送信するプランを返す。
+/// 送信前に記録するため、送信に失敗しても同じエスカレーションは二度と送らない。
+#[async_validate_return(validate_plans)]
+pub async fn execute<R: EscalationRepository>(
+    repo: &R,
+    kind: EscalationKind,
+    now_unix: i64,
+) -> anyhow::Result<Vec<DiscordExecPlan>> {
+    let candidates = repo
+        .fetch_escalation_candidates(kind, now_unix, ESCALATION_LOOKBACK_SECS)
+        .await?;
+
+    let mut plans = Vec::new();
+    for candidate in candidates {
+        let Some(plan) = build_plan(kind, &candidate) else {
+            continue;
+        };
+        if repo
+            .record_escalation(candidate.mention_id, kind, now_unix)
+            .await?
+        {
+            plans.push(plan);
+        }
+    }
+    Ok(plans)
+}
+
+fn validate_plans(plans: &[DiscordExecPlan]) -> Result<(), PlanValidationError> {
+    plans.iter().try_for_each(validate_plan)
+}
+
+/// 未読者が残っていなければ `None` を返す。
+fn build_plan(kind: EscalationKind, candidate: &EscalationCandidate) -> Option<DiscordExecPlan> {
+    let mention = &candidate.mention;
+    let unread = unread_users(mention);
+    if unread.is_empty() {
+        return None;
+    }
+
+    let message_link = format!(
+        "https://discord.com/channels/{}/{}/{}",
+        mention.guild_id, mention.channel_id, mention.message_id
+    );
+    let step = match kind {
+        EscalationKind::AuthorDm => DiscordExecStep::SendDm {
+            user_id: mention.author_id,
+            payload: MessagePayload {
+                content: Some(format!(
+                    "⏰ **未読のメンションがあります**\n\
+                     {}\n内容: {}\n未読 {}人: {}",
+                    message_link,
+                    truncate(&mention.content, 100),
+                    unread.len(),
+                    format_user_mentions(&unread)
+                )),
+                ..Default::default()
+            },
+        },
+        EscalationKind::Reping => DiscordExecStep::SendInThread {
+            channel_id: mention.channel_id,
+            message_id: mention.message_id,
+            thread_name: REPING_THREAD_NAME.to_string(),
+            payload: MessagePayload {
+                content: Some(format!(
+                    "{}\nこのメッセージがまだ未読です。確認をお願いします。",
+                    format_user_mentions(&unread)
+                )),
+                ..Default::default()
+            },
+        },
+    };
+    Some(DiscordExecPlan::new(vec![step]))
+}
+
+/// 解決済みは既読を兼ねるため、既読・解決のどちらかがあれば既読とみなす。
+fn unread_users(mention: &StoredMention) -> Vec<UserId> {
+    let targets = mention
+        .target_user_ids
+        .iter()
+        .map(|id| UserId::new(*id))
+        .collect::<Vec<_>>();
+    let reactions = mention
+        .read_user_ids
+        .iter()
+        .chain(&mention.done_user_ids)
+        .map(|id| UserId::new(*id))
+        .collect::<Vec<_>>();
+    let (_, unread) = read_status_calc::calculate_read_status(&targets, &reactions);
+    unread
+}
+
+fn format_user_mentions(users: &[UserId]) -> String {
+    let mut text = users
+        .iter()
+        .take(MAX_REPING_USERS)
+        .map(|id| format!("<@{}>", id.get()))
+        .collect::<Vec<_>>()
+        .join(" ");
+    if users.len() > MAX_REPING_USERS {
+        text.push_str(&format!(" …他{}人", users.len() - MAX_REPING_USERS));
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use super::*;
+    use crate::domain::model::MentionPriority;
+
+    struct FakeRepository {
+        candidates: Vec<EscalationCandidate>,
+        recorded: Mutex<Vec<i64>>,
+    }
+
+    impl EscalationRepository for FakeRepository {
+        async fn fetch_escalation_candidates(
+            &self,
+            _kind: EscalationKind,
+            _now_unix: i64,
+            _lookback_secs: i64,
+        ) -> anyhow::Result<Vec<EscalationCandidate>> {
+            Ok(self.candidates.clone())
+        }
+
+        async fn record_escalation(
+            &self,
+            mention_id: i64,
+            _kind: EscalationKind,
+            _sent_at_unix: i64,
+        ) -> anyhow::Result<bool> {
+            let mut recorded = self.recorded.lock().unwrap();
+            if recorded.contains(&mention_id) {
+                return Ok(false);
+            }
+            recorded.push(mention_id);
+            Ok(true)
+        }
+    }
+
+    fn candidate(mention_id: i64, reads: Vec<u64>, dones: Vec<u64>) -> EscalationCandidate {
+        EscalationCandidate {
+            mention_id,
+            mention: StoredMention {
+                author_id: 1,
+                guild_id: 2,
+                channel_id: 3,
+                message_id: 4,
+                content: "<@10> <@11> <@12> 至急".into(),
+                mention_everyone: false,
+                created_at_unix: 0,
+                due_at_unix: None,
+                priority: MentionPriority::Normal,
+                target_user_ids: vec![10, 11, 12],
+                read_user_ids: reads,
+                done_user_ids: dones,
+            },
+        }
+    }
+
+    fn repo(candidates: Vec<EscalationCandidate>) -> FakeRepository {
+        FakeRepository {
+            candidates,
+            recorded: Mutex::new(Vec::new()),
+        }
+    }
+
+    #[tokio::test]
+    async fn dms_author_with_unread_users() {
+        let repo = repo(vec![candidate(7, vec![10], vec![])]);
+        let plans = execute(&repo, EscalationKind::AuthorDm, 100)
+            .await
+            .expect("expected plans");
+
+        assert_eq!(*repo.recorded.lock().unwrap(), vec![7]);
+        match plans[0].steps() {
+            [DiscordExecStep::SendDm { user_id, payload }] => {
+                assert_eq!(*user_id, 1);
+                let content = payload.content.as_deref().unwrap_or_default();
+                assert!(content.contains("未読 2人: <@11> <@12>"));
+            }
+            other => panic!("unexpected steps: {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn repings_only_users_still_unread() {
+        let repo = repo(vec![candidate(7, vec![10], vec![12])]);
+        let plans = execute(&repo, EscalationKind::Reping, 100)
+            .await
+            .expect("expected plans");
+
+        match plans[0].steps() {
+            [DiscordExecStep::SendInThread {
+                channel_id: 3,
+                message_id: 4,
+                thread_name,
+                payload,
+            }] => {
+                assert_eq!(thread_name, REPING_THREAD_NAME);
+                let content = payload.content.as_deref().unwrap_or_default();
+                assert!(content.starts_with("<@11>\n"));
+            }
+            other => panic!("unexpected steps: {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn skips_when_everyone_read_or_already_sent() {
+        let repo = repo(vec![
+            candidate(7, vec![10, 11], vec![12]),
+            candidate(8, vec![], vec![]),
+        ]);
+        repo.recorded.lock().unwrap().push(8);
+
+        let plans = execute(&repo, EscalationKind::Reping, 100)
+            .await
+            .expect("expected plans");
+        assert!(plans.is_empty());
+        assert_eq!(*repo.recorded.lock().unwrap(), vec![8]);
+    }
+}
@@ -0,0 +1,5 @@
+pub mod due_reminder;
+pub mod escalation;
+pub mod reconcile;
+pub mod retention;
+pub mod team_report;
@@ -0,0 +1,53 @@
+/// 起動時の補完で遡る最大期間。長く停止していた場合も、これより古いメッセージは取り込まない。
+pub const MAX_BACKFILL_AGE_SECS: i64 = 3 * 24 * 60 * 60;
+/// 1チャンネルあたりに取り込むメッセージの上限。
+pub const MAX_BACKFILL_MESSAGES_PER_CHANNEL: usize = 500;
+/// リアクションを照合する未解決メンションの上限。
+pub const MAX_RECONCILE_MENTIONS: i64 = 500;
+
+/// Discord の ID が数え始める時刻（2015-01-01T00:00:00Z）のミリ秒。
+const DISCORD_EPOCH_MILLIS: i64 = 1_420_070_400_000;
+
+/// 指定した時刻に発行された最小の ID。
+pub fn snowflake_at(unix: i64) -> u64 {
+    let millis = (unix * 1000 - DISCORD_EPOCH_MILLIS).max(0);
+    (millis as u64) << 22
+}
+
+/// 補完で取得を始める位置。最後に記録したメッセージが古すぎる場合は遡る期間の上限で切る。
+pub fn backfill_after(last_message_id: u64, now_unix: i64) -> u64 {
+    last_message_id.max(snowflake_at(now_unix - MAX_BACKFILL_AGE_SECS))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn converts_unix_time_to_snowflake() {
+        // 2016-04-30T11:18:25.796Z に発行された ID
+        let id: u64 = 175928847299117063;
+        assert_eq!(
+            snowflake_at(1_462_015_105),
+            ((id >> 22) / 1000 * 1000) << 22
+        );
+        assert_eq!(snowflake_at(0), 0);
+    }
+
+    #[test]
+    fn keeps_recent_cursor() {
+        let now = 1_700_000_000;
+        let recent = snowflake_at(now - 60);
+        assert_eq!(backfill_after(recent, now), recent);
+    }
+
+    #[test]
+    fn clamps_old_cursor_to_max_age() {
+        let now = 1_700_000_000;
+        let old = snowflake_at(now - MAX_BACKFILL_AGE_SECS - 1);
+        assert_eq!(
+            backfill_after(old, now),
+            snowflake_at(now - MAX_BACKFILL_AGE_SECS)
+        );
+    }
+}
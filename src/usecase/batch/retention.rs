@@ -0,0 +1,185 @@
+use crate::usecase::dto::repository::PurgePreview;
+use crate::usecase::ports::RetentionRepository;
+
+/// 1回の DELETE で消すメンションの上限。長いロックを避けるため小分けにする。
+pub const PURGE_BATCH_SIZE: i64 = 500;
+/// 1回の実行で繰り返す DELETE の上限。残りは次回の実行で削除する。
+const MAX_BATCHES_PER_RUN: usize = 200;
+/// Bot が退出したギルドの記録を残す期間。期間内に再招待されれば記録をそのまま使う。
+pub const GUILD_DEPARTURE_GRACE_SECS: i64 = 30 * 24 * 60 * 60;
+
+pub struct RetentionInput {
+    /// ギルドごとの設定が無い場合の保持日数。`None` なら削除しない。
+    pub default_days: Option<u32>,
+    pub now_unix: i64,
+}
+
+/// 保持期間を過ぎたメンションを小分けに削除し、削除件数を返す。
+pub async fn execute<R: RetentionRepository>(
+    repo: &R,
+    input: RetentionInput,
+) -> anyhow::Result<u64> {
+    let mut total = 0;
+    for _ in 0..MAX_BATCHES_PER_RUN {
+        let deleted = repo
+            .purge_expired_mentions(input.default_days, input.now_unix, PURGE_BATCH_SIZE)
+            .await?;
+        total += deleted;
+        if deleted < PURGE_BATCH_SIZE as u64 {
+            break;
+        }
+    }
+    Ok(total)
+}
+
+/// 退出してから猶予期間を過ぎたギルドのメンションと設定を削除し、削除したメンション数を返す。
+pub async fn purge_departed_guilds<R: RetentionRepository>(
+    repo: &R,
+    now_unix: i64,
+) -> anyhow::Result<u64> {
+    repo.purge_departed_guilds(now_unix - GUILD_DEPARTURE_GRACE_SECS)
+        .await
+}
+
+/// ギルドの設定を優先する。`Some(0)` は無期限として `None` を返す。
+pub fn effective_retention_days(guild_days: Option<u32>, default_days: Option<u32>) -> Option<u32> {
+    guild_days.or(default_days).filter(|days| *days > 0)
+}
+
+/// 保持期間の設定と、次回の削除で消えるメンションの概要を表示する文面。
+pub fn format_preview(
+    guild_days: Option<u32>,
+    default_days: Option<u32>,
+    preview: PurgePreview,
+) -> String {
+    let policy = match (
+        guild_days,
+        effective_retention_days(guild_days, default_days),
+    ) {
+        (Some(_), Some(days)) => format!("{days}日（このサーバーの設定）"),
+        (Some(_), None) => "無期限（このサーバーの設定）".to_string(),
+        (None, Some(days)) => format!("{days}日（既定値）"),
+        (None, None) => "無期限（既定値）".to_string(),
+    };
+
+    let mut lines = vec![format!("**保持期間**: {policy}")];
+    if preview.mention_count == 0 {
+        lines.push("次回の削除で消えるメンションはありません。".to_string());
+    } else {
+        lines.push(format!(
+            "次回の削除で {}件のメンションが消えます（全員が解決または無視したもの）。",
+            preview.mention_count
+        ));
+        if let Some(oldest) = preview.oldest_created_at_unix {
+            lines.push(format!("最も古いもの: <t:{oldest}:f>"));
+        }
+    }
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use super::*;
+
+    struct FakeRepo {
+        remaining: Mutex<u64>,
+        calls: Mutex<usize>,
+    }
+
+    impl FakeRepo {
+        fn new(remaining: u64) -> Self {
+            Self {
+                remaining: Mutex::new(remaining),
+                calls: Mutex::new(0),
+            }
+        }
+    }
+
+    impl RetentionRepository for FakeRepo {
+        async fn purge_expired_mentions(
+            &self,
+            default_days: Option<u32>,
+            _now_unix: i64,
+            limit: i64,
+        ) -> anyhow::Result<u64> {
+            assert_eq!(default_days, Some(90));
+            *self.calls.lock().unwrap() += 1;
+            let mut remaining = self.remaining.lock().unwrap();
+            let deleted = (*remaining).min(limit as u64);
+            *remaining -= deleted;
+            Ok(deleted)
+        }
+
+        async fn purge_departed_guilds(&self, left_before_unix: i64) -> anyhow::Result<u64> {
+            *self.calls.lock().unwrap() += 1;
+            Ok(left_before_unix as u64)
+        }
+    }
+
+    fn input() -> RetentionInput {
+        RetentionInput {
+            default_days: Some(90),
+            now_unix: 1_000_000,
+        }
+    }
+
+    #[tokio::test]
+    async fn deletes_in_batches_until_exhausted() {
+        let repo = FakeRepo::new(1_200);
+
+        let deleted = execute(&repo, input()).await.unwrap();
+
+        assert_eq!(deleted, 1_200);
+        assert_eq!(*repo.calls.lock().unwrap(), 3);
+    }
+
+    #[tokio::test]
+    async fn stops_after_max_batches() {
+        let repo = FakeRepo::new(u64::MAX);
+
+        let deleted = execute(&repo, input()).await.unwrap();
+
+        assert_eq!(
+            deleted,
+            PURGE_BATCH_SIZE as u64 * MAX_BATCHES_PER_RUN as u64
+        );
+        assert_eq!(*repo.calls.lock().unwrap(), MAX_BATCHES_PER_RUN);
+    }
+
+    #[tokio::test]
+    async fn purges_guilds_departed_before_grace_period() {
+        let repo = FakeRepo::new(0);
+        let now = GUILD_DEPARTURE_GRACE_SECS + 500;
+
+        let left_before = purge_departed_guilds(&repo, now).await.unwrap();
+
+        assert_eq!(left_before, 500);
+    }
+
+    #[test]
+    fn guild_setting_overrides_default() {
+        assert_eq!(effective_retention_days(Some(30), Some(180)), Some(30));
+        assert_eq!(effective_retention_days(Some(0), Some(180)), None);
+        assert_eq!(effective_retention_days(None, Some(180)), Some(180));
+        assert_eq!(effective_retention_days(None, None), None);
+    }
+
+    #[test]
+    fn formats_preview_with_policy_source() {
+        let preview = PurgePreview {
+            mention_count: 12,
+            oldest_created_at_unix: Some(100),
+        };
+
+        let content = format_preview(None, Some(180), preview);
+        assert!(content.contains("180日（既定値）"));
+        assert!(content.contains("12件"));
+        assert!(content.contains("<t:100:f>"));
+
+        let content = format_preview(Some(0), Some(180), PurgePreview::default());
+        assert!(content.contains("無期限（このサーバーの設定）"));
+        assert!(content.contains("消えるメンションはありません"));
+    }
+}
@@ -0,0 +1,267 @@
+use chrono::{DateTime, Utc};
+use validate_macro::async_validate_return;
+
+use crate::usecase::dto::output::discord_exec::validate_plan;
+use crate::usecase::dto::output::status_color::COLOR_READ;
+use crate::usecase::dto::repository::{
+    MentionStats, ReportSettings, StatsFilter, UnreadCount, UnresolvedMention,
+};
+use crate::usecase::dto::{
+    DiscordExecPlan, DiscordExecStep, EmbedFieldPayload, EmbedFooterPayload, EmbedPayload,
+    MessagePayload,
+};
+use crate::usecase::ports::TeamReportRepository;
+use crate::usecase::slash_commands::stats::format_duration;
+
+const ONE_WEEK_SECS: i64 = 7 * 24 * 3600;
+const TOP_UNREAD_USERS_LIMIT: i64 = 5;
+const OLDEST_UNRESOLVED_LIMIT: i64 = 5;
+
+pub struct TeamReportInput {
+    pub settings: ReportSettings,
+    pub now_unix: i64,
+}
+
+/// 先週分のチームレポートを設定されたチャンネルへ投稿するプランを組み立てる。
+#[async_validate_return(validate_plan)]
+pub async fn execute<R: TeamReportRepository>(
+    repo: &R,
+    input: TeamReportInput,
+) -> anyhow::Result<DiscordExecPlan> {
+    let guild_id = input.settings.guild_id;
+    let since_unix = input.now_unix - ONE_WEEK_SECS;
+    let until_unix = input.now_unix;
+
+    let mention_count = repo
+        .count_mentions_in_guild(guild_id, since_unix, until_unix)
+        .await?;
+    let stats = repo
+        .fetch_mention_stats(&StatsFilter {
+            guild_id,
+            since_unix,
+            until_unix,
+            ..Default::default()
+        })
+        .await?;
+    let top_unread = repo
+        .fetch_top_unread_users(guild_id, TOP_UNREAD_USERS_LIMIT)
+        .await?;
+    let oldest = repo
+        .fetch_oldest_unresolved(guild_id, OLDEST_UNRESOLVED_LIMIT)
+        .await?;
+
+    let embed = build_embed(
+        mention_count,
+        &stats,
+        &top_unread,
+        &oldest,
+        input.settings.name_users,
+    );
+    Ok(DiscordExecPlan::new(vec![DiscordExecStep::Send {
+        channel_id: input.settings.channel_id,
+        payload: MessagePayload {
+            embeds: Some(vec![embed]),
+            ..Default::default()
+        },
+    }]))
+}
+
+fn build_embed(
+    mention_count: i64,
+    stats: &MentionStats,
+    top_unread: &[UnreadCount],
+    oldest: &[UnresolvedMention],
+    name_users: bool,
+) -> EmbedPayload {
+    let read_rate = if stats.target_count == 0 {
+        "対象なし".to_string()
+    } else {
+        format!(
+            "{}/{} ({}%)",
+            stats.read_count,
+            stats.target_count,
+            stats.read_count * 100 / stats.target_count
+        )
+    };
+    let read_median = stats
+        .read_median_secs
+        .map(format_duration)
+        .unwrap_or_else(|| "データなし".into());
+
+    EmbedPayload::new()
+        .title("📊 週次チームレポート")
+        .description("先週送信されたメンションの集計です。")
+        .color(COLOR_READ)
+        .field(EmbedFieldPayload::new(
+            "メンション数",
+            format!("{}件", mention_count),
+            true,
+        ))
+        .field(EmbedFieldPayload::new("既読率", read_rate, true))
+        .field(EmbedFieldPayload::new(
+            "既読までの時間 (中央値)",
+            read_median,
+            true,
+        ))
+        .field(EmbedFieldPayload::new(
+            "未読を多く抱えているメンバー",
+            format_top_unread(top_unread, name_users),
+            false,
+        ))
+        .field(EmbedFieldPayload::new(
+            "古い未解決メンション",
+            format_oldest(oldest, name_users),
+            false,
+        ))
+        .footer(EmbedFooterPayload::new(
+            "未読・未解決は期間に関係なく現在の件数です",
+        ))
+}
+
+fn format_top_unread(top_unread: &[UnreadCount], name_users: bool) -> String {
+    if top_unread.is_empty() {
+        return "なし 🎉".into();
+    }
+    top_unread
+        .iter()
+        .enumerate()
+        .map(|(index, entry)| {
+            if name_users {
+                format!(
+                    "{}. <@{}> {}件",
+                    index + 1,
+                    entry.user_id,
+                    entry.unread_count
+                )
+            } else {
+                format!("{}. {}件", index + 1, entry.unread_count)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn format_oldest(oldest: &[UnresolvedMention], name_users: bool) -> String {
+    if oldest.is_empty() {
+        return "なし 🎉".into();
+    }
+    oldest
+        .iter()
+        .map(|mention| {
+            let link = format!(
+                "https://discord.com/channels/{}/{}/{}",
+                mention.guild_id, mention.channel_id, mention.message_id
+            );
+            let date = DateTime::<Utc>::from_timestamp(mention.created_at_unix, 0)
+                .map(|dt| dt.format("%Y-%m-%d").to_string())
+                .unwrap_or_else(|| "不明".to_string());
+            if name_users {
+                format!(
+                    "[{}]({}) <@{}> 未解決 {}人",
+                    date, link, mention.author_id, mention.pending_count
+                )
+            } else {
+                format!("[{}]({}) 未解決 {}人", date, link, mention.pending_count)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct FakeRepository;
+
+    impl TeamReportRepository for FakeRepository {
+        async fn count_mentions_in_guild(
+            &self,
+            _guild_id: u64,
+            _since_unix: i64,
+            _until_unix: i64,
+        ) -> anyhow::Result<i64> {
+            Ok(12)
+        }
+
+        async fn fetch_mention_stats(&self, _filter: &StatsFilter) -> anyhow::Result<MentionStats> {
+            Ok(MentionStats {
+                target_count: 20,
+                read_count: 15,
+                read_median_secs: Some(1_800.0),
+                ..Default::default()
+            })
+        }
+
+        async fn fetch_top_unread_users(
+            &self,
+            _guild_id: u64,
+            _limit: i64,
+        ) -> anyhow::Result<Vec<UnreadCount>> {
+            Ok(vec![UnreadCount {
+                user_id: 10,
+                unread_count: 4,
+            }])
+        }
+
+        async fn fetch_oldest_unresolved(
+            &self,
+            _guild_id: u64,
+            _limit: i64,
+        ) -> anyhow::Result<Vec<UnresolvedMention>> {
+            Ok(vec![UnresolvedMention {
+                guild_id: 1,
+                channel_id: 2,
+                message_id: 3,
+                author_id: 20,
+                created_at_unix: 1_700_000_000,
+                pending_count: 2,
+            }])
+        }
+    }
+
+    async fn report(name_users: bool) -> (u64, EmbedPayload) {
+        let input = TeamReportInput {
+            settings: ReportSettings {
+                guild_id: 1,
+                channel_id: 99,
+                name_users,
+            },
+            now_unix: 1_700_600_000,
+        };
+        let plan = execute(&FakeRepository, input)
+            .await
+            .expect("expected plan");
+        match plan.into_steps().into_iter().next() {
+            Some(DiscordExecStep::Send {
+                channel_id,
+                payload,
+            }) => (
+                channel_id,
+                payload.embeds.expect("expected embeds").remove(0),
+            ),
+            other => panic!("unexpected exec step: {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn posts_summary_to_configured_channel() {
+        let (channel_id, embed) = report(true).await;
+        assert_eq!(channel_id, 99);
+        assert_eq!(embed.fields[0].value, "12件");
+        assert_eq!(embed.fields[1].value, "15/20 (75%)");
+        assert_eq!(embed.fields[2].value, "30分");
+        assert_eq!(embed.fields[3].value, "1. <@10> 4件");
+        assert_eq!(
+            embed.fields[4].value,
+            "[2023-11-14](https://discord.com/channels/1/2/3) <@20> 未解決 2人"
+        );
+    }
+
+    #[tokio::test]
+    async fn omits_names_when_opted_out() {
+        let (_, embed) = report(false).await;
+        assert_eq!(embed.fields[3].value, "1. 4件");
+        assert!(!embed.fields[4].value.contains("<@"));
+    }
+}
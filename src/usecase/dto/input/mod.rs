@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+
 use serenity::model::prelude::{ChannelId, MessageId, RoleId, UserId};
 
 #[derive(Debug, Clone)]
@@ -26,3 +28,38 @@ pub struct MessageInputDto {
     pub mentions_everyone: bool,
     pub is_reply: bool,
 }
+
+/// セレクトメニューで選択された値。メニューの種類ごとに型付けされている。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SelectedValues {
+    Strings(Vec<String>),
+    Users(Vec<u64>),
+    Roles(Vec<u64>),
+    Mentionables { users: Vec<u64>, roles: Vec<u64> },
+    Channels(Vec<u64>),
+}
+
+#[derive(Debug, Clone)]
+pub struct SelectMenuInput {
+    pub custom_id: String,
+    pub user_id: u64,
+    pub values: SelectedValues,
+}
+
+/// モーダル送信で受け取ったテキスト入力。`TextInputPayload` の custom_id をキーに値を引く。
+#[derive(Debug, Clone)]
+pub struct ModalSubmitInput {
+    pub custom_id: String,
+    pub user_id: u64,
+    pub values: HashMap<String, String>,
+}
+
+impl ModalSubmitInput {
+    /// 指定した入力欄の値を返す。未入力（空文字）の任意項目は `None` として扱う。
+    pub fn value(&self, input_custom_id: &str) -> Option<&str> {
+        self.values
+            .get(input_custom_id)
+            .map(String::as_str)
+            .filter(|value| !value.is_empty())
+    }
+}
@@ -1,12 +1,15 @@
 pub mod input;
 pub mod output;
+pub mod repository;
 
 #[allow(unused_imports)]
-pub use input::{MessageInput, MessageInputDto};
+pub use input::{MessageInput, MessageInputDto, ModalSubmitInput, SelectMenuInput, SelectedValues};
 pub use output::discord_exec::{
-    ActionRowPayload, ButtonPayload, ButtonStylePayload, DeferPayload, DiscordExecPlan,
-    DiscordExecStep, EmbedPayload, MessagePayload, ModalPayload, SelectMenuPayload,
-    SelectOptionPayload, TextInputPayload, TextInputStylePayload,
+    ActionRowPayload, AllowedMentionsPayload, ButtonPayload, ButtonStylePayload,
+    ChannelTypePayload, DeferPayload, DiscordExecPlan, DiscordExecStep, EmbedAuthorPayload,
+    EmbedFieldPayload, EmbedFooterPayload, EmbedPayload, MessagePayload, ModalPayload,
+    SelectMenuKindPayload, SelectMenuPayload, SelectOptionPayload, TextInputPayload,
+    TextInputStylePayload,
 };
 pub use output::error::validation::PlanValidationError;
 pub use output::mvp::{AddReadReactionOutputDto, HelpCommandDto, HelpOutputDto, UsecaseError};
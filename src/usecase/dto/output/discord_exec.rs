@@ -1,7 +1,39 @@
 #![allow(dead_code)]
 
+use validate_core::rules::max_len;
+use validate_core::{field_path, index_path, Validate, ValidationErrors, ViolationKind};
+use validate_macro::Validate;
+
 use super::error::validation::PlanValidationError;
 
+/// Discord API の上限値。各ペイロードの `#[validate]` 属性で参照し、
+/// 送信前に `validate_plan` で検証する。
+pub const MAX_CONTENT_CHARS: usize = 2000;
+pub const MAX_EMBEDS: usize = 10;
+pub const MAX_EMBED_TITLE_CHARS: usize = 256;
+pub const MAX_EMBED_DESCRIPTION_CHARS: usize = 4096;
+pub const MAX_EMBED_FIELDS: usize = 25;
+pub const MAX_EMBED_FIELD_NAME_CHARS: usize = 256;
+pub const MAX_EMBED_FIELD_VALUE_CHARS: usize = 1024;
+pub const MAX_EMBED_FOOTER_CHARS: usize = 2048;
+pub const MAX_EMBED_AUTHOR_NAME_CHARS: usize = 256;
+/// 1メッセージ内の全 Embed のタイトル・説明・フィールド・フッター・作成者名の合計。
+pub const MAX_EMBED_TOTAL_CHARS: usize = 6000;
+pub const MAX_ACTION_ROWS: usize = 5;
+pub const MAX_BUTTONS_PER_ROW: usize = 5;
+pub const MAX_BUTTON_LABEL_CHARS: usize = 80;
+pub const MAX_CUSTOM_ID_CHARS: usize = 100;
+pub const MAX_SELECT_OPTIONS: usize = 25;
+pub const MAX_SELECT_PLACEHOLDER_CHARS: usize = 150;
+pub const MAX_SELECT_OPTION_LABEL_CHARS: usize = 100;
+pub const MAX_SELECT_OPTION_VALUE_CHARS: usize = 100;
+pub const MAX_SELECT_OPTION_DESCRIPTION_CHARS: usize = 100;
+pub const MAX_MODAL_TITLE_CHARS: usize = 45;
+pub const MAX_MODAL_INPUTS: usize = 5;
+pub const MAX_TEXT_INPUT_LABEL_CHARS: usize = 45;
+pub const MAX_TEXT_INPUT_PLACEHOLDER_CHARS: usize = 100;
+pub const MAX_TEXT_INPUT_VALUE_CHARS: usize = 4000;
+
 #[derive(Debug, Clone)]
 pub struct DiscordExecPlan {
     steps: Vec<DiscordExecStep>,
@@ -27,6 +59,24 @@ pub enum DiscordExecStep {
         channel_id: u64,
         payload: MessagePayload,
     },
+    /// ユーザーへの DM として送信する。
+    SendDm {
+        user_id: u64,
+        payload: MessagePayload,
+    },
+    /// 指定したメッセージへの返信として送信する。
+    Reply {
+        channel_id: u64,
+        message_id: u64,
+        payload: MessagePayload,
+    },
+    /// 指定したメッセージのスレッドに送信する。スレッドが無ければ `thread_name` で作成する。
+    SendInThread {
+        channel_id: u64,
+        message_id: u64,
+        thread_name: String,
+        payload: MessagePayload,
+    },
     Defer(DeferPayload),
     Response(MessagePayload),
     EditOriginal(MessagePayload),
@@ -35,7 +85,8 @@ pub enum DiscordExecStep {
 }
 
 pub fn validate_plan(plan: &DiscordExecPlan) -> Result<(), PlanValidationError> {
-    validate_steps(&plan.steps)
+    validate_steps(&plan.steps)?;
+    validate_payloads(&plan.steps)
 }
 
 fn validate_steps(steps: &[DiscordExecStep]) -> Result<(), PlanValidationError> {
@@ -43,12 +94,8 @@ fn validate_steps(steps: &[DiscordExecStep]) -> Result<(), PlanValidationError>
         return Ok(());
     }
 
-    let has_send = steps
-        .iter()
-        .any(|step| matches!(step, DiscordExecStep::Send { .. }));
-    let has_interaction = steps
-        .iter()
-        .any(|step| !matches!(step, DiscordExecStep::Send { .. }));
+    let has_send = steps.iter().any(is_send_step);
+    let has_interaction = steps.iter().any(|step| !is_send_step(step));
 
     if has_send && has_interaction {
         return Err(PlanValidationError::MixedSendAndInteraction);
@@ -103,10 +150,71 @@ fn validate_steps(steps: &[DiscordExecStep]) -> Result<(), PlanValidationError>
     Ok(())
 }
 
+/// インタラクションを介さずに送信するステップか。
+fn is_send_step(step: &DiscordExecStep) -> bool {
+    matches!(
+        step,
+        DiscordExecStep::Send { .. }
+            | DiscordExecStep::SendDm { .. }
+            | DiscordExecStep::Reply { .. }
+            | DiscordExecStep::SendInThread { .. }
+    )
+}
+
+fn validate_payloads(steps: &[DiscordExecStep]) -> Result<(), PlanValidationError> {
+    let mut errors = ValidationErrors::default();
+    for (index, step) in steps.iter().enumerate() {
+        let path = index_path("steps", index);
+        match step {
+            DiscordExecStep::Send { payload, .. }
+            | DiscordExecStep::SendDm { payload, .. }
+            | DiscordExecStep::Reply { payload, .. }
+            | DiscordExecStep::SendInThread { payload, .. }
+            | DiscordExecStep::Response(payload)
+            | DiscordExecStep::EditOriginal(payload)
+            | DiscordExecStep::FollowUp(payload) => {
+                payload.validate_into(&path, &mut errors);
+                validate_embed_total(payload, &path, &mut errors);
+            }
+            DiscordExecStep::Defer(_) => {}
+            DiscordExecStep::OpenModal(modal) => modal.validate_into(&path, &mut errors),
+        }
+    }
+    errors
+        .into_result()
+        .map_err(PlanValidationError::InvalidPayload)
+}
+
+/// Embed ごとの上限とは別に、Discord はメッセージ内の Embed の合計文字数も制限する。
+fn validate_embed_total(payload: &MessagePayload, path: &str, errors: &mut ValidationErrors) {
+    let Some(embeds) = &payload.embeds else {
+        return;
+    };
+    let len = embeds.iter().map(EmbedPayload::total_chars).sum();
+    if len > MAX_EMBED_TOTAL_CHARS {
+        errors.push(
+            field_path(path, "embeds"),
+            ViolationKind::MaxChars {
+                len,
+                max: MAX_EMBED_TOTAL_CHARS,
+            },
+        );
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::super::error::validation::PlanValidationError;
-    use super::{validate_plan, DiscordExecPlan, DiscordExecStep, MessagePayload};
+    use super::{
+        validate_plan, ActionRowPayload, ButtonPayload, ChannelTypePayload, DiscordExecPlan,
+        DiscordExecStep, EmbedFieldPayload, EmbedPayload, MessagePayload, SelectMenuKindPayload,
+        SelectMenuPayload, SelectOptionPayload, TextInputPayload, TextInputStylePayload,
+    };
+    use validate_core::ViolationKind;
+
+    fn response_plan(payload: MessagePayload) -> DiscordExecPlan {
+        DiscordExecPlan::new(vec![DiscordExecStep::Response(payload)])
+    }
 
     #[test]
     fn allows_empty_plan() {
@@ -128,6 +236,39 @@ mod tests {
         assert_eq!(err, PlanValidationError::MixedSendAndInteraction);
     }
 
+    #[test]
+    fn treats_dm_reply_and_thread_as_send_steps() {
+        let steps = vec![
+            DiscordExecStep::SendDm {
+                user_id: 1,
+                payload: MessagePayload::default(),
+            },
+            DiscordExecStep::Reply {
+                channel_id: 2,
+                message_id: 3,
+                payload: MessagePayload::default(),
+            },
+            DiscordExecStep::SendInThread {
+                channel_id: 2,
+                message_id: 3,
+                thread_name: "thread".into(),
+                payload: MessagePayload::default(),
+            },
+        ];
+        assert!(validate_plan(&DiscordExecPlan::new(steps)).is_ok());
+
+        let mixed = vec![
+            DiscordExecStep::SendDm {
+                user_id: 1,
+                payload: MessagePayload::default(),
+            },
+            DiscordExecStep::FollowUp(MessagePayload::default()),
+        ];
+        let err =
+            validate_plan(&DiscordExecPlan::new(mixed)).expect_err("expected validation error");
+        assert_eq!(err, PlanValidationError::MixedSendAndInteraction);
+    }
+
     #[test]
     fn rejects_response_not_first() {
         let steps = vec![
@@ -149,6 +290,328 @@ mod tests {
             validate_plan(&DiscordExecPlan::new(steps)).expect_err("expected validation error");
         assert_eq!(err, PlanValidationError::OpenModalNotExclusive);
     }
+
+    fn violations(err: PlanValidationError) -> Vec<(String, ViolationKind)> {
+        match err {
+            PlanValidationError::InvalidPayload(errors) => errors
+                .violations()
+                .iter()
+                .map(|v| (v.path.clone(), v.kind.clone()))
+                .collect(),
+            other => panic!("unexpected validation error: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn rejects_content_over_limit() {
+        let payload = MessagePayload {
+            content: Some("あ".repeat(2001)),
+            ..Default::default()
+        };
+        let err = validate_plan(&response_plan(payload)).expect_err("expected validation error");
+        assert_eq!(
+            violations(err),
+            vec![(
+                "steps[0].content".into(),
+                ViolationKind::MaxChars {
+                    len: 2001,
+                    max: 2000
+                }
+            )]
+        );
+    }
+
+    #[test]
+    fn allows_content_at_limit() {
+        let payload = MessagePayload {
+            content: Some("あ".repeat(2000)),
+            ..Default::default()
+        };
+        assert!(validate_plan(&response_plan(payload)).is_ok());
+    }
+
+    #[test]
+    fn rejects_too_many_embeds() {
+        let payload = MessagePayload {
+            embeds: Some(vec![EmbedPayload::new(); 11]),
+            ..Default::default()
+        };
+        let err = validate_plan(&response_plan(payload)).expect_err("expected validation error");
+        assert_eq!(
+            violations(err),
+            vec![(
+                "steps[0].embeds".into(),
+                ViolationKind::MaxLen { len: 11, max: 10 }
+            )]
+        );
+    }
+
+    #[test]
+    fn rejects_too_many_embed_fields() {
+        let embed = (0..26).fold(EmbedPayload::new(), |embed, i| {
+            embed.field(EmbedFieldPayload::new(format!("f{i}"), "v", false))
+        });
+        let payload = MessagePayload {
+            embeds: Some(vec![embed]),
+            ..Default::default()
+        };
+        let err = validate_plan(&response_plan(payload)).expect_err("expected validation error");
+        assert_eq!(
+            violations(err),
+            vec![(
+                "steps[0].embeds[0].fields".into(),
+                ViolationKind::MaxLen { len: 26, max: 25 }
+            )]
+        );
+    }
+
+    #[test]
+    fn rejects_embed_field_value_over_limit() {
+        let embed = EmbedPayload::new().field(EmbedFieldPayload::new("f", "x".repeat(1025), false));
+        let payload = MessagePayload {
+            embeds: Some(vec![embed]),
+            ..Default::default()
+        };
+        let err = validate_plan(&response_plan(payload)).expect_err("expected validation error");
+        assert_eq!(
+            violations(err),
+            vec![(
+                "steps[0].embeds[0].fields[0].value".into(),
+                ViolationKind::MaxChars {
+                    len: 1025,
+                    max: 1024
+                }
+            )]
+        );
+    }
+
+    #[test]
+    fn rejects_embed_total_over_limit() {
+        let embed = EmbedPayload::new()
+            .title("t".repeat(256))
+            .description("d".repeat(4096));
+        let payload = MessagePayload {
+            embeds: Some(vec![embed.clone(), embed]),
+            ..Default::default()
+        };
+        let err = validate_plan(&response_plan(payload)).expect_err("expected validation error");
+        assert_eq!(
+            violations(err),
+            vec![(
+                "steps[0].embeds".into(),
+                ViolationKind::MaxChars {
+                    len: 8704,
+                    max: 6000
+                }
+            )]
+        );
+    }
+
+    #[test]
+    fn allows_embed_total_at_limit() {
+        let embed = EmbedPayload::new()
+            .description("d".repeat(4000))
+            .field(EmbedFieldPayload::new(
+                "n".repeat(200),
+                "v".repeat(800),
+                false,
+            ))
+            .footer(super::EmbedFooterPayload::new("f".repeat(900)))
+            .author(super::EmbedAuthorPayload::new("a".repeat(100)));
+        let payload = MessagePayload {
+            embeds: Some(vec![embed]),
+            ..Default::default()
+        };
+        assert!(validate_plan(&response_plan(payload)).is_ok());
+    }
+
+    #[test]
+    fn rejects_select_option_over_limit() {
+        let mut option = SelectOptionPayload::new("l".repeat(101), "v".repeat(101));
+        option.description = Some("d".repeat(101));
+        let menu =
+            SelectMenuPayload::new("sel", vec![SelectOptionPayload::new("ok", "ok"), option]);
+        let payload = MessagePayload {
+            components: Some(vec![ActionRowPayload::SelectMenu(menu)]),
+            ..Default::default()
+        };
+        let err = validate_plan(&response_plan(payload)).expect_err("expected validation error");
+        let over = ViolationKind::MaxChars { len: 101, max: 100 };
+        assert_eq!(
+            violations(err),
+            vec![
+                (
+                    "steps[0].components[0].kind.options[1].label".into(),
+                    over.clone()
+                ),
+                (
+                    "steps[0].components[0].kind.options[1].value".into(),
+                    over.clone()
+                ),
+                (
+                    "steps[0].components[0].kind.options[1].description".into(),
+                    over
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_too_many_default_channels() {
+        let menu = SelectMenuPayload::channels("sel", vec![ChannelTypePayload::Text])
+            .default_channels((0..26).collect());
+        let payload = MessagePayload {
+            components: Some(vec![ActionRowPayload::SelectMenu(menu)]),
+            ..Default::default()
+        };
+        let err = validate_plan(&response_plan(payload)).expect_err("expected validation error");
+        assert_eq!(
+            violations(err),
+            vec![(
+                "steps[0].components[0].kind.default_channels".into(),
+                ViolationKind::MaxLen { len: 26, max: 25 }
+            )]
+        );
+    }
+
+    #[test]
+    fn counts_mentionable_defaults_together() {
+        let menu = SelectMenuPayload::mentionables("sel")
+            .default_users((0..13).collect())
+            .default_roles((0..13).collect());
+        let payload = MessagePayload {
+            components: Some(vec![ActionRowPayload::SelectMenu(menu)]),
+            ..Default::default()
+        };
+        let err = validate_plan(&response_plan(payload)).expect_err("expected validation error");
+        assert_eq!(
+            violations(err),
+            vec![(
+                "steps[0].components[0].kind.default_values".into(),
+                ViolationKind::MaxLen { len: 26, max: 25 }
+            )]
+        );
+    }
+
+    #[test]
+    fn ignores_defaults_for_mismatched_kind() {
+        let menu = SelectMenuPayload::users("sel").default_channels(vec![1]);
+        assert!(matches!(
+            menu.kind,
+            SelectMenuKindPayload::User { ref default_users } if default_users.is_empty()
+        ));
+    }
+
+    #[test]
+    fn rejects_too_many_action_rows() {
+        let row = ActionRowPayload::Buttons(vec![ButtonPayload::new("id", "label")]);
+        let payload = MessagePayload {
+            components: Some(vec![row; 6]),
+            ..Default::default()
+        };
+        let err = validate_plan(&response_plan(payload)).expect_err("expected validation error");
+        assert_eq!(
+            violations(err),
+            vec![(
+                "steps[0].components".into(),
+                ViolationKind::MaxLen { len: 6, max: 5 }
+            )]
+        );
+    }
+
+    #[test]
+    fn rejects_too_many_buttons_in_row() {
+        let buttons = (0..6)
+            .map(|i| ButtonPayload::new(format!("id{i}"), "label"))
+            .collect();
+        let payload = MessagePayload {
+            components: Some(vec![ActionRowPayload::Buttons(buttons)]),
+            ..Default::default()
+        };
+        let err = validate_plan(&response_plan(payload)).expect_err("expected validation error");
+        assert_eq!(
+            violations(err),
+            vec![(
+                "steps[0].components[0]".into(),
+                ViolationKind::MaxLen { len: 6, max: 5 }
+            )]
+        );
+    }
+
+    #[test]
+    fn rejects_custom_id_over_limit() {
+        let button = ButtonPayload::new("x".repeat(101), "label");
+        let payload = MessagePayload {
+            components: Some(vec![ActionRowPayload::Buttons(vec![button])]),
+            ..Default::default()
+        };
+        let err = validate_plan(&response_plan(payload)).expect_err("expected validation error");
+        assert_eq!(
+            violations(err),
+            vec![(
+                "steps[0].components[0][0].custom_id".into(),
+                ViolationKind::MaxChars { len: 101, max: 100 }
+            )]
+        );
+    }
+
+    #[test]
+    fn rejects_modal_with_too_many_inputs() {
+        let inputs = (0..6)
+            .map(|i| TextInputPayload::new(format!("in{i}"), "label", TextInputStylePayload::Short))
+            .collect();
+        let steps = vec![DiscordExecStep::OpenModal(super::ModalPayload::new(
+            "id", "title", inputs,
+        ))];
+        let err =
+            validate_plan(&DiscordExecPlan::new(steps)).expect_err("expected validation error");
+        assert_eq!(
+            violations(err),
+            vec![(
+                "steps[0].inputs".into(),
+                ViolationKind::MaxLen { len: 6, max: 5 }
+            )]
+        );
+    }
+
+    #[test]
+    fn validates_send_payloads() {
+        let steps = vec![DiscordExecStep::Send {
+            channel_id: 1,
+            payload: MessagePayload {
+                content: Some("x".repeat(2001)),
+                ..Default::default()
+            },
+        }];
+        let err =
+            validate_plan(&DiscordExecPlan::new(steps)).expect_err("expected validation error");
+        assert!(matches!(err, PlanValidationError::InvalidPayload(_)));
+    }
+
+    #[test]
+    fn lists_every_violating_field() {
+        let embed = EmbedPayload::new()
+            .field(EmbedFieldPayload::new("", "ok", false))
+            .field(EmbedFieldPayload::new("ok", "", false));
+        let payload = MessagePayload {
+            content: Some("x".repeat(2001)),
+            embeds: Some(vec![embed]),
+            ..Default::default()
+        };
+        let err = validate_plan(&response_plan(payload)).expect_err("expected validation error");
+        let paths = violations(err)
+            .into_iter()
+            .map(|(path, _)| path)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            paths,
+            vec![
+                "steps[0].content",
+                "steps[0].embeds[0].fields[0].name",
+                "steps[0].embeds[0].fields[1].value",
+            ]
+        );
+    }
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -166,19 +629,57 @@ impl DeferPayload {
     }
 }
 
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Validate)]
 pub struct MessagePayload {
+    #[validate(max_chars = MAX_CONTENT_CHARS)]
     pub content: Option<String>,
+    #[validate(max_len = MAX_EMBEDS, nested)]
     pub embeds: Option<Vec<EmbedPayload>>,
+    #[validate(max_len = MAX_ACTION_ROWS, nested)]
     pub components: Option<Vec<ActionRowPayload>>,
     pub ephemeral: Option<bool>,
+    /// 実際に通知するメンション。`None` なら本文中のメンションをすべて通知する。
+    /// ユーザーが書いた文章を Bot が投稿する場合は、意図した相手だけに絞る。
+    pub allowed_mentions: Option<AllowedMentionsPayload>,
 }
 
-#[derive(Debug, Clone, Default)]
+/// メッセージで通知を許可するメンション。ここに含まれないメンションは表示されるだけで通知されない。
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AllowedMentionsPayload {
+    pub user_ids: Vec<u64>,
+    pub role_ids: Vec<u64>,
+    pub everyone: bool,
+}
+
+impl AllowedMentionsPayload {
+    /// 指定したユーザーだけを通知する。
+    pub fn users(user_ids: Vec<u64>) -> Self {
+        Self {
+            user_ids,
+            ..Self::default()
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Validate)]
 pub struct EmbedPayload {
+    #[validate(max_chars = MAX_EMBED_TITLE_CHARS)]
     pub title: Option<String>,
+    #[validate(max_chars = MAX_EMBED_DESCRIPTION_CHARS)]
     pub description: Option<String>,
+    #[validate(max_len = MAX_EMBED_FIELDS, nested)]
     pub fields: Vec<EmbedFieldPayload>,
+    /// 0xRRGGBB
+    pub color: Option<u32>,
+    pub url: Option<String>,
+    #[validate(nested)]
+    pub footer: Option<EmbedFooterPayload>,
+    #[validate(nested)]
+    pub author: Option<EmbedAuthorPayload>,
+    /// UNIX 秒
+    pub timestamp: Option<i64>,
+    pub thumbnail_url: Option<String>,
+    pub image_url: Option<String>,
 }
 
 impl EmbedPayload {
@@ -200,11 +701,111 @@ impl EmbedPayload {
         self.fields.push(field);
         self
     }
+
+    pub fn color(mut self, color: u32) -> Self {
+        self.color = Some(color);
+        self
+    }
+
+    pub fn url(mut self, url: impl Into<String>) -> Self {
+        self.url = Some(url.into());
+        self
+    }
+
+    pub fn footer(mut self, footer: EmbedFooterPayload) -> Self {
+        self.footer = Some(footer);
+        self
+    }
+
+    pub fn author(mut self, author: EmbedAuthorPayload) -> Self {
+        self.author = Some(author);
+        self
+    }
+
+    pub fn timestamp(mut self, unix: i64) -> Self {
+        self.timestamp = Some(unix);
+        self
+    }
+
+    pub fn thumbnail(mut self, url: impl Into<String>) -> Self {
+        self.thumbnail_url = Some(url.into());
+        self
+    }
+
+    pub fn image(mut self, url: impl Into<String>) -> Self {
+        self.image_url = Some(url.into());
+        self
+    }
+
+    /// `MAX_EMBED_TOTAL_CHARS` の対象になる文字数。
+    pub fn total_chars(&self) -> usize {
+        let chars = |text: &str| text.chars().count();
+        self.title.as_deref().map_or(0, chars)
+            + self.description.as_deref().map_or(0, chars)
+            + self
+                .fields
+                .iter()
+                .map(|field| chars(&field.name) + chars(&field.value))
+                .sum::<usize>()
+            + self.footer.as_ref().map_or(0, |footer| chars(&footer.text))
+            + self.author.as_ref().map_or(0, |author| chars(&author.name))
+    }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Validate)]
+pub struct EmbedFooterPayload {
+    #[validate(non_empty, max_chars = MAX_EMBED_FOOTER_CHARS)]
+    pub text: String,
+    pub icon_url: Option<String>,
+}
+
+impl EmbedFooterPayload {
+    pub fn new(text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            icon_url: None,
+        }
+    }
+
+    pub fn icon_url(mut self, icon_url: impl Into<String>) -> Self {
+        self.icon_url = Some(icon_url.into());
+        self
+    }
+}
+
+#[derive(Debug, Clone, Validate)]
+pub struct EmbedAuthorPayload {
+    #[validate(non_empty, max_chars = MAX_EMBED_AUTHOR_NAME_CHARS)]
+    pub name: String,
+    pub url: Option<String>,
+    pub icon_url: Option<String>,
+}
+
+impl EmbedAuthorPayload {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            url: None,
+            icon_url: None,
+        }
+    }
+
+    pub fn url(mut self, url: impl Into<String>) -> Self {
+        self.url = Some(url.into());
+        self
+    }
+
+    pub fn icon_url(mut self, icon_url: impl Into<String>) -> Self {
+        self.icon_url = Some(icon_url.into());
+        self
+    }
+}
+
+#[derive(Debug, Clone, Validate)]
 pub struct EmbedFieldPayload {
+    #[validate(non_empty, max_chars = MAX_EMBED_FIELD_NAME_CHARS)]
     pub name: String,
+    #[validate(non_empty, max_chars = MAX_EMBED_FIELD_VALUE_CHARS)]
     pub value: String,
     pub inline: bool,
 }
@@ -226,6 +827,19 @@ pub enum ActionRowPayload {
     InputText(TextInputPayload),
 }
 
+impl Validate for ActionRowPayload {
+    fn validate_into(&self, path: &str, errors: &mut ValidationErrors) {
+        match self {
+            ActionRowPayload::Buttons(buttons) => {
+                max_len(buttons, MAX_BUTTONS_PER_ROW, path, errors);
+                buttons.validate_into(path, errors);
+            }
+            ActionRowPayload::SelectMenu(menu) => menu.validate_into(path, errors),
+            ActionRowPayload::InputText(input) => input.validate_into(path, errors),
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub enum ButtonStylePayload {
     Primary,
@@ -235,10 +849,12 @@ pub enum ButtonStylePayload {
     Link,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Validate)]
 pub struct ButtonPayload {
     pub style: ButtonStylePayload,
+    #[validate(max_chars = MAX_BUTTON_LABEL_CHARS)]
     pub label: Option<String>,
+    #[validate(non_empty, max_chars = MAX_CUSTOM_ID_CHARS)]
     pub custom_id: Option<String>,
     pub url: Option<String>,
     pub disabled: bool,
@@ -264,12 +880,25 @@ impl ButtonPayload {
             disabled: false,
         }
     }
+
+    pub fn style(mut self, style: ButtonStylePayload) -> Self {
+        self.style = style;
+        self
+    }
+
+    pub fn disabled(mut self, disabled: bool) -> Self {
+        self.disabled = disabled;
+        self
+    }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Validate)]
 pub struct SelectMenuPayload {
+    #[validate(non_empty, max_chars = MAX_CUSTOM_ID_CHARS)]
     pub custom_id: String,
-    pub options: Vec<SelectOptionPayload>,
+    #[validate(nested)]
+    pub kind: SelectMenuKindPayload,
+    #[validate(max_chars = MAX_SELECT_PLACEHOLDER_CHARS)]
     pub placeholder: Option<String>,
     pub min_values: Option<u8>,
     pub max_values: Option<u8>,
@@ -278,21 +907,203 @@ pub struct SelectMenuPayload {
 
 impl SelectMenuPayload {
     pub fn new(custom_id: impl Into<String>, options: Vec<SelectOptionPayload>) -> Self {
+        Self::with_kind(custom_id, SelectMenuKindPayload::String { options })
+    }
+
+    pub fn users(custom_id: impl Into<String>) -> Self {
+        Self::with_kind(
+            custom_id,
+            SelectMenuKindPayload::User {
+                default_users: Vec::new(),
+            },
+        )
+    }
+
+    pub fn roles(custom_id: impl Into<String>) -> Self {
+        Self::with_kind(
+            custom_id,
+            SelectMenuKindPayload::Role {
+                default_roles: Vec::new(),
+            },
+        )
+    }
+
+    pub fn mentionables(custom_id: impl Into<String>) -> Self {
+        Self::with_kind(
+            custom_id,
+            SelectMenuKindPayload::Mentionable {
+                default_users: Vec::new(),
+                default_roles: Vec::new(),
+            },
+        )
+    }
+
+    /// `channel_types` が空の場合はすべての種類のチャンネルを選択できる。
+    pub fn channels(custom_id: impl Into<String>, channel_types: Vec<ChannelTypePayload>) -> Self {
+        Self::with_kind(
+            custom_id,
+            SelectMenuKindPayload::Channel {
+                channel_types,
+                default_channels: Vec::new(),
+            },
+        )
+    }
+
+    fn with_kind(custom_id: impl Into<String>, kind: SelectMenuKindPayload) -> Self {
         Self {
             custom_id: custom_id.into(),
-            options,
+            kind,
             placeholder: None,
             min_values: None,
             max_values: None,
             disabled: false,
         }
     }
+
+    pub fn placeholder(mut self, placeholder: impl Into<String>) -> Self {
+        self.placeholder = Some(placeholder.into());
+        self
+    }
+
+    pub fn min_values(mut self, min: u8) -> Self {
+        self.min_values = Some(min);
+        self
+    }
+
+    pub fn max_values(mut self, max: u8) -> Self {
+        self.max_values = Some(max);
+        self
+    }
+
+    /// 初期選択値を設定する。String 以外の種類でのみ有効で、
+    /// Mentionable では `default_users` に設定する（ロールは `default_roles` を使う）。
+    pub fn default_users(mut self, user_ids: Vec<u64>) -> Self {
+        match &mut self.kind {
+            SelectMenuKindPayload::User { default_users }
+            | SelectMenuKindPayload::Mentionable { default_users, .. } => {
+                *default_users = user_ids;
+            }
+            _ => {}
+        }
+        self
+    }
+
+    pub fn default_roles(mut self, role_ids: Vec<u64>) -> Self {
+        match &mut self.kind {
+            SelectMenuKindPayload::Role { default_roles }
+            | SelectMenuKindPayload::Mentionable { default_roles, .. } => {
+                *default_roles = role_ids;
+            }
+            _ => {}
+        }
+        self
+    }
+
+    pub fn default_channels(mut self, channel_ids: Vec<u64>) -> Self {
+        if let SelectMenuKindPayload::Channel {
+            default_channels, ..
+        } = &mut self.kind
+        {
+            *default_channels = channel_ids;
+        }
+        self
+    }
 }
 
 #[derive(Debug, Clone)]
+pub enum SelectMenuKindPayload {
+    String {
+        options: Vec<SelectOptionPayload>,
+    },
+    User {
+        default_users: Vec<u64>,
+    },
+    Role {
+        default_roles: Vec<u64>,
+    },
+    Mentionable {
+        default_users: Vec<u64>,
+        default_roles: Vec<u64>,
+    },
+    Channel {
+        channel_types: Vec<ChannelTypePayload>,
+        default_channels: Vec<u64>,
+    },
+}
+
+impl Validate for SelectMenuKindPayload {
+    fn validate_into(&self, path: &str, errors: &mut ValidationErrors) {
+        match self {
+            SelectMenuKindPayload::String { options } => {
+                let path = field_path(path, "options");
+                max_len(options, MAX_SELECT_OPTIONS, &path, errors);
+                options.validate_into(&path, errors);
+            }
+            SelectMenuKindPayload::User { default_users } => {
+                max_len(
+                    default_users,
+                    MAX_SELECT_OPTIONS,
+                    &field_path(path, "default_users"),
+                    errors,
+                );
+            }
+            SelectMenuKindPayload::Role { default_roles } => {
+                max_len(
+                    default_roles,
+                    MAX_SELECT_OPTIONS,
+                    &field_path(path, "default_roles"),
+                    errors,
+                );
+            }
+            SelectMenuKindPayload::Mentionable {
+                default_users,
+                default_roles,
+            } => {
+                let count = default_users.len() + default_roles.len();
+                if count > MAX_SELECT_OPTIONS {
+                    errors.push(
+                        field_path(path, "default_values"),
+                        ViolationKind::MaxLen {
+                            len: count,
+                            max: MAX_SELECT_OPTIONS,
+                        },
+                    );
+                }
+            }
+            SelectMenuKindPayload::Channel {
+                default_channels, ..
+            } => {
+                max_len(
+                    default_channels,
+                    MAX_SELECT_OPTIONS,
+                    &field_path(path, "default_channels"),
+                    errors,
+                );
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChannelTypePayload {
+    Text,
+    Voice,
+    Category,
+    News,
+    Stage,
+    Forum,
+    PublicThread,
+    PrivateThread,
+    NewsThread,
+}
+
+#[derive(Debug, Clone, Validate)]
 pub struct SelectOptionPayload {
+    #[validate(max_chars = MAX_SELECT_OPTION_LABEL_CHARS)]
     pub label: String,
+    #[validate(max_chars = MAX_SELECT_OPTION_VALUE_CHARS)]
     pub value: String,
+    #[validate(max_chars = MAX_SELECT_OPTION_DESCRIPTION_CHARS)]
     pub description: Option<String>,
     pub default: bool,
 }
@@ -308,10 +1119,13 @@ impl SelectOptionPayload {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Validate)]
 pub struct ModalPayload {
+    #[validate(non_empty, max_chars = MAX_CUSTOM_ID_CHARS)]
     pub custom_id: String,
+    #[validate(non_empty, max_chars = MAX_MODAL_TITLE_CHARS)]
     pub title: String,
+    #[validate(non_empty, max_len = MAX_MODAL_INPUTS, nested)]
     pub inputs: Vec<TextInputPayload>,
 }
 
@@ -335,15 +1149,19 @@ pub enum TextInputStylePayload {
     Paragraph,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Validate)]
 pub struct TextInputPayload {
+    #[validate(non_empty, max_chars = MAX_CUSTOM_ID_CHARS)]
     pub custom_id: String,
+    #[validate(non_empty, max_chars = MAX_TEXT_INPUT_LABEL_CHARS)]
     pub label: String,
     pub style: TextInputStylePayload,
+    #[validate(max_chars = MAX_TEXT_INPUT_PLACEHOLDER_CHARS)]
     pub placeholder: Option<String>,
     pub min_length: Option<u16>,
     pub max_length: Option<u16>,
     pub required: bool,
+    #[validate(max_chars = MAX_TEXT_INPUT_VALUE_CHARS)]
     pub value: Option<String>,
 }
 
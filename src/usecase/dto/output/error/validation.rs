@@ -1,3 +1,5 @@
+use validate_core::ValidationErrors;
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum PlanValidationError {
     MixedSendAndInteraction,
@@ -6,23 +8,32 @@ pub enum PlanValidationError {
     DeferNotFirst,
     DeferAndResponse,
     OpenModalNotExclusive,
+    /// ペイロードが Discord の上限や必須条件を満たさない。違反したフィールドをすべて含む。
+    InvalidPayload(ValidationErrors),
 }
 
 impl std::fmt::Display for PlanValidationError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let message = match self {
+        match self {
             PlanValidationError::MixedSendAndInteraction => {
-                "Send cannot be mixed with interaction steps"
+                write!(f, "Send cannot be mixed with interaction steps")
             }
-            PlanValidationError::InvalidFirstStep => {
+            PlanValidationError::InvalidFirstStep => write!(
+                f,
                 "interaction plan must start with Defer, Response, or OpenModal"
+            ),
+            PlanValidationError::ResponseNotFirst => write!(f, "Response must be the first step"),
+            PlanValidationError::DeferNotFirst => write!(f, "Defer must be the first step"),
+            PlanValidationError::DeferAndResponse => {
+                write!(f, "Defer and Response cannot both appear")
+            }
+            PlanValidationError::OpenModalNotExclusive => {
+                write!(f, "OpenModal must be the only step")
+            }
+            PlanValidationError::InvalidPayload(errors) => {
+                write!(f, "invalid payload: {errors}")
             }
-            PlanValidationError::ResponseNotFirst => "Response must be the first step",
-            PlanValidationError::DeferNotFirst => "Defer must be the first step",
-            PlanValidationError::DeferAndResponse => "Defer and Response cannot both appear",
-            PlanValidationError::OpenModalNotExclusive => "OpenModal must be the only step",
-        };
-        write!(f, "{message}")
+        }
     }
 }
 
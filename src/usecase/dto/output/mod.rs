@@ -1,3 +1,5 @@
 pub mod discord_exec;
 pub mod error;
 pub mod mvp;
+pub mod status_color;
+pub mod text;
@@ -0,0 +1,5 @@
+//! メンションの状態を表す埋め込みの色 (0xRRGGBB)。
+
+pub const COLOR_UNREAD: u32 = 0xED4245;
+pub const COLOR_READ: u32 = 0xFEE75C;
+pub const COLOR_DONE: u32 = 0x57F287;
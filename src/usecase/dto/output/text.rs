@@ -0,0 +1,17 @@
+pub fn truncate(content: &str, max_chars: usize) -> String {
+    let mut truncated = content.chars().take(max_chars).collect::<String>();
+    if content.chars().count() > max_chars {
+        truncated.push('…');
+    }
+    truncated
+}
+
+/// 期限を Discord のタイムスタンプ記法で表示する。未解決のまま過ぎていれば強調する。
+pub fn format_due(due_at_unix: i64, now_unix: i64, is_resolved: bool) -> String {
+    let label = format!("<t:{due_at_unix}:f>（<t:{due_at_unix}:R>）");
+    if !is_resolved && due_at_unix <= now_unix {
+        format!("⚠️ 期限切れ {label}")
+    } else {
+        label
+    }
+}
@@ -0,0 +1,320 @@
+//! リポジトリ（ポート）とやり取りするデータ。実装側（`infrastructure::db`）はこれらの型に変換して返す。
+
+use crate::domain::model::{ContentStorageMode, MentionPriority};
+
+#[derive(Debug, Clone)]
+pub struct NewMention {
+    pub guild_id: u64,
+    pub channel_id: u64,
+    pub message_id: u64,
+    pub author_id: u64,
+    pub content: String,
+    pub mention_everyone: bool,
+    pub created_at_unix: i64,
+    /// 本文から読み取った期限。
+    pub due_at_unix: Option<i64>,
+    pub priority: MentionPriority,
+    /// ギルドの本文の保存方式。`content` は保存時にこの方式で変換する。
+    pub content_storage: ContentStorageMode,
+    pub targets: Vec<u64>,
+}
+
+#[derive(Debug, Clone)]
+pub struct StoredMention {
+    pub author_id: u64,
+    pub guild_id: u64,
+    pub channel_id: u64,
+    pub message_id: u64,
+    pub content: String,
+    pub mention_everyone: bool,
+    pub created_at_unix: i64,
+    pub due_at_unix: Option<i64>,
+    pub priority: MentionPriority,
+    pub target_user_ids: Vec<u64>,
+    pub read_user_ids: Vec<u64>,
+    pub done_user_ids: Vec<u64>,
+}
+
+#[derive(Debug, Clone)]
+pub struct MentionForTarget {
+    pub mention_id: i64,
+    pub guild_id: u64,
+    pub channel_id: u64,
+    pub message_id: u64,
+    pub author_id: u64,
+    pub content: String,
+    pub mention_everyone: bool,
+    pub created_at_unix: i64,
+    pub due_at_unix: Option<i64>,
+    pub priority: MentionPriority,
+    pub is_read: bool,
+    pub is_done: bool,
+    pub extended_until: Option<i64>,
+}
+
+/// 統計の集計範囲。期間は `[since_unix, until_unix)` の半開区間。
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StatsFilter {
+    pub guild_id: u64,
+    pub since_unix: i64,
+    pub until_unix: i64,
+    pub channel_id: Option<u64>,
+    /// 指定した場合、この対象者宛てのメンションのみ集計する。
+    pub target_user_ids: Option<Vec<u64>>,
+}
+
+/// メンション対象者単位（mention × user）で集計した統計。時間は秒。
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MentionStats {
+    pub target_count: i64,
+    pub read_count: i64,
+    pub done_count: i64,
+    pub never_read_count: i64,
+    pub read_median_secs: Option<f64>,
+    pub read_p90_secs: Option<f64>,
+    pub done_median_secs: Option<f64>,
+    pub done_p90_secs: Option<f64>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResponderStats {
+    pub user_id: u64,
+    pub target_count: i64,
+    pub never_read_count: i64,
+    pub read_median_secs: f64,
+}
+
+/// チームレポートの投稿設定。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReportSettings {
+    pub guild_id: u64,
+    pub channel_id: u64,
+    /// `false` の場合、レポートに個人名を載せない。
+    pub name_users: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnreadCount {
+    pub user_id: u64,
+    pub unread_count: i64,
+}
+
+/// 誰かが未解決のまま残っているメンション。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnresolvedMention {
+    pub guild_id: u64,
+    pub channel_id: u64,
+    pub message_id: u64,
+    pub author_id: u64,
+    pub created_at_unix: i64,
+    pub pending_count: i64,
+}
+
+/// 全員が既読・解決済みになったときの送信者への通知方法。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AllReadNotifyMode {
+    Off,
+    Dm,
+    Reply,
+}
+
+impl AllReadNotifyMode {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            AllReadNotifyMode::Off => "off",
+            AllReadNotifyMode::Dm => "dm",
+            AllReadNotifyMode::Reply => "reply",
+        }
+    }
+
+    pub fn from_db(value: &str) -> Self {
+        match value {
+            "dm" => AllReadNotifyMode::Dm,
+            "reply" => AllReadNotifyMode::Reply,
+            _ => AllReadNotifyMode::Off,
+        }
+    }
+}
+
+/// ユーザーが通知を受け取らないようにする対象。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UserMute {
+    Channel(u64),
+    Author(u64),
+}
+
+impl UserMute {
+    pub fn kind_str(self) -> &'static str {
+        match self {
+            UserMute::Channel(_) => "channel",
+            UserMute::Author(_) => "author",
+        }
+    }
+
+    pub fn id(self) -> u64 {
+        match self {
+            UserMute::Channel(id) | UserMute::Author(id) => id,
+        }
+    }
+
+    pub fn from_db(kind: &str, id: u64) -> Option<Self> {
+        match kind {
+            "channel" => Some(UserMute::Channel(id)),
+            "author" => Some(UserMute::Author(id)),
+            _ => None,
+        }
+    }
+}
+
+/// ユーザー個人の通知設定。
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct UserNotifySettings {
+    /// 週次・月次の DM を送らない。
+    pub digest_dm_disabled: bool,
+    /// メンションの対象者として記録せず、既読一覧にも表示しない。
+    pub tracking_opt_out: bool,
+    pub mutes: Vec<UserMute>,
+}
+
+/// ユーザーが送ったメンション（個人データのエクスポート用）。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuthoredMentionRecord {
+    pub guild_id: u64,
+    pub channel_id: u64,
+    pub message_id: u64,
+    pub content: String,
+    pub created_at_unix: i64,
+    pub due_at_unix: Option<i64>,
+    pub priority: MentionPriority,
+    pub target_user_ids: Vec<u64>,
+}
+
+/// ユーザーが対象者になった、または既読・解決したメンション（個人データのエクスポート用）。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReceivedMentionRecord {
+    pub guild_id: u64,
+    pub channel_id: u64,
+    pub message_id: u64,
+    pub author_id: u64,
+    pub content: String,
+    pub created_at_unix: i64,
+    pub is_target: bool,
+    pub read_at_unix: Option<i64>,
+    pub done_at_unix: Option<i64>,
+    pub extended_until: Option<i64>,
+    pub ignored_at_unix: Option<i64>,
+}
+
+/// ユーザーについて保存しているデータ。`settings` はギルドに限定しない場合だけ含む。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PersonalData {
+    pub authored: Vec<AuthoredMentionRecord>,
+    pub received: Vec<ReceivedMentionRecord>,
+    pub settings: Option<UserNotifySettings>,
+    pub all_read_notify: Option<AllReadNotifyMode>,
+}
+
+/// 個人データの削除件数。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PersonalDataDeletion {
+    pub mentions: u64,
+    pub targets: u64,
+    pub reads: u64,
+    pub dones: u64,
+    /// 削除または初期化した通知設定。追跡の停止とミュートは残す。
+    pub settings: u64,
+}
+
+/// 保持期間を過ぎて削除対象になるメンションの概要。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PurgePreview {
+    pub mention_count: i64,
+    pub oldest_created_at_unix: Option<i64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TargetProgress {
+    pub user_id: u64,
+    pub read_at_unix: Option<i64>,
+    pub done_at_unix: Option<i64>,
+}
+
+/// メンションと、無視していない対象者ごとの既読・解決時刻。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MentionProgress {
+    pub guild_id: u64,
+    pub channel_id: u64,
+    pub message_id: u64,
+    pub author_id: u64,
+    pub created_at_unix: i64,
+    pub targets: Vec<TargetProgress>,
+}
+
+/// 送信者への完了通知の種類。通知済みかどうかは種類ごとに記録する。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompletionKind {
+    AllRead,
+    AllDone,
+}
+
+impl CompletionKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            CompletionKind::AllRead => "all_read",
+            CompletionKind::AllDone => "all_done",
+        }
+    }
+}
+
+/// 未読メンションのエスカレーションの種類。送信済みかどうかは種類ごとに記録する。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EscalationKind {
+    /// 送信者へ未読者一覧を DM する。
+    AuthorDm,
+    /// 元メッセージへの返信で未読者を再メンションする。
+    Reping,
+}
+
+impl EscalationKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            EscalationKind::AuthorDm => "author_dm",
+            EscalationKind::Reping => "reping",
+        }
+    }
+}
+
+/// エスカレーション対象のメンション。`mention.target_user_ids` は無視・ミュートした対象者を含まない。
+#[derive(Debug, Clone)]
+pub struct EscalationCandidate {
+    pub mention_id: i64,
+    pub mention: StoredMention,
+}
+
+/// 対象者へ個別に送るリマインダーの種類。週次の未読通知より早く知らせたいものに使う。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DueReminderKind {
+    /// 期限の24時間前。
+    DueSoon,
+    /// 期限を過ぎたとき。
+    Overdue,
+    /// 至急のメンションが送信から1時間たっても未読のとき。
+    UrgentUnread,
+}
+
+impl DueReminderKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            DueReminderKind::DueSoon => "due_soon",
+            DueReminderKind::Overdue => "overdue",
+            DueReminderKind::UrgentUnread => "urgent_unread",
+        }
+    }
+}
+
+/// 期限リマインダーを送る対象者とメンション。
+#[derive(Debug, Clone)]
+pub struct DueReminderCandidate {
+    pub user_id: u64,
+    pub mention: MentionForTarget,
+}
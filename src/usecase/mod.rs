@@ -1,4 +1,7 @@
+pub mod batch;
 pub mod dto;
 pub mod on_message;
+pub mod on_modal_submit;
+pub mod on_reaction_add;
 pub mod ports;
 pub mod slash_commands;
@@ -0,0 +1,7 @@
+use crate::domain::model::MentionPriority;
+use crate::domain::policy::mention_detection;
+
+/// メッセージ本文とチャンネルの既定からメンションの優先度を決める。
+pub fn execute(content: &str, channel_default: Option<MentionPriority>) -> MentionPriority {
+    mention_detection::classify_priority(content, channel_default)
+}
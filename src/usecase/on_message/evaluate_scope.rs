@@ -0,0 +1,10 @@
+use crate::domain::model::ScopeRule;
+use crate::domain::policy::mention_detection::{self, ScopeDecision};
+use crate::interface::mapper::input_mapper;
+use crate::usecase::dto::MessageInputDto;
+
+/// ギルドの追跡範囲ルールをメッセージに適用する。`location` はチャンネルとその親の ID。
+pub fn execute(input: &MessageInputDto, rules: &[ScopeRule], location: &[u64]) -> ScopeDecision {
+    let message = input_mapper::to_domain_message(input);
+    mention_detection::evaluate_tracking_scope(rules, location, &message)
+}
@@ -1,2 +1,5 @@
 pub mod auto_add_read_reaction;
+pub mod classify_priority;
+pub mod evaluate_scope;
 pub mod greeting;
+pub mod resolve_due_at;
@@ -0,0 +1,35 @@
+use chrono::{DateTime, Utc};
+
+use crate::domain::policy::due_date;
+
+/// 期限表現は日本時間として解釈する。
+const JST_OFFSET_SECS: i64 = 9 * 3600;
+
+/// メッセージ本文から期限を読み取り、UNIX 秒で返す。期限表現が無ければ `None`。
+pub fn execute(content: &str, sent_at_unix: i64) -> Option<i64> {
+    let now_jst = DateTime::<Utc>::from_timestamp(sent_at_unix + JST_OFFSET_SECS, 0)?.naive_utc();
+    let due_jst = due_date::parse_due_at(content, now_jst)?;
+    Some(due_jst.and_utc().timestamp() - JST_OFFSET_SECS)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::execute;
+
+    // 2026-10-21 (水) 10:00 JST
+    const SENT_AT: i64 = 1_792_544_400;
+
+    #[test]
+    fn converts_jst_deadline_to_unix() {
+        // 2026-10-23 (金) 23:59 JST
+        assert_eq!(
+            execute("<@1> 金曜までに", SENT_AT),
+            Some(SENT_AT + 2 * 86400 + 13 * 3600 + 59 * 60)
+        );
+    }
+
+    #[test]
+    fn returns_none_without_deadline() {
+        assert_eq!(execute("<@1> 確認お願いします", SENT_AT), None);
+    }
+}
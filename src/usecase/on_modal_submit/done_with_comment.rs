@@ -0,0 +1,327 @@
+use validate_macro::async_validate_return;
+
+use crate::usecase::dto::output::discord_exec::validate_plan;
+use crate::usecase::dto::{
+    AllowedMentionsPayload, DiscordExecPlan, DiscordExecStep, MessagePayload, ModalPayload,
+    ModalSubmitInput, PlanValidationError, TextInputPayload, TextInputStylePayload,
+};
+use crate::usecase::ports::MentionRepository;
+
+/// モーダルの custom_id 接頭辞。形式: "mm:done:{message_id}:{user_id}"
+pub const MODAL_CUSTOM_ID_PREFIX: &str = "mm:done:";
+const COMMENT_INPUT_ID: &str = "comment";
+const MAX_COMMENT_CHARS: u16 = 1000;
+
+const NOT_RECORDED_MESSAGE: &str = "このメッセージは記録されていません。";
+const NOT_TARGET_MESSAGE: &str = "あなたはこのメンションの対象者ではありません。";
+const DONE_MESSAGE: &str = "解決済みにしました。";
+const DONE_WITH_COMMENT_MESSAGE: &str = "解決済みにし、コメントを返信しました。";
+
+/// 「コメントして解決」モーダルの送信内容。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DoneWithCommentInput {
+    pub message_id: u64,
+    pub user_id: u64,
+    pub comment: Option<String>,
+    pub done_at_unix: i64,
+}
+
+impl DoneWithCommentInput {
+    /// モーダル送信を型付きの入力に変換する。
+    /// custom_id が不正な場合や、モーダルを開いた本人以外の送信では `None` を返す。
+    pub fn from_modal_submit(input: &ModalSubmitInput, done_at_unix: i64) -> Option<Self> {
+        let rest = input.custom_id.strip_prefix(MODAL_CUSTOM_ID_PREFIX)?;
+        let (message_id, owner_user_id) = rest.split_once(':')?;
+        let message_id: u64 = message_id.parse().ok()?;
+        let owner_user_id: u64 = owner_user_id.parse().ok()?;
+        if owner_user_id != input.user_id {
+            return None;
+        }
+        Some(Self {
+            message_id,
+            user_id: owner_user_id,
+            comment: input
+                .value(COMMENT_INPUT_ID)
+                .map(str::trim)
+                .filter(|comment| !comment.is_empty())
+                .map(str::to_string),
+            done_at_unix,
+        })
+    }
+}
+
+pub struct DoneWithCommentOutput {
+    /// モーダル送信者への応答。
+    pub response: DiscordExecPlan,
+    /// 元メッセージのチャンネルへ投稿するコメント。コメントが無ければ空のプラン。
+    pub reply: DiscordExecPlan,
+}
+
+/// メンションを解決済みにするモーダルを組み立てる。
+pub fn build_modal(message_id: u64, user_id: u64) -> ModalPayload {
+    let mut comment = TextInputPayload::new(
+        COMMENT_INPUT_ID,
+        "コメント（任意）",
+        TextInputStylePayload::Paragraph,
+    );
+    comment.placeholder = Some("メンション送信者への返信として投稿されます".into());
+    comment.max_length = Some(MAX_COMMENT_CHARS);
+    comment.required = false;
+
+    ModalPayload::new(
+        format!("{MODAL_CUSTOM_ID_PREFIX}{message_id}:{user_id}"),
+        "コメントして解決",
+        vec![comment],
+    )
+}
+
+#[async_validate_return(validate_output)]
+pub async fn execute<R: MentionRepository>(
+    repo: &R,
+    input: DoneWithCommentInput,
+) -> anyhow::Result<DoneWithCommentOutput> {
+    let mention = match repo.fetch_mention_by_message_id(input.message_id).await? {
+        Some(mention) => mention,
+        None => return Ok(response_only(NOT_RECORDED_MESSAGE)),
+    };
+
+    if !mention.target_user_ids.contains(&input.user_id) {
+        return Ok(response_only(NOT_TARGET_MESSAGE));
+    }
+
+    repo.record_done(input.message_id, input.user_id, input.done_at_unix)
+        .await?;
+
+    let Some(comment) = input.comment else {
+        return Ok(response_only(DONE_MESSAGE));
+    };
+
+    let message_link = format!(
+        "https://discord.com/channels/{}/{}/{}",
+        mention.guild_id, mention.channel_id, mention.message_id
+    );
+    let reply = DiscordExecPlan::new(vec![DiscordExecStep::Send {
+        channel_id: mention.channel_id,
+        payload: MessagePayload {
+            content: Some(format!(
+                "<@{}> {}\n（<@{}> が解決済みにしました: {}）",
+                mention.author_id, comment, input.user_id, message_link
+            )),
+            // コメント中の @everyone やロールのメンションで Bot の権限を使わせない。
+            allowed_mentions: Some(AllowedMentionsPayload::users(vec![mention.author_id])),
+            ..Default::default()
+        },
+    }]);
+
+    Ok(DoneWithCommentOutput {
+        response: ephemeral_response_plan(DONE_WITH_COMMENT_MESSAGE),
+        reply,
+    })
+}
+
+fn validate_output(output: &DoneWithCommentOutput) -> Result<(), PlanValidationError> {
+    validate_plan(&output.response)?;
+    validate_plan(&output.reply)
+}
+
+fn response_only(content: &str) -> DoneWithCommentOutput {
+    DoneWithCommentOutput {
+        response: ephemeral_response_plan(content),
+        reply: DiscordExecPlan::new(vec![]),
+    }
+}
+
+fn ephemeral_response_plan(content: &str) -> DiscordExecPlan {
+    DiscordExecPlan::new(vec![DiscordExecStep::Response(MessagePayload {
+        content: Some(content.to_string()),
+        ephemeral: Some(true),
+        ..Default::default()
+    })])
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::sync::Mutex;
+
+    use super::*;
+    use crate::domain::model::MentionPriority;
+    use crate::usecase::dto::repository::StoredMention;
+
+    struct FakeRepository {
+        mention: Option<StoredMention>,
+        done: Mutex<Vec<(u64, u64, i64)>>,
+    }
+
+    impl FakeRepository {
+        fn new(mention: Option<StoredMention>) -> Self {
+            Self {
+                mention,
+                done: Mutex::new(Vec::new()),
+            }
+        }
+    }
+
+    impl MentionRepository for FakeRepository {
+        async fn fetch_mention_by_message_id(
+            &self,
+            _message_id: u64,
+        ) -> anyhow::Result<Option<StoredMention>> {
+            Ok(self.mention.clone())
+        }
+
+        async fn delete_targets_except_by_message_id(
+            &self,
+            _message_id: u64,
+            _keep_user_ids: &[u64],
+        ) -> anyhow::Result<u64> {
+            Ok(0)
+        }
+
+        async fn record_done(
+            &self,
+            message_id: u64,
+            user_id: u64,
+            done_at_unix: i64,
+        ) -> anyhow::Result<bool> {
+            self.done
+                .lock()
+                .unwrap()
+                .push((message_id, user_id, done_at_unix));
+            Ok(true)
+        }
+    }
+
+    fn stored_mention() -> StoredMention {
+        StoredMention {
+            author_id: 1,
+            guild_id: 2,
+            channel_id: 3,
+            message_id: 4,
+            content: "<@10> 確認お願いします".into(),
+            mention_everyone: false,
+            created_at_unix: 0,
+            due_at_unix: None,
+            priority: MentionPriority::Normal,
+            target_user_ids: vec![10],
+            read_user_ids: Vec::new(),
+            done_user_ids: Vec::new(),
+        }
+    }
+
+    fn modal_submit(custom_id: &str, user_id: u64, comment: &str) -> ModalSubmitInput {
+        ModalSubmitInput {
+            custom_id: custom_id.into(),
+            user_id,
+            values: HashMap::from([(COMMENT_INPUT_ID.to_string(), comment.to_string())]),
+        }
+    }
+
+    fn input(comment: Option<&str>) -> DoneWithCommentInput {
+        DoneWithCommentInput {
+            message_id: 4,
+            user_id: 10,
+            comment: comment.map(str::to_string),
+            done_at_unix: 100,
+        }
+    }
+
+    fn response_content(plan: &DiscordExecPlan) -> Option<&str> {
+        match plan.steps().first() {
+            Some(DiscordExecStep::Response(payload)) => payload.content.as_deref(),
+            other => panic!("unexpected exec step: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn parses_modal_submit_into_typed_input() {
+        let submit = modal_submit("mm:done:4:10", 10, "  対応しました  ");
+        assert_eq!(
+            DoneWithCommentInput::from_modal_submit(&submit, 100),
+            Some(input(Some("対応しました")))
+        );
+
+        let blank = modal_submit("mm:done:4:10", 10, "   ");
+        assert_eq!(
+            DoneWithCommentInput::from_modal_submit(&blank, 100),
+            Some(input(None))
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_or_foreign_submissions() {
+        let foreign = modal_submit("mm:done:4:10", 11, "");
+        assert_eq!(DoneWithCommentInput::from_modal_submit(&foreign, 0), None);
+
+        let malformed = modal_submit("mm:done:abc", 10, "");
+        assert_eq!(DoneWithCommentInput::from_modal_submit(&malformed, 0), None);
+    }
+
+    #[test]
+    fn builds_modal_round_tripping_custom_id() {
+        let modal = build_modal(4, 10);
+        assert_eq!(modal.custom_id, "mm:done:4:10");
+        assert_eq!(modal.inputs[0].custom_id, COMMENT_INPUT_ID);
+        assert!(!modal.inputs[0].required);
+        assert!(
+            validate_plan(&DiscordExecPlan::new(vec![DiscordExecStep::OpenModal(
+                modal
+            )]))
+            .is_ok()
+        );
+    }
+
+    #[tokio::test]
+    async fn records_done_and_replies_with_comment() {
+        let repo = FakeRepository::new(Some(stored_mention()));
+        let output = execute(&repo, input(Some("対応しました")))
+            .await
+            .expect("expected output");
+
+        assert_eq!(*repo.done.lock().unwrap(), vec![(4, 10, 100)]);
+        assert_eq!(
+            response_content(&output.response),
+            Some(DONE_WITH_COMMENT_MESSAGE)
+        );
+        match output.reply.steps() {
+            [DiscordExecStep::Send {
+                channel_id,
+                payload,
+            }] => {
+                assert_eq!(*channel_id, 3);
+                let content = payload.content.as_deref().unwrap_or_default();
+                assert!(content.starts_with("<@1> 対応しました"));
+                assert!(content.contains("https://discord.com/channels/2/3/4"));
+                assert_eq!(
+                    payload.allowed_mentions,
+                    Some(AllowedMentionsPayload::users(vec![1]))
+                );
+            }
+            other => panic!("unexpected reply steps: {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn records_done_without_reply_when_comment_is_empty() {
+        let repo = FakeRepository::new(Some(stored_mention()));
+        let output = execute(&repo, input(None)).await.expect("expected output");
+
+        assert_eq!(repo.done.lock().unwrap().len(), 1);
+        assert_eq!(response_content(&output.response), Some(DONE_MESSAGE));
+        assert!(output.reply.steps().is_empty());
+    }
+
+    #[tokio::test]
+    async fn does_not_record_for_non_target() {
+        let mut mention = stored_mention();
+        mention.target_user_ids = vec![11];
+        let repo = FakeRepository::new(Some(mention));
+        let output = execute(&repo, input(Some("対応しました")))
+            .await
+            .expect("expected output");
+
+        assert!(repo.done.lock().unwrap().is_empty());
+        assert_eq!(response_content(&output.response), Some(NOT_TARGET_MESSAGE));
+        assert!(output.reply.steps().is_empty());
+    }
+}
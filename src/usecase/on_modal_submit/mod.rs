@@ -0,0 +1 @@
+pub mod done_with_comment;
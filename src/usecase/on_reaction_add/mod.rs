@@ -0,0 +1 @@
+pub mod notify_completion;
@@ -0,0 +1,347 @@
+use validate_macro::async_validate_return;
+
+use crate::usecase::dto::output::discord_exec::validate_plan;
+use crate::usecase::dto::output::status_color::{COLOR_DONE, COLOR_READ};
+use crate::usecase::dto::repository::{
+    AllReadNotifyMode, CompletionKind, MentionProgress, TargetProgress,
+};
+use crate::usecase::dto::{
+    DiscordExecPlan, DiscordExecStep, EmbedFieldPayload, EmbedPayload, MessagePayload,
+};
+use crate::usecase::ports::CompletionNotifyRepository;
+use crate::usecase::slash_commands::stats::format_duration;
+
+/// 新たに記録されたリアクションの種類。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompletionEvent {
+    Read,
+    Done,
+}
+
+pub struct NotifyCompletionInput {
+    pub message_id: u64,
+    pub user_id: u64,
+    pub event: CompletionEvent,
+    pub now_unix: i64,
+}
+
+/// 最後の対象者が既読・解決したときに、送信者へ通知するプランを返す。
+/// 通知不要な場合は空のプランを返す。
+#[async_validate_return(validate_plan)]
+pub async fn execute<R: CompletionNotifyRepository>(
+    repo: &R,
+    input: NotifyCompletionInput,
+) -> anyhow::Result<DiscordExecPlan> {
+    let Some(progress) = repo.fetch_mention_progress(input.message_id).await? else {
+        return Ok(DiscordExecPlan::new(vec![]));
+    };
+    let Some(completion) = detect_completion(&progress, input.user_id, input.event) else {
+        return Ok(DiscordExecPlan::new(vec![]));
+    };
+    // 同時に届いた最後のリアクションで二重に通知しないよう、通知済みの記録を先に取る。
+    if !repo
+        .claim_completion_notification(input.message_id, completion, input.now_unix)
+        .await?
+    {
+        return Ok(DiscordExecPlan::new(vec![]));
+    }
+
+    let mode = repo
+        .fetch_all_read_notify_mode(progress.author_id, progress.guild_id)
+        .await?;
+    let payload = MessagePayload {
+        embeds: Some(vec![build_embed(&progress, completion)]),
+        ..Default::default()
+    };
+    let steps = match mode {
+        AllReadNotifyMode::Off => vec![],
+        AllReadNotifyMode::Dm => vec![DiscordExecStep::SendDm {
+            user_id: progress.author_id,
+            payload,
+        }],
+        AllReadNotifyMode::Reply => vec![DiscordExecStep::Reply {
+            channel_id: progress.channel_id,
+            message_id: progress.message_id,
+            payload,
+        }],
+    };
+    Ok(DiscordExecPlan::new(steps))
+}
+
+/// 今回の記録で初めて全員が既読（または解決済み）になったかを判定する。
+/// 解決済みは既読を兼ねるため、既読の判定には解決時刻も含める。
+fn detect_completion(
+    progress: &MentionProgress,
+    user_id: u64,
+    event: CompletionEvent,
+) -> Option<CompletionKind> {
+    let target = progress.targets.iter().find(|t| t.user_id == user_id)?;
+    let all_read = progress.targets.iter().all(|t| first_ack_at(t).is_some());
+    let all_done = progress.targets.iter().all(|t| t.done_at_unix.is_some());
+
+    match event {
+        CompletionEvent::Done if all_done => Some(CompletionKind::AllDone),
+        // 既に既読だった対象者の解決では、全員既読の状態は変わらない。
+        CompletionEvent::Done if all_read && target.read_at_unix.is_none() => {
+            Some(CompletionKind::AllRead)
+        }
+        CompletionEvent::Read if all_read && target.done_at_unix.is_none() => {
+            Some(CompletionKind::AllRead)
+        }
+        _ => None,
+    }
+}
+
+fn first_ack_at(target: &TargetProgress) -> Option<i64> {
+    match (target.read_at_unix, target.done_at_unix) {
+        (Some(read), Some(done)) => Some(read.min(done)),
+        (read, done) => read.or(done),
+    }
+}
+
+fn build_embed(progress: &MentionProgress, completion: CompletionKind) -> EmbedPayload {
+    let (title, color, label) = match completion {
+        CompletionKind::AllRead => ("全員が既読になりました", COLOR_READ, "既読"),
+        CompletionKind::AllDone => ("全員が解決済みになりました", COLOR_DONE, "解決"),
+    };
+    let mut elapsed = progress
+        .targets
+        .iter()
+        .filter_map(|t| match completion {
+            CompletionKind::AllRead => first_ack_at(t),
+            CompletionKind::AllDone => t.done_at_unix,
+        })
+        .map(|at| (at - progress.created_at_unix).max(0))
+        .collect::<Vec<_>>();
+    elapsed.sort_unstable();
+
+    let message_link = format!(
+        "https://discord.com/channels/{}/{}/{}",
+        progress.guild_id, progress.channel_id, progress.message_id
+    );
+    let mut embed = EmbedPayload::new()
+        .title(title)
+        .url(message_link)
+        .color(color)
+        .field(EmbedFieldPayload::new(
+            "対象者",
+            format!("{}人", progress.targets.len()),
+            true,
+        ));
+    if let (Some(first), Some(last)) = (elapsed.first(), elapsed.last()) {
+        embed = embed
+            .field(EmbedFieldPayload::new(
+                format!("最初の{}", label),
+                format_duration(*first as f64),
+                true,
+            ))
+            .field(EmbedFieldPayload::new(
+                format!("最後の{}", label),
+                format_duration(*last as f64),
+                true,
+            ))
+            .field(EmbedFieldPayload::new(
+                "中央値",
+                format_duration(median(&elapsed)),
+                true,
+            ));
+    }
+    embed
+}
+
+fn median(sorted: &[i64]) -> f64 {
+    let mid = sorted.len() / 2;
+    if sorted.len().is_multiple_of(2) {
+        (sorted[mid - 1] + sorted[mid]) as f64 / 2.0
+    } else {
+        sorted[mid] as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use super::*;
+
+    struct FakeRepository {
+        progress: MentionProgress,
+        mode: AllReadNotifyMode,
+        claimed: Mutex<Vec<CompletionKind>>,
+    }
+
+    impl FakeRepository {
+        fn new(progress: MentionProgress, mode: AllReadNotifyMode) -> Self {
+            Self {
+                progress,
+                mode,
+                claimed: Mutex::new(Vec::new()),
+            }
+        }
+    }
+
+    impl CompletionNotifyRepository for FakeRepository {
+        async fn fetch_mention_progress(
+            &self,
+            _message_id: u64,
+        ) -> anyhow::Result<Option<MentionProgress>> {
+            Ok(Some(self.progress.clone()))
+        }
+
+        async fn fetch_all_read_notify_mode(
+            &self,
+            _author_id: u64,
+            _guild_id: u64,
+        ) -> anyhow::Result<AllReadNotifyMode> {
+            Ok(self.mode)
+        }
+
+        async fn claim_completion_notification(
+            &self,
+            _message_id: u64,
+            kind: CompletionKind,
+            _notified_at_unix: i64,
+        ) -> anyhow::Result<bool> {
+            let mut claimed = self.claimed.lock().unwrap();
+            if claimed.contains(&kind) {
+                return Ok(false);
+            }
+            claimed.push(kind);
+            Ok(true)
+        }
+    }
+
+    fn target(user_id: u64, read: Option<i64>, done: Option<i64>) -> TargetProgress {
+        TargetProgress {
+            user_id,
+            read_at_unix: read,
+            done_at_unix: done,
+        }
+    }
+
+    fn progress(targets: Vec<TargetProgress>) -> MentionProgress {
+        MentionProgress {
+            guild_id: 1,
+            channel_id: 2,
+            message_id: 3,
+            author_id: 4,
+            created_at_unix: 1_000,
+            targets,
+        }
+    }
+
+    fn input(user_id: u64, event: CompletionEvent) -> NotifyCompletionInput {
+        NotifyCompletionInput {
+            message_id: 3,
+            user_id,
+            event,
+            now_unix: 5_000,
+        }
+    }
+
+    #[test]
+    fn detects_all_read_on_last_read() {
+        let p = progress(vec![
+            target(10, Some(1_060), None),
+            target(11, Some(1_600), None),
+        ]);
+        assert_eq!(
+            detect_completion(&p, 11, CompletionEvent::Read),
+            Some(CompletionKind::AllRead)
+        );
+    }
+
+    #[test]
+    fn ignores_read_after_done_and_partial_progress() {
+        let already_done = progress(vec![
+            target(10, Some(1_060), None),
+            target(11, Some(1_600), Some(1_300)),
+        ]);
+        assert_eq!(
+            detect_completion(&already_done, 11, CompletionEvent::Read),
+            None
+        );
+
+        let partial = progress(vec![target(10, Some(1_060), None), target(11, None, None)]);
+        assert_eq!(detect_completion(&partial, 10, CompletionEvent::Read), None);
+    }
+
+    #[test]
+    fn done_completes_read_when_it_was_the_first_ack() {
+        let p = progress(vec![
+            target(10, Some(1_060), None),
+            target(11, None, Some(1_600)),
+        ]);
+        assert_eq!(
+            detect_completion(&p, 11, CompletionEvent::Done),
+            Some(CompletionKind::AllRead)
+        );
+
+        let all_done = progress(vec![
+            target(10, Some(1_060), Some(1_100)),
+            target(11, Some(1_500), Some(1_600)),
+        ]);
+        assert_eq!(
+            detect_completion(&all_done, 11, CompletionEvent::Done),
+            Some(CompletionKind::AllDone)
+        );
+    }
+
+    #[tokio::test]
+    async fn replies_with_timing_stats() {
+        let repo = FakeRepository::new(
+            progress(vec![
+                target(10, Some(1_060), None),
+                target(11, Some(4_600), None),
+            ]),
+            AllReadNotifyMode::Reply,
+        );
+        let plan = execute(&repo, input(11, CompletionEvent::Read))
+            .await
+            .expect("expected plan");
+
+        match plan.steps() {
+            [DiscordExecStep::Reply {
+                channel_id: 2,
+                message_id: 3,
+                payload,
+            }] => {
+                let embed = &payload.embeds.as_ref().expect("expected embeds")[0];
+                assert_eq!(embed.title.as_deref(), Some("全員が既読になりました"));
+                assert_eq!(embed.fields[1].value, "1分");
+                assert_eq!(embed.fields[2].value, "1時間0分");
+                assert_eq!(embed.fields[3].value, "30分");
+            }
+            other => panic!("unexpected steps: {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn sends_nothing_when_opted_out() {
+        let repo = FakeRepository::new(
+            progress(vec![target(10, Some(1_060), None)]),
+            AllReadNotifyMode::Off,
+        );
+        let plan = execute(&repo, input(10, CompletionEvent::Read))
+            .await
+            .expect("expected plan");
+        assert!(plan.steps().is_empty());
+    }
+
+    #[tokio::test]
+    async fn notifies_only_once_when_completion_is_already_claimed() {
+        let repo = FakeRepository::new(
+            progress(vec![target(10, Some(1_060), None)]),
+            AllReadNotifyMode::Dm,
+        );
+        let first = execute(&repo, input(10, CompletionEvent::Read))
+            .await
+            .expect("expected plan");
+        let second = execute(&repo, input(10, CompletionEvent::Read))
+            .await
+            .expect("expected plan");
+
+        assert_eq!(first.steps().len(), 1);
+        assert!(second.steps().is_empty());
+    }
+}
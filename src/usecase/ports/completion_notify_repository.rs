@@ -0,0 +1,25 @@
+use std::future::Future;
+
+use crate::usecase::dto::repository::{AllReadNotifyMode, CompletionKind, MentionProgress};
+
+/// 全員既読・全員解決の通知判定に使うポート。
+pub trait CompletionNotifyRepository: Sync {
+    fn fetch_mention_progress(
+        &self,
+        message_id: u64,
+    ) -> impl Future<Output = anyhow::Result<Option<MentionProgress>>> + Send;
+
+    fn fetch_all_read_notify_mode(
+        &self,
+        author_id: u64,
+        guild_id: u64,
+    ) -> impl Future<Output = anyhow::Result<AllReadNotifyMode>> + Send;
+
+    /// 完了通知を送る権利を取得する。既に通知済み（他の処理が取得済み）なら `false`。
+    fn claim_completion_notification(
+        &self,
+        message_id: u64,
+        kind: CompletionKind,
+        notified_at_unix: i64,
+    ) -> impl Future<Output = anyhow::Result<bool>> + Send;
+}
@@ -0,0 +1,21 @@
+use std::future::Future;
+
+use crate::usecase::dto::repository::{DueReminderCandidate, DueReminderKind};
+
+/// 期限付きメンションのリマインダーに使うポート。
+pub trait DueReminderRepository: Sync {
+    fn fetch_due_reminder_candidates(
+        &self,
+        kind: DueReminderKind,
+        now_unix: i64,
+        lookback_secs: i64,
+    ) -> impl Future<Output = anyhow::Result<Vec<DueReminderCandidate>>> + Send;
+
+    fn record_due_reminder(
+        &self,
+        mention_id: i64,
+        user_id: u64,
+        kind: DueReminderKind,
+        sent_at_unix: i64,
+    ) -> impl Future<Output = anyhow::Result<bool>> + Send;
+}
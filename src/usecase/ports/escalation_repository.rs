@@ -0,0 +1,20 @@
+use std::future::Future;
+
+use crate::usecase::dto::repository::{EscalationCandidate, EscalationKind};
+
+/// 未読メンションのエスカレーションに使うポート。
+pub trait EscalationRepository: Sync {
+    fn fetch_escalation_candidates(
+        &self,
+        kind: EscalationKind,
+        now_unix: i64,
+        lookback_secs: i64,
+    ) -> impl Future<Output = anyhow::Result<Vec<EscalationCandidate>>> + Send;
+
+    fn record_escalation(
+        &self,
+        mention_id: i64,
+        kind: EscalationKind,
+        sent_at_unix: i64,
+    ) -> impl Future<Output = anyhow::Result<bool>> + Send;
+}
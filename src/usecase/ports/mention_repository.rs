@@ -0,0 +1,25 @@
+use std::future::Future;
+
+use crate::usecase::dto::repository::StoredMention;
+
+/// Usecase から参照するメンション永続化のポート。
+/// 実装は `infrastructure::db::Db`。テストではフェイク実装に差し替える。
+pub trait MentionRepository: Sync {
+    fn fetch_mention_by_message_id(
+        &self,
+        message_id: u64,
+    ) -> impl Future<Output = anyhow::Result<Option<StoredMention>>> + Send;
+
+    fn delete_targets_except_by_message_id(
+        &self,
+        message_id: u64,
+        keep_user_ids: &[u64],
+    ) -> impl Future<Output = anyhow::Result<u64>> + Send;
+
+    fn record_done(
+        &self,
+        message_id: u64,
+        user_id: u64,
+        done_at_unix: i64,
+    ) -> impl Future<Output = anyhow::Result<bool>> + Send;
+}
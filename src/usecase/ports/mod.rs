@@ -1 +1,19 @@
-// ポート定義は必要になった時点で追加する
+pub mod completion_notify_repository;
+pub mod due_reminder_repository;
+pub mod escalation_repository;
+pub mod mention_repository;
+pub mod personal_data_repository;
+pub mod retention_repository;
+pub mod stats_repository;
+pub mod team_report_repository;
+pub mod tracking_repository;
+
+pub use completion_notify_repository::CompletionNotifyRepository;
+pub use due_reminder_repository::DueReminderRepository;
+pub use escalation_repository::EscalationRepository;
+pub use mention_repository::MentionRepository;
+pub use personal_data_repository::PersonalDataRepository;
+pub use retention_repository::RetentionRepository;
+pub use stats_repository::StatsRepository;
+pub use team_report_repository::TeamReportRepository;
+pub use tracking_repository::TrackingRepository;
@@ -0,0 +1,18 @@
+use std::future::Future;
+
+use crate::usecase::dto::repository::{PersonalData, PersonalDataDeletion};
+
+/// 個人データのエクスポートと削除のためのポート。`guild_id` が `None` なら全ギルドが対象。
+pub trait PersonalDataRepository: Sync {
+    fn fetch_personal_data(
+        &self,
+        user_id: u64,
+        guild_id: Option<u64>,
+    ) -> impl Future<Output = anyhow::Result<PersonalData>> + Send;
+
+    fn delete_personal_data(
+        &self,
+        user_id: u64,
+        guild_id: Option<u64>,
+    ) -> impl Future<Output = anyhow::Result<PersonalDataDeletion>> + Send;
+}
@@ -0,0 +1,18 @@
+use std::future::Future;
+
+/// 保持期間を過ぎたメンションや、退出したギルドの記録を削除するためのポート。
+pub trait RetentionRepository: Sync {
+    /// 最大 `limit` 件を削除し、削除件数を返す。
+    fn purge_expired_mentions(
+        &self,
+        default_days: Option<u32>,
+        now_unix: i64,
+        limit: i64,
+    ) -> impl Future<Output = anyhow::Result<u64>> + Send;
+
+    /// `left_before_unix` より前に退出したギルドの記録を削除し、削除したメンション数を返す。
+    fn purge_departed_guilds(
+        &self,
+        left_before_unix: i64,
+    ) -> impl Future<Output = anyhow::Result<u64>> + Send;
+}
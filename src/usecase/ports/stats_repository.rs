@@ -0,0 +1,17 @@
+use std::future::Future;
+
+use crate::usecase::dto::repository::{MentionStats, ResponderStats, StatsFilter};
+
+/// `/統計` から参照する集計のポート。集計は SQL 側で行う。
+pub trait StatsRepository: Sync {
+    fn fetch_mention_stats(
+        &self,
+        filter: &StatsFilter,
+    ) -> impl Future<Output = anyhow::Result<MentionStats>> + Send;
+
+    fn fetch_slowest_responders(
+        &self,
+        filter: &StatsFilter,
+        limit: i64,
+    ) -> impl Future<Output = anyhow::Result<Vec<ResponderStats>>> + Send;
+}
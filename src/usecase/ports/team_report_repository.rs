@@ -0,0 +1,30 @@
+use std::future::Future;
+
+use crate::usecase::dto::repository::{MentionStats, StatsFilter, UnreadCount, UnresolvedMention};
+
+/// 週次チームレポートの集計に使うポート。
+pub trait TeamReportRepository: Sync {
+    fn count_mentions_in_guild(
+        &self,
+        guild_id: u64,
+        since_unix: i64,
+        until_unix: i64,
+    ) -> impl Future<Output = anyhow::Result<i64>> + Send;
+
+    fn fetch_mention_stats(
+        &self,
+        filter: &StatsFilter,
+    ) -> impl Future<Output = anyhow::Result<MentionStats>> + Send;
+
+    fn fetch_top_unread_users(
+        &self,
+        guild_id: u64,
+        limit: i64,
+    ) -> impl Future<Output = anyhow::Result<Vec<UnreadCount>>> + Send;
+
+    fn fetch_oldest_unresolved(
+        &self,
+        guild_id: u64,
+        limit: i64,
+    ) -> impl Future<Output = anyhow::Result<Vec<UnresolvedMention>>> + Send;
+}
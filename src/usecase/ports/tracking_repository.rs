@@ -0,0 +1,20 @@
+use std::future::Future;
+
+use crate::usecase::dto::repository::NewMention;
+
+/// メッセージの追跡を手動で開始・停止するためのポート。
+pub trait TrackingRepository: Sync {
+    /// 記録済みのメッセージであれば対象者だけを追加する。
+    fn insert_mention(
+        &self,
+        mention: NewMention,
+    ) -> impl Future<Output = anyhow::Result<()>> + Send;
+
+    /// 記録を削除し、起動時の補完で取り込み直さないよう停止したことを残す。削除したメンション数を返す。
+    fn stop_tracking_message(
+        &self,
+        guild_id: u64,
+        message_id: u64,
+        stopped_at_unix: i64,
+    ) -> impl Future<Output = anyhow::Result<u64>> + Send;
+}
@@ -12,11 +12,84 @@ pub fn execute() -> Result<HelpOutputDto, UsecaseError> {
             description: "メッセージの既読・未読・解決済みユーザーを確認します。".into(),
             example: "メッセージを右クリック → アプリ → 既読状況確認".into(),
         },
+        HelpCommandDto {
+            name: "追跡を開始 / 追跡を停止".into(),
+            description: "記録されていないメッセージを、選んだユーザー・ロールを対象に追跡します。停止すると記録とリアクションを削除し、再起動時の補完でも取り込みません（送信者またはメッセージ管理権限が必要）。"
+                .into(),
+            example: "メッセージを右クリック → アプリ → 追跡を開始".into(),
+        },
         HelpCommandDto {
             name: "/通知一覧".into(),
-            description: "自分宛のメンション一覧を確認します。show_done で解決済みも表示できます。"
+            description: "自分宛のメンション一覧を確認します。show_done で解決済みも、sort_by_due で期限順にも表示できます。本文の「金曜までに」「期限: 11/1 18:00」などは期限として記録され、期限の24時間前と期限切れ時にDMでお知らせします。"
+                .into(),
+            example: "/通知一覧 show_done:true sort_by_due:true".into(),
+        },
+        HelpCommandDto {
+            name: "/統計".into(),
+            description: "既読率や既読・解決までの時間を集計します。対象と期間を指定できます。"
+                .into(),
+            example: "/統計 scope:チャンネル channel:#general period:直近1ヶ月".into(),
+        },
+        HelpCommandDto {
+            name: "/管理設定 レポート".into(),
+            description:
+                "毎週月曜にチームレポートを投稿するチャンネルを設定します（サーバー管理権限が必要）。"
+                    .into(),
+            example: "/管理設定 レポート channel:#team name_users:false".into(),
+        },
+        HelpCommandDto {
+            name: "/完了通知".into(),
+            description:
+                "送ったメンションを全員が既読・解決したときに、DMまたは返信で通知します。サーバーの既定は管理者が /管理設定 完了通知 で設定します。".into(),
+            example: "/完了通知 mode:DM".into(),
+        },
+        HelpCommandDto {
+            name: "/管理設定 エスカレーション".into(),
+            description: "未読が続くメンションを送信者へDMし、未読者を元メッセージのスレッドで再メンションします（サーバー管理権限が必要）。"
+                .into(),
+            example: "/管理設定 エスカレーション author_after_hours:24 reping_after_hours:48".into(),
+        },
+        HelpCommandDto {
+            name: "/管理設定 優先度".into(),
+            description: "本文に [至急] や [FYI] が無いメンションの既定の優先度をチャンネルごとに設定します。至急は1時間未読でDMし一覧の先頭に表示、FYIは解決リアクションとリマインダーを付けません（チャンネル管理権限が必要）。"
+                .into(),
+            example: "/管理設定 優先度 channel:#announcements priority:FYI（共有のみ）".into(),
+        },
+        HelpCommandDto {
+            name: "/管理設定 追跡範囲".into(),
+            description: "追跡するチャンネル・カテゴリ・ロールを設定します。「含める」が1件でもあればそれ以外は追跡せず、「除外」は常に優先されます。チャンネルごとに @everyone / @here だけを除外することもできます（サーバー管理権限が必要）。"
+                .into(),
+            example: "/管理設定 追跡範囲 チャンネル channel:#random mode:除外".into(),
+        },
+        HelpCommandDto {
+            name: "/通知設定".into(),
+            description: "自分あての通知を調整します。週次・月次の DM の停止、チャンネルや送信者ごとのミュート、自分を追跡対象から外す設定ができます。"
+                .into(),
+            example: "/通知設定 送信者ミュート user:@bot-owner action:ミュート".into(),
+        },
+        HelpCommandDto {
+            name: "/データ".into(),
+            description: "自分について保存しているメッセージ本文・既読・解決などの記録を JSON/CSV で書き出す（export）か、削除します（delete）。追跡の停止とミュートの設定は削除後も残します。脱退したメンバーの分は管理者が /管理設定 データ で扱えます。"
+                .into(),
+            example: "/データ export format:CSV".into(),
+        },
+        HelpCommandDto {
+            name: "/管理設定 保持期間".into(),
+            description: "全員が解決または無視したメンションを何日後に削除するかを設定します。確認サブコマンドで次回の削除対象を事前に確認できます（サーバー管理権限が必要）。"
+                .into(),
+            example: "/管理設定 保持期間 設定 days:180".into(),
+        },
+        HelpCommandDto {
+            name: "/管理設定 本文保存".into(),
+            description: "メッセージ本文を全文・先頭100文字・保存しない・暗号化のどれで保存するかを設定します。保存しない場合、一覧と既読状況では表示時に Discord から本文を取得します（サーバー管理権限が必要）。"
+                .into(),
+            example: "/管理設定 本文保存 mode:保存しない（表示時に取得）".into(),
+        },
+        HelpCommandDto {
+            name: "/管理設定 インポート".into(),
+            description: "導入前のメッセージを読み込み、指定日以降のメンションと既存の KIDOKU・DONE リアクションを記録します。取り込んだ既読・解決は付けた時刻が分からないため、統計の件数には含め、既読・解決までの時間には含めません。進み具合は実行したチャンネルのメッセージで更新されます（サーバー管理権限が必要）。"
                 .into(),
-            example: "/通知一覧 show_done:true".into(),
+            example: "/管理設定 インポート channel:#general since:2026-09-01".into(),
         },
     ];
 
@@ -0,0 +1,109 @@
+use crate::usecase::slash_commands::stats::{jst_midnight_unix, parse_jst_date};
+
+const FUTURE_SINCE_MESSAGE: &str = "開始日には今日以前の日付を指定してください。";
+
+/// 取り込みの途中経過。ステータスメッセージの編集に使う。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ImportProgress {
+    /// 読み込んだメッセージ数
+    pub scanned: u64,
+    /// メンションとして記録したメッセージ数
+    pub imported: u64,
+    /// 既存のリアクションから記録した既読数
+    pub reads: u64,
+    /// 既存のリアクションから記録した解決数
+    pub dones: u64,
+    /// 最後に読み込んだメッセージの送信時刻
+    pub last_message_unix: Option<i64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportStatus {
+    Running,
+    Finished,
+    /// 取得に失敗して途中で止まった。
+    Aborted,
+    /// Bot の停止のため途中で止めた。
+    Interrupted,
+}
+
+/// 開始日 (JST, YYYY-MM-DD) を UNIX 秒に変換する。不正な指定はユーザー向けのメッセージを返す。
+pub fn parse_since(value: &str, now_unix: i64) -> Result<i64, &'static str> {
+    let since = jst_midnight_unix(parse_jst_date(value)?);
+    if since > now_unix {
+        return Err(FUTURE_SINCE_MESSAGE);
+    }
+    Ok(since)
+}
+
+pub fn format_progress(channel_id: u64, status: ImportStatus, progress: &ImportProgress) -> String {
+    let heading = match status {
+        ImportStatus::Running => format!("<#{channel_id}> の過去のメンションを取り込んでいます…"),
+        ImportStatus::Finished => format!("<#{channel_id}> の取り込みが完了しました。"),
+        ImportStatus::Aborted => format!(
+            "<#{channel_id}> の取り込みを中断しました。Bot の権限を確認して再実行してください。"
+        ),
+        ImportStatus::Interrupted => format!(
+            "<#{channel_id}> の取り込みを Bot の停止のため中断しました。同じ開始日で再実行すると、取り込み済みの分は重複せずに続きを取り込めます。"
+        ),
+    };
+    let mut lines = vec![
+        heading,
+        format!(
+            "読み込んだメッセージ: {}件 / 記録したメンション: {}件",
+            progress.scanned, progress.imported
+        ),
+        format!(
+            "リアクションから記録: 既読 {}件 / 解決 {}件",
+            progress.reads, progress.dones
+        ),
+    ];
+    if let Some(last) = progress.last_message_unix {
+        lines.push(format!("最後に読み込んだメッセージ: <t:{last}:f>"));
+    }
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_since_as_jst_midnight() {
+        // 2026-09-01T00:00:00+09:00
+        assert_eq!(parse_since("2026-09-01", 1_800_000_000), Ok(1_788_188_400));
+    }
+
+    #[test]
+    fn rejects_invalid_or_future_since() {
+        assert!(parse_since("2026/09/01", 1_800_000_000).is_err());
+        assert_eq!(
+            parse_since("2030-01-01", 1_800_000_000),
+            Err(FUTURE_SINCE_MESSAGE)
+        );
+    }
+
+    #[test]
+    fn formats_progress_by_status() {
+        let progress = ImportProgress {
+            scanned: 250,
+            imported: 12,
+            reads: 30,
+            dones: 4,
+            last_message_unix: Some(100),
+        };
+
+        let running = format_progress(1, ImportStatus::Running, &progress);
+        assert!(running.contains("取り込んでいます"));
+        assert!(running.contains("読み込んだメッセージ: 250件 / 記録したメンション: 12件"));
+        assert!(running.contains("既読 30件 / 解決 4件"));
+        assert!(running.contains("<t:100:f>"));
+
+        let finished = format_progress(1, ImportStatus::Finished, &ImportProgress::default());
+        assert!(finished.contains("完了しました"));
+        assert!(!finished.contains("<t:"));
+
+        let interrupted = format_progress(1, ImportStatus::Interrupted, &ImportProgress::default());
+        assert!(interrupted.contains("停止のため中断"));
+    }
+}
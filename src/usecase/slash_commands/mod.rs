@@ -1,2 +1,7 @@
 pub mod help;
+pub mod import;
+pub mod my_mentions;
+pub mod personal_data;
+pub mod stats;
+pub mod track_message;
 pub mod view_read_status;
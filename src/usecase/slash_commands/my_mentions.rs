@@ -0,0 +1,376 @@
+use chrono::{DateTime, Utc};
+
+use crate::domain::model::MentionPriority;
+use crate::usecase::dto::output::status_color::{COLOR_DONE, COLOR_READ, COLOR_UNREAD};
+use crate::usecase::dto::output::text::{format_due, truncate};
+use crate::usecase::dto::repository::MentionForTarget;
+use crate::usecase::dto::{
+    ActionRowPayload, ButtonPayload, ButtonStylePayload, EmbedFieldPayload, EmbedFooterPayload,
+    EmbedPayload, MessagePayload, SelectMenuPayload, SelectOptionPayload,
+};
+
+/// 解決するメンションを選ぶセレクトメニューの custom_id 接頭辞。形式: "mm:done_select:{user_id}"
+pub const DONE_SELECT_CUSTOM_ID_PREFIX: &str = "mm:done_select:";
+
+/// `/通知一覧` の表示オプション。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ListOptions {
+    pub show_done: bool,
+    /// 期限が近い順に並べる。期限のないメンションは後ろに回す。
+    pub sort_by_due: bool,
+}
+
+impl ListOptions {
+    /// ページ送りボタンの custom_id に埋め込むビットフラグ。
+    /// bit0 = show_done, bit1 = sort_by_due（旧形式の "0"/"1" と互換）。
+    pub fn to_flags(self) -> u8 {
+        u8::from(self.show_done) | (u8::from(self.sort_by_due) << 1)
+    }
+
+    pub fn from_flags(flags: u8) -> Self {
+        Self {
+            show_done: flags & 1 != 0,
+            sort_by_due: flags & 2 != 0,
+        }
+    }
+}
+
+/// `/通知一覧` の1ページ分（埋め込み + ページ送りボタン）を組み立てる。
+pub fn build_page(
+    items: &[MentionForTarget],
+    guild_id: Option<u64>,
+    page: usize,
+    options: ListOptions,
+    user_id: u64,
+    has_next: bool,
+    now_unix: i64,
+) -> MessagePayload {
+    let mut components = build_nav_buttons(page, options, user_id, page > 0, has_next);
+    if let Some(select) = build_done_select(items, user_id) {
+        components.push(ActionRowPayload::SelectMenu(select));
+    }
+    MessagePayload {
+        embeds: Some(build_embeds(items, guild_id, page, now_unix)),
+        components: Some(components),
+        ..Default::default()
+    }
+}
+
+pub fn build_embeds(
+    items: &[MentionForTarget],
+    guild_id: Option<u64>,
+    page: usize,
+    now_unix: i64,
+) -> Vec<EmbedPayload> {
+    let last_index = items.len().saturating_sub(1);
+    items
+        .iter()
+        .enumerate()
+        .map(|(index, item)| {
+            let embed = build_embed(item, guild_id, now_unix);
+            if index == last_index {
+                embed.footer(EmbedFooterPayload::new(format!("ページ {}", page + 1)))
+            } else {
+                embed
+            }
+        })
+        .collect()
+}
+
+fn build_embed(item: &MentionForTarget, guild_id: Option<u64>, now_unix: i64) -> EmbedPayload {
+    let date = format_date(item.created_at_unix);
+
+    let snippet = truncate(&item.content, 100);
+
+    let guild_id_val = guild_id.unwrap_or(item.guild_id);
+    let message_link = format!(
+        "https://discord.com/channels/{}/{}/{}",
+        guild_id_val, item.channel_id, item.message_id
+    );
+
+    let (status, color) = status_label_and_color(item);
+
+    let mut embed = EmbedPayload::new()
+        .title(format!(
+            "{}メッセージ ({})",
+            priority_prefix(item.priority),
+            date
+        ))
+        .url(message_link.clone())
+        .color(color)
+        .timestamp(item.created_at_unix);
+    if !snippet.is_empty() {
+        embed = embed.description(snippet);
+    }
+    embed = embed
+        .field(EmbedFieldPayload::new(
+            "送信者",
+            format!("<@{}>", item.author_id),
+            true,
+        ))
+        .field(EmbedFieldPayload::new("状態", status, true))
+        .field(EmbedFieldPayload::new(
+            "リンク",
+            format!("[開く]({})", message_link),
+            true,
+        ));
+    match item.due_at_unix {
+        Some(due_at) => embed.field(EmbedFieldPayload::new(
+            "期限",
+            format_due(due_at, now_unix, item.is_done),
+            false,
+        )),
+        None => embed,
+    }
+}
+
+/// 埋め込みタイトルの先頭に付ける優先度の表示。
+pub fn priority_prefix(priority: MentionPriority) -> &'static str {
+    match priority {
+        MentionPriority::Normal => "",
+        MentionPriority::Urgent => "🚨 至急 ",
+        MentionPriority::Fyi => "ℹ️ FYI ",
+    }
+}
+
+/// 未解決のメンションから「コメントして解決」するものを選ぶメニューを組み立てる。
+/// FYI は解決を求めないため候補に含めない。
+/// 未解決のメンションが無ければ `None` を返す。
+fn build_done_select(items: &[MentionForTarget], user_id: u64) -> Option<SelectMenuPayload> {
+    let options = items
+        .iter()
+        .filter(|item| !item.is_done && item.priority.requires_action())
+        .map(|item| {
+            let date = format_date(item.created_at_unix);
+            let snippet = truncate(&item.content, 60);
+            SelectOptionPayload::new(
+                format!("{} {}", date, snippet).trim_end().to_string(),
+                item.message_id.to_string(),
+            )
+        })
+        .collect::<Vec<_>>();
+    if options.is_empty() {
+        return None;
+    }
+    Some(
+        SelectMenuPayload::new(format!("{DONE_SELECT_CUSTOM_ID_PREFIX}{user_id}"), options)
+            .placeholder("コメントして解決するメンションを選択"),
+    )
+}
+
+fn format_date(unix: i64) -> String {
+    DateTime::<Utc>::from_timestamp(unix, 0)
+        .map(|dt| dt.format("%Y-%m-%d").to_string())
+        .unwrap_or_else(|| "不明".to_string())
+}
+
+fn status_label_and_color(item: &MentionForTarget) -> (&'static str, u32) {
+    if item.is_done || (item.is_read && !item.priority.requires_action()) {
+        ("解決済み 🔒", COLOR_DONE)
+    } else if item.is_read {
+        ("既読 ✅", COLOR_READ)
+    } else {
+        ("未読 ❌", COLOR_UNREAD)
+    }
+}
+
+pub fn build_nav_buttons(
+    page: usize,
+    options: ListOptions,
+    user_id: u64,
+    has_prev: bool,
+    has_next: bool,
+) -> Vec<ActionRowPayload> {
+    let flags = options.to_flags();
+
+    let prev_button = ButtonPayload::new(
+        format!("mm:p:{}:{}:{}", page.saturating_sub(1), flags, user_id),
+        "◀ 前へ",
+    )
+    .style(ButtonStylePayload::Secondary)
+    .disabled(!has_prev);
+
+    let next_button =
+        ButtonPayload::new(format!("mm:p:{}:{}:{}", page + 1, flags, user_id), "次へ ▶")
+            .style(ButtonStylePayload::Secondary)
+            .disabled(!has_next);
+
+    vec![ActionRowPayload::Buttons(vec![prev_button, next_button])]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::usecase::dto::SelectMenuKindPayload;
+
+    const NOW: i64 = 1_700_100_000;
+
+    fn item(is_read: bool, is_done: bool) -> MentionForTarget {
+        MentionForTarget {
+            mention_id: 1,
+            guild_id: 2,
+            channel_id: 3,
+            message_id: 4,
+            author_id: 5,
+            content: "<@6> 確認お願いします".into(),
+            mention_everyone: false,
+            created_at_unix: 1_700_000_000,
+            due_at_unix: None,
+            priority: MentionPriority::Normal,
+            is_read,
+            is_done,
+            extended_until: None,
+        }
+    }
+
+    #[test]
+    fn colors_embeds_by_status() {
+        let items = vec![item(false, false), item(true, false), item(true, true)];
+        let colors = build_embeds(&items, None, 0, NOW)
+            .into_iter()
+            .map(|embed| embed.color)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            colors,
+            vec![Some(COLOR_UNREAD), Some(COLOR_READ), Some(COLOR_DONE)]
+        );
+    }
+
+    #[test]
+    fn puts_page_footer_on_last_embed_only() {
+        let items = vec![item(false, false), item(false, false)];
+        let embeds = build_embeds(&items, None, 1, NOW);
+        assert!(embeds[0].footer.is_none());
+        assert_eq!(
+            embeds[1].footer.as_ref().map(|f| f.text.as_str()),
+            Some("ページ 2")
+        );
+    }
+
+    #[test]
+    fn links_to_message_in_current_guild() {
+        let embeds = build_embeds(&[item(false, false)], Some(9), 0, NOW);
+        assert_eq!(
+            embeds[0].url.as_deref(),
+            Some("https://discord.com/channels/9/3/4")
+        );
+        assert_eq!(embeds[0].timestamp, Some(1_700_000_000));
+    }
+
+    #[test]
+    fn disables_prev_on_first_page() {
+        let options = ListOptions {
+            show_done: true,
+            sort_by_due: false,
+        };
+        let rows = build_nav_buttons(0, options, 42, false, true);
+        let ActionRowPayload::Buttons(buttons) = &rows[0] else {
+            panic!("expected button row");
+        };
+        assert!(buttons[0].disabled);
+        assert!(!buttons[1].disabled);
+        assert_eq!(buttons[1].custom_id.as_deref(), Some("mm:p:1:1:42"));
+    }
+
+    #[test]
+    fn offers_only_unresolved_mentions_for_done_select() {
+        let mut done = item(true, true);
+        done.message_id = 7;
+        let payload = build_page(
+            &[item(false, false), done],
+            None,
+            0,
+            ListOptions::default(),
+            42,
+            false,
+            NOW,
+        );
+        let rows = payload.components.expect("expected components");
+        let ActionRowPayload::SelectMenu(select) = &rows[1] else {
+            panic!("expected select menu row");
+        };
+        assert_eq!(select.custom_id, "mm:done_select:42");
+        let SelectMenuKindPayload::String { options } = &select.kind else {
+            panic!("expected string select");
+        };
+        assert_eq!(options.len(), 1);
+        assert_eq!(options[0].value, "4");
+    }
+
+    #[test]
+    fn omits_done_select_when_everything_is_resolved() {
+        let payload = build_page(
+            &[item(true, true)],
+            None,
+            0,
+            ListOptions::default(),
+            42,
+            false,
+            NOW,
+        );
+        assert_eq!(payload.components.map(|rows| rows.len()), Some(1));
+    }
+
+    #[test]
+    fn round_trips_list_options_through_flags() {
+        for (show_done, sort_by_due) in [(false, false), (true, false), (false, true), (true, true)]
+        {
+            let options = ListOptions {
+                show_done,
+                sort_by_due,
+            };
+            assert_eq!(ListOptions::from_flags(options.to_flags()), options);
+        }
+        // 旧形式の custom_id ("0"/"1") も解決済み表示フラグとして読める。
+        assert!(ListOptions::from_flags(1).show_done);
+        assert!(!ListOptions::from_flags(1).sort_by_due);
+    }
+
+    #[test]
+    fn shows_due_field_only_when_deadline_exists() {
+        let mut due = item(false, false);
+        due.due_at_unix = Some(NOW - 60);
+        let embeds = build_embeds(&[item(false, false), due], None, 0, NOW);
+        assert!(embeds[0].fields.iter().all(|f| f.name != "期限"));
+        let field = embeds[1]
+            .fields
+            .iter()
+            .find(|f| f.name == "期限")
+            .expect("expected due field");
+        assert!(field.value.starts_with("⚠️ 期限切れ"));
+    }
+
+    #[test]
+    fn does_not_flag_resolved_mentions_as_overdue() {
+        assert_eq!(
+            format_due(NOW - 60, NOW, true),
+            format!("<t:{0}:f>（<t:{0}:R>）", NOW - 60)
+        );
+    }
+
+    #[test]
+    fn marks_priority_in_title_and_treats_read_fyi_as_resolved() {
+        let mut urgent = item(false, false);
+        urgent.priority = MentionPriority::Urgent;
+        let mut fyi = item(true, false);
+        fyi.priority = MentionPriority::Fyi;
+        let embeds = build_embeds(&[urgent, fyi], None, 0, NOW);
+        assert!(embeds[0]
+            .title
+            .as_deref()
+            .is_some_and(|t| t.starts_with("🚨 至急 ")));
+        assert!(embeds[1]
+            .title
+            .as_deref()
+            .is_some_and(|t| t.starts_with("ℹ️ FYI ")));
+        assert_eq!(embeds[1].color, Some(COLOR_DONE));
+    }
+
+    #[test]
+    fn excludes_fyi_from_done_select() {
+        let mut fyi = item(false, false);
+        fyi.priority = MentionPriority::Fyi;
+        let payload = build_page(&[fyi], None, 0, ListOptions::default(), 42, false, NOW);
+        assert_eq!(payload.components.map(|rows| rows.len()), Some(1));
+    }
+}
@@ -0,0 +1,395 @@
+use serde_json::json;
+
+use crate::usecase::dto::repository::{
+    AuthoredMentionRecord, PersonalData, PersonalDataDeletion, ReceivedMentionRecord, UserMute,
+};
+use crate::usecase::ports::PersonalDataRepository;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Json,
+    Csv,
+}
+
+pub struct ExportInput {
+    pub user_id: u64,
+    /// 管理者が代理で扱う場合はそのギルドに限る。本人なら `None`。
+    pub guild_id: Option<u64>,
+    pub format: ExportFormat,
+    pub now_unix: i64,
+}
+
+/// 添付ファイルとして返すエクスポート結果。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExportOutput {
+    pub content: String,
+    pub filename: String,
+    pub body: String,
+}
+
+pub struct DeleteInput {
+    pub user_id: u64,
+    pub guild_id: Option<u64>,
+}
+
+pub async fn export<R: PersonalDataRepository>(
+    repo: &R,
+    input: ExportInput,
+) -> anyhow::Result<ExportOutput> {
+    let data = repo
+        .fetch_personal_data(input.user_id, input.guild_id)
+        .await?;
+
+    let (extension, body) = match input.format {
+        ExportFormat::Json => ("json", render_json(input.user_id, input.now_unix, &data)),
+        ExportFormat::Csv => ("csv", render_csv(&data)),
+    };
+    let content = format!(
+        "<@{}> について保存しているデータです（送信 {}件、受信 {}件）。",
+        input.user_id,
+        data.authored.len(),
+        data.received.len()
+    );
+
+    Ok(ExportOutput {
+        content,
+        filename: format!("kiduku-{}-{}.{}", input.user_id, input.now_unix, extension),
+        body,
+    })
+}
+
+pub async fn delete<R: PersonalDataRepository>(
+    repo: &R,
+    input: DeleteInput,
+) -> anyhow::Result<String> {
+    let deleted = repo
+        .delete_personal_data(input.user_id, input.guild_id)
+        .await?;
+    Ok(format_deletion(
+        input.user_id,
+        input.guild_id.is_none(),
+        deleted,
+    ))
+}
+
+fn format_deletion(user_id: u64, is_self: bool, deleted: PersonalDataDeletion) -> String {
+    let mut lines = vec![
+        format!("<@{}> について保存しているデータを削除しました。", user_id),
+        format!("- 送信したメンション: {}件", deleted.mentions),
+        format!("- 対象者としての記録: {}件", deleted.targets),
+        format!("- 既読: {}件", deleted.reads),
+        format!("- 解決: {}件", deleted.dones),
+    ];
+    if is_self {
+        lines.push(format!("- 個人設定: {}件", deleted.settings));
+        lines.push(
+            "追跡の停止とミュートの設定は、今後も守るため削除せずに残しています。".to_string(),
+        );
+        lines.push(
+            "今後のメンションも記録しない場合は `/通知設定 追跡` で「追跡しない」を選んでください。"
+                .to_string(),
+        );
+    }
+    lines.join("\n")
+}
+
+fn render_json(user_id: u64, now_unix: i64, data: &PersonalData) -> String {
+    let authored = data.authored.iter().map(authored_json).collect::<Vec<_>>();
+    let received = data.received.iter().map(received_json).collect::<Vec<_>>();
+    let settings = data.settings.as_ref().map(|settings| {
+        let mutes = settings
+            .mutes
+            .iter()
+            .map(|mute| match mute {
+                UserMute::Channel(id) => json!({ "kind": "channel", "id": id.to_string() }),
+                UserMute::Author(id) => json!({ "kind": "author", "id": id.to_string() }),
+            })
+            .collect::<Vec<_>>();
+        json!({
+            "digest_dm_disabled": settings.digest_dm_disabled,
+            "tracking_opt_out": settings.tracking_opt_out,
+            "all_read_notify": data.all_read_notify.map(|mode| mode.as_str()),
+            "mutes": mutes,
+        })
+    });
+
+    let value = json!({
+        "user_id": user_id.to_string(),
+        "exported_at": now_unix,
+        "authored_mentions": authored,
+        "received_mentions": received,
+        "settings": settings,
+    });
+    serde_json::to_string_pretty(&value).unwrap_or_default()
+}
+
+// Discord の ID は JavaScript の数値で精度を失うため文字列で出力する。
+fn authored_json(record: &AuthoredMentionRecord) -> serde_json::Value {
+    json!({
+        "guild_id": record.guild_id.to_string(),
+        "channel_id": record.channel_id.to_string(),
+        "message_id": record.message_id.to_string(),
+        "content": record.content,
+        "created_at": record.created_at_unix,
+        "due_at": record.due_at_unix,
+        "priority": record.priority.as_str(),
+        "target_user_ids": record
+            .target_user_ids
+            .iter()
+            .map(u64::to_string)
+            .collect::<Vec<_>>(),
+    })
+}
+
+fn received_json(record: &ReceivedMentionRecord) -> serde_json::Value {
+    json!({
+        "guild_id": record.guild_id.to_string(),
+        "channel_id": record.channel_id.to_string(),
+        "message_id": record.message_id.to_string(),
+        "author_id": record.author_id.to_string(),
+        "content": record.content,
+        "created_at": record.created_at_unix,
+        "is_target": record.is_target,
+        "read_at": record.read_at_unix,
+        "done_at": record.done_at_unix,
+        "extended_until": record.extended_until,
+        "ignored_at": record.ignored_at_unix,
+    })
+}
+
+const CSV_HEADER: &str = "relation,guild_id,channel_id,message_id,author_id,content,created_at,\
+                          due_at,priority,target_user_ids,read_at,done_at,extended_until,ignored_at";
+
+/// 送信・受信のメンションを1行ずつ出力する。個人設定は JSON でのみ出力する。
+fn render_csv(data: &PersonalData) -> String {
+    let mut lines = vec![CSV_HEADER.to_string()];
+    for record in &data.authored {
+        let targets = record
+            .target_user_ids
+            .iter()
+            .map(u64::to_string)
+            .collect::<Vec<_>>()
+            .join(" ");
+        lines.push(csv_row(&[
+            "authored".to_string(),
+            record.guild_id.to_string(),
+            record.channel_id.to_string(),
+            record.message_id.to_string(),
+            String::new(),
+            record.content.clone(),
+            record.created_at_unix.to_string(),
+            optional(record.due_at_unix),
+            record.priority.as_str().to_string(),
+            targets,
+            String::new(),
+            String::new(),
+            String::new(),
+            String::new(),
+        ]));
+    }
+    for record in &data.received {
+        let relation = if record.is_target {
+            "target"
+        } else {
+            "reaction"
+        };
+        lines.push(csv_row(&[
+            relation.to_string(),
+            record.guild_id.to_string(),
+            record.channel_id.to_string(),
+            record.message_id.to_string(),
+            record.author_id.to_string(),
+            record.content.clone(),
+            record.created_at_unix.to_string(),
+            String::new(),
+            String::new(),
+            String::new(),
+            optional(record.read_at_unix),
+            optional(record.done_at_unix),
+            optional(record.extended_until),
+            optional(record.ignored_at_unix),
+        ]));
+    }
+    lines.join("\n") + "\n"
+}
+
+fn optional(value: Option<i64>) -> String {
+    value.map(|value| value.to_string()).unwrap_or_default()
+}
+
+fn csv_row(fields: &[String]) -> String {
+    fields
+        .iter()
+        .map(|field| csv_escape(field))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+fn csv_escape(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::model::MentionPriority;
+    use crate::usecase::dto::repository::{AllReadNotifyMode, UserNotifySettings};
+
+    struct FakeRepo {
+        data: PersonalData,
+    }
+
+    impl PersonalDataRepository for FakeRepo {
+        async fn fetch_personal_data(
+            &self,
+            _user_id: u64,
+            _guild_id: Option<u64>,
+        ) -> anyhow::Result<PersonalData> {
+            Ok(self.data.clone())
+        }
+
+        async fn delete_personal_data(
+            &self,
+            _user_id: u64,
+            _guild_id: Option<u64>,
+        ) -> anyhow::Result<PersonalDataDeletion> {
+            Ok(PersonalDataDeletion {
+                mentions: 1,
+                targets: 2,
+                reads: 3,
+                dones: 4,
+                settings: 5,
+            })
+        }
+    }
+
+    fn sample_data() -> PersonalData {
+        PersonalData {
+            authored: vec![AuthoredMentionRecord {
+                guild_id: 1,
+                channel_id: 2,
+                message_id: 3,
+                content: "確認お願いします, \"至急\"".into(),
+                created_at_unix: 100,
+                due_at_unix: None,
+                priority: MentionPriority::Urgent,
+                target_user_ids: vec![20, 30],
+            }],
+            received: vec![ReceivedMentionRecord {
+                guild_id: 1,
+                channel_id: 2,
+                message_id: 4,
+                author_id: 20,
+                content: "見てください".into(),
+                created_at_unix: 200,
+                is_target: true,
+                read_at_unix: Some(210),
+                done_at_unix: None,
+                extended_until: None,
+                ignored_at_unix: None,
+            }],
+            settings: Some(UserNotifySettings {
+                digest_dm_disabled: true,
+                tracking_opt_out: false,
+                mutes: vec![UserMute::Author(20)],
+            }),
+            all_read_notify: Some(AllReadNotifyMode::Dm),
+        }
+    }
+
+    fn export_input(format: ExportFormat) -> ExportInput {
+        ExportInput {
+            user_id: 10,
+            guild_id: None,
+            format,
+            now_unix: 1000,
+        }
+    }
+
+    #[tokio::test]
+    async fn exports_json_with_string_ids() {
+        let repo = FakeRepo {
+            data: sample_data(),
+        };
+        let output = export(&repo, export_input(ExportFormat::Json))
+            .await
+            .unwrap();
+
+        assert_eq!(output.filename, "kiduku-10-1000.json");
+        let value: serde_json::Value = serde_json::from_str(&output.body).unwrap();
+        assert_eq!(value["user_id"], "10");
+        assert_eq!(value["authored_mentions"][0]["priority"], "urgent");
+        assert_eq!(
+            value["authored_mentions"][0]["target_user_ids"],
+            json!(["20", "30"])
+        );
+        assert_eq!(value["received_mentions"][0]["read_at"], 210);
+        assert_eq!(value["settings"]["all_read_notify"], "dm");
+        assert_eq!(value["settings"]["mutes"][0]["kind"], "author");
+        assert!(output.content.contains("送信 1件、受信 1件"));
+    }
+
+    #[tokio::test]
+    async fn exports_csv_with_escaped_content() {
+        let repo = FakeRepo {
+            data: sample_data(),
+        };
+        let output = export(&repo, export_input(ExportFormat::Csv))
+            .await
+            .unwrap();
+
+        let lines = output.body.lines().collect::<Vec<_>>();
+        assert_eq!(output.filename, "kiduku-10-1000.csv");
+        assert_eq!(lines[0], CSV_HEADER);
+        assert_eq!(
+            lines[1],
+            "authored,1,2,3,,\"確認お願いします, \"\"至急\"\"\",100,,urgent,20 30,,,,"
+        );
+        assert_eq!(lines[2], "target,1,2,4,20,見てください,200,,,,210,,,");
+    }
+
+    #[tokio::test]
+    async fn delete_reports_counts_and_opt_out_hint_for_self() {
+        let repo = FakeRepo {
+            data: sample_data(),
+        };
+        let content = delete(
+            &repo,
+            DeleteInput {
+                user_id: 10,
+                guild_id: None,
+            },
+        )
+        .await
+        .unwrap();
+
+        assert!(content.contains("送信したメンション: 1件"));
+        assert!(content.contains("個人設定: 5件"));
+        assert!(content.contains("追跡の停止とミュートの設定は"));
+        assert!(content.contains("/通知設定 追跡"));
+    }
+
+    #[tokio::test]
+    async fn delete_on_behalf_omits_personal_settings() {
+        let repo = FakeRepo {
+            data: sample_data(),
+        };
+        let content = delete(
+            &repo,
+            DeleteInput {
+                user_id: 10,
+                guild_id: Some(1),
+            },
+        )
+        .await
+        .unwrap();
+
+        assert!(content.contains("解決: 4件"));
+        assert!(!content.contains("個人設定"));
+        assert!(!content.contains("ミュート"));
+    }
+}
@@ -0,0 +1,459 @@
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use validate_macro::async_validate_return;
+
+use crate::usecase::dto::output::discord_exec::validate_plan;
+use crate::usecase::dto::output::status_color::{COLOR_DONE, COLOR_READ, COLOR_UNREAD};
+use crate::usecase::dto::repository::{MentionStats, ResponderStats, StatsFilter};
+use crate::usecase::dto::{
+    DiscordExecPlan, DiscordExecStep, EmbedFieldPayload, EmbedFooterPayload, EmbedPayload,
+    MessagePayload,
+};
+use crate::usecase::ports::StatsRepository;
+
+const JST_OFFSET_SECS: i64 = 9 * 3600;
+const ONE_DAY_SECS: i64 = 24 * 3600;
+const MAX_CUSTOM_PERIOD_DAYS: i64 = 366;
+const SLOWEST_RESPONDERS_LIMIT: i64 = 5;
+
+const NO_MENTIONS_MESSAGE: &str = "期間内に集計対象のメンションがありません。";
+const MISSING_FROM_MESSAGE: &str = "カスタム期間では開始日 (from) を指定してください。";
+const INVALID_DATE_MESSAGE: &str = "日付は YYYY-MM-DD 形式で指定してください。";
+const INVALID_RANGE_MESSAGE: &str = "開始日は終了日以前の日付を指定してください。";
+const TOO_LONG_RANGE_MESSAGE: &str = "カスタム期間は366日以内で指定してください。";
+
+/// 集計対象。ロールは呼び出し側でメンバーに展開して渡す。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StatsScope {
+    User(u64),
+    Role { role_id: u64, member_ids: Vec<u64> },
+    Channel(u64),
+    Guild,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StatsPeriod {
+    Week,
+    Month,
+    /// JST の日付 (YYYY-MM-DD)。終了日を省略した場合は今日まで。
+    Custom {
+        from: Option<String>,
+        to: Option<String>,
+    },
+}
+
+pub struct StatsInput {
+    pub guild_id: u64,
+    pub scope: StatsScope,
+    pub period: StatsPeriod,
+    pub now_unix: i64,
+}
+
+#[async_validate_return(validate_plan)]
+pub async fn execute<R: StatsRepository>(
+    repo: &R,
+    input: StatsInput,
+) -> anyhow::Result<DiscordExecPlan> {
+    let (since_unix, until_unix) = match resolve_period(&input.period, input.now_unix) {
+        Ok(range) => range,
+        Err(message) => return Ok(ephemeral_response_plan(message)),
+    };
+
+    let filter = build_filter(input.guild_id, &input.scope, since_unix, until_unix);
+    let stats = repo.fetch_mention_stats(&filter).await?;
+    if stats.target_count == 0 {
+        return Ok(ephemeral_response_plan(NO_MENTIONS_MESSAGE));
+    }
+    let slowest = repo
+        .fetch_slowest_responders(&filter, SLOWEST_RESPONDERS_LIMIT)
+        .await?;
+
+    let payload = MessagePayload {
+        embeds: Some(vec![build_embed(
+            &input.scope,
+            since_unix,
+            until_unix,
+            &stats,
+            &slowest,
+        )]),
+        ephemeral: Some(true),
+        ..Default::default()
+    };
+    Ok(DiscordExecPlan::new(vec![DiscordExecStep::Response(
+        payload,
+    )]))
+}
+
+/// 期間を `[since, until)` の UNIX 秒に変換する。不正な指定はユーザー向けのメッセージを返す。
+pub fn resolve_period(period: &StatsPeriod, now_unix: i64) -> Result<(i64, i64), &'static str> {
+    match period {
+        StatsPeriod::Week => Ok((now_unix - 7 * ONE_DAY_SECS, now_unix)),
+        StatsPeriod::Month => Ok((now_unix - 30 * ONE_DAY_SECS, now_unix)),
+        StatsPeriod::Custom { from, to } => {
+            let from = from.as_deref().ok_or(MISSING_FROM_MESSAGE)?;
+            let from = parse_jst_date(from)?;
+            let to = match to.as_deref() {
+                Some(to) => parse_jst_date(to)?,
+                None => jst_today(now_unix),
+            };
+            if from > to {
+                return Err(INVALID_RANGE_MESSAGE);
+            }
+            if (to - from).num_days() >= MAX_CUSTOM_PERIOD_DAYS {
+                return Err(TOO_LONG_RANGE_MESSAGE);
+            }
+            let since = jst_midnight_unix(from);
+            let until = jst_midnight_unix(to + Duration::days(1));
+            Ok((since, until))
+        }
+    }
+}
+
+pub fn parse_jst_date(value: &str) -> Result<NaiveDate, &'static str> {
+    NaiveDate::parse_from_str(value.trim(), "%Y-%m-%d").map_err(|_| INVALID_DATE_MESSAGE)
+}
+
+fn jst_today(now_unix: i64) -> NaiveDate {
+    DateTime::<Utc>::from_timestamp(now_unix + JST_OFFSET_SECS, 0)
+        .map(|dt| dt.date_naive())
+        .unwrap_or_default()
+}
+
+pub fn jst_midnight_unix(date: NaiveDate) -> i64 {
+    date.and_hms_opt(0, 0, 0)
+        .map(|dt| dt.and_utc().timestamp() - JST_OFFSET_SECS)
+        .unwrap_or_default()
+}
+
+fn build_filter(
+    guild_id: u64,
+    scope: &StatsScope,
+    since_unix: i64,
+    until_unix: i64,
+) -> StatsFilter {
+    let mut filter = StatsFilter {
+        guild_id,
+        since_unix,
+        until_unix,
+        ..Default::default()
+    };
+    match scope {
+        StatsScope::User(user_id) => filter.target_user_ids = Some(vec![*user_id]),
+        StatsScope::Role { member_ids, .. } => filter.target_user_ids = Some(member_ids.clone()),
+        StatsScope::Channel(channel_id) => filter.channel_id = Some(*channel_id),
+        StatsScope::Guild => {}
+    }
+    filter
+}
+
+fn build_embed(
+    scope: &StatsScope,
+    since_unix: i64,
+    until_unix: i64,
+    stats: &MentionStats,
+    slowest: &[ResponderStats],
+) -> EmbedPayload {
+    let read_percent = percent(stats.read_count, stats.target_count);
+    let color = if stats.done_count == stats.target_count {
+        COLOR_DONE
+    } else if stats.read_count == stats.target_count {
+        COLOR_READ
+    } else {
+        COLOR_UNREAD
+    };
+
+    let mut embed = EmbedPayload::new()
+        .title("メンション統計")
+        .description(format!(
+            "対象: {}\n期間: {}",
+            scope_label(scope),
+            period_label(since_unix, until_unix)
+        ))
+        .color(color)
+        .field(EmbedFieldPayload::new(
+            "既読率",
+            format!(
+                "{}/{} ({}%)",
+                stats.read_count, stats.target_count, read_percent
+            ),
+            true,
+        ))
+        .field(EmbedFieldPayload::new(
+            "解決数",
+            format!("{}件", stats.done_count),
+            true,
+        ))
+        .field(EmbedFieldPayload::new(
+            "未読のまま",
+            format!("{}件", stats.never_read_count),
+            true,
+        ))
+        .field(EmbedFieldPayload::new(
+            "既読までの時間",
+            format_median_and_p90(stats.read_median_secs, stats.read_p90_secs),
+            false,
+        ))
+        .field(EmbedFieldPayload::new(
+            "解決までの時間",
+            format_median_and_p90(stats.done_median_secs, stats.done_p90_secs),
+            false,
+        ));
+
+    if !slowest.is_empty() {
+        let lines = slowest
+            .iter()
+            .map(|responder| {
+                format!(
+                    "<@{}> 中央値 {} (未読 {}/{}件)",
+                    responder.user_id,
+                    format_duration(responder.read_median_secs),
+                    responder.never_read_count,
+                    responder.target_count
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        embed = embed.field(EmbedFieldPayload::new("応答の遅い対象者", lines, false));
+    }
+
+    embed.footer(EmbedFooterPayload::new(
+        "既読は既読・解決のうち早い方で計測しています",
+    ))
+}
+
+fn scope_label(scope: &StatsScope) -> String {
+    match scope {
+        StatsScope::User(user_id) => format!("<@{}>", user_id),
+        StatsScope::Role { role_id, .. } => format!("<@&{}>", role_id),
+        StatsScope::Channel(channel_id) => format!("<#{}>", channel_id),
+        StatsScope::Guild => "サーバー全体".into(),
+    }
+}
+
+fn period_label(since_unix: i64, until_unix: i64) -> String {
+    // until は排他的なので、表示上は直前の時刻の日付を終了日とする。
+    format!(
+        "{} 〜 {}",
+        format_jst_date(since_unix),
+        format_jst_date(until_unix - 1)
+    )
+}
+
+fn format_jst_date(unix: i64) -> String {
+    DateTime::<Utc>::from_timestamp(unix + JST_OFFSET_SECS, 0)
+        .map(|dt| dt.format("%Y-%m-%d").to_string())
+        .unwrap_or_else(|| "不明".to_string())
+}
+
+fn percent(count: i64, total: i64) -> i64 {
+    if total == 0 {
+        0
+    } else {
+        count * 100 / total
+    }
+}
+
+fn format_median_and_p90(median: Option<f64>, p90: Option<f64>) -> String {
+    match (median, p90) {
+        (Some(median), Some(p90)) => format!(
+            "中央値 {} / p90 {}",
+            format_duration(median),
+            format_duration(p90)
+        ),
+        _ => "データなし".into(),
+    }
+}
+
+/// 秒数を「1日2時間」「3時間4分」「5分」のような表記に丸める。
+pub fn format_duration(secs: f64) -> String {
+    let secs = secs.max(0.0).round() as i64;
+    let days = secs / ONE_DAY_SECS;
+    let hours = secs % ONE_DAY_SECS / 3600;
+    let minutes = secs % 3600 / 60;
+    if days > 0 {
+        format!("{}日{}時間", days, hours)
+    } else if hours > 0 {
+        format!("{}時間{}分", hours, minutes)
+    } else if minutes > 0 {
+        format!("{}分", minutes)
+    } else {
+        format!("{}秒", secs)
+    }
+}
+
+fn ephemeral_response_plan(content: &str) -> DiscordExecPlan {
+    DiscordExecPlan::new(vec![DiscordExecStep::Response(MessagePayload {
+        content: Some(content.to_string()),
+        ephemeral: Some(true),
+        ..Default::default()
+    })])
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use super::*;
+
+    struct FakeRepository {
+        stats: MentionStats,
+        slowest: Vec<ResponderStats>,
+        filter: Mutex<Option<StatsFilter>>,
+    }
+
+    impl FakeRepository {
+        fn new(stats: MentionStats, slowest: Vec<ResponderStats>) -> Self {
+            Self {
+                stats,
+                slowest,
+                filter: Mutex::new(None),
+            }
+        }
+    }
+
+    impl StatsRepository for FakeRepository {
+        async fn fetch_mention_stats(&self, filter: &StatsFilter) -> anyhow::Result<MentionStats> {
+            *self.filter.lock().unwrap() = Some(filter.clone());
+            Ok(self.stats.clone())
+        }
+
+        async fn fetch_slowest_responders(
+            &self,
+            _filter: &StatsFilter,
+            _limit: i64,
+        ) -> anyhow::Result<Vec<ResponderStats>> {
+            Ok(self.slowest.clone())
+        }
+    }
+
+    // 2024-01-10 12:00:00 JST
+    const NOW: i64 = 1_704_855_600;
+
+    fn input(scope: StatsScope, period: StatsPeriod) -> StatsInput {
+        StatsInput {
+            guild_id: 1,
+            scope,
+            period,
+            now_unix: NOW,
+        }
+    }
+
+    fn custom(from: Option<&str>, to: Option<&str>) -> StatsPeriod {
+        StatsPeriod::Custom {
+            from: from.map(str::to_string),
+            to: to.map(str::to_string),
+        }
+    }
+
+    fn single_response(plan: DiscordExecPlan) -> MessagePayload {
+        match plan.into_steps().into_iter().next() {
+            Some(DiscordExecStep::Response(payload)) => payload,
+            other => panic!("unexpected exec step: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn resolves_custom_period_as_inclusive_jst_days() {
+        let (since, until) =
+            resolve_period(&custom(Some("2024-01-01"), Some("2024-01-02")), NOW).unwrap();
+        // 2024-01-01 00:00 JST 〜 2024-01-03 00:00 JST
+        assert_eq!(since, 1_704_034_800);
+        assert_eq!(until, 1_704_207_600);
+        assert_eq!(period_label(since, until), "2024-01-01 〜 2024-01-02");
+    }
+
+    #[test]
+    fn defaults_custom_end_to_today() {
+        let (_, until) = resolve_period(&custom(Some("2024-01-01"), None), NOW).unwrap();
+        assert_eq!(format_jst_date(until - 1), "2024-01-10");
+    }
+
+    #[test]
+    fn rejects_invalid_custom_periods() {
+        assert_eq!(
+            resolve_period(&custom(None, None), NOW),
+            Err(MISSING_FROM_MESSAGE)
+        );
+        assert_eq!(
+            resolve_period(&custom(Some("2024/01/01"), None), NOW),
+            Err(INVALID_DATE_MESSAGE)
+        );
+        assert_eq!(
+            resolve_period(&custom(Some("2024-01-05"), Some("2024-01-01")), NOW),
+            Err(INVALID_RANGE_MESSAGE)
+        );
+        assert_eq!(
+            resolve_period(&custom(Some("2022-01-01"), Some("2024-01-01")), NOW),
+            Err(TOO_LONG_RANGE_MESSAGE)
+        );
+    }
+
+    #[test]
+    fn formats_durations_by_magnitude() {
+        assert_eq!(format_duration(42.0), "42秒");
+        assert_eq!(format_duration(125.0), "2分");
+        assert_eq!(format_duration(3_780.0), "1時間3分");
+        assert_eq!(format_duration(93_600.0), "1日2時間");
+    }
+
+    #[tokio::test]
+    async fn scopes_role_to_member_ids() {
+        let repo = FakeRepository::new(MentionStats::default(), vec![]);
+        let scope = StatsScope::Role {
+            role_id: 9,
+            member_ids: vec![10, 11],
+        };
+        let plan = execute(&repo, input(scope, StatsPeriod::Week))
+            .await
+            .expect("expected plan");
+
+        let filter = repo
+            .filter
+            .lock()
+            .unwrap()
+            .clone()
+            .expect("expected filter");
+        assert_eq!(filter.target_user_ids, Some(vec![10, 11]));
+        assert_eq!(filter.channel_id, None);
+        assert_eq!(filter.until_unix - filter.since_unix, 7 * ONE_DAY_SECS);
+        assert_eq!(
+            single_response(plan).content.as_deref(),
+            Some(NO_MENTIONS_MESSAGE)
+        );
+    }
+
+    #[tokio::test]
+    async fn renders_stats_embed() {
+        let stats = MentionStats {
+            target_count: 4,
+            read_count: 3,
+            done_count: 1,
+            never_read_count: 1,
+            read_median_secs: Some(600.0),
+            read_p90_secs: Some(7_200.0),
+            done_median_secs: Some(86_400.0),
+            done_p90_secs: Some(86_400.0),
+        };
+        let slowest = vec![ResponderStats {
+            user_id: 10,
+            target_count: 2,
+            never_read_count: 1,
+            read_median_secs: 7_200.0,
+        }];
+        let repo = FakeRepository::new(stats, slowest);
+        let plan = execute(&repo, input(StatsScope::Channel(5), StatsPeriod::Month))
+            .await
+            .expect("expected plan");
+
+        let payload = single_response(plan);
+        assert_eq!(payload.ephemeral, Some(true));
+        let embed = &payload.embeds.expect("expected embeds")[0];
+        assert!(embed
+            .description
+            .as_deref()
+            .is_some_and(|d| d.starts_with("対象: <#5>")));
+        assert_eq!(embed.color, Some(COLOR_UNREAD));
+        assert_eq!(embed.fields[0].value, "3/4 (75%)");
+        assert_eq!(embed.fields[3].value, "中央値 10分 / p90 2時間0分");
+        assert_eq!(embed.fields[5].value, "<@10> 中央値 2時間0分 (未読 1/2件)");
+    }
+}
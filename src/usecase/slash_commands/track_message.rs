@@ -0,0 +1,324 @@
+use validate_macro::sync_validate_return;
+
+use crate::domain::model::{ContentStorageMode, MentionPriority};
+use crate::usecase::dto::output::discord_exec::validate_plan;
+use crate::usecase::dto::repository::NewMention;
+use crate::usecase::dto::{
+    ActionRowPayload, DiscordExecPlan, DiscordExecStep, MessagePayload, PlanValidationError,
+    SelectMenuPayload,
+};
+use crate::usecase::on_message::{classify_priority, resolve_due_at};
+use crate::usecase::ports::TrackingRepository;
+
+/// 追跡対象を選ぶセレクトメニューの custom_id 接頭辞。
+/// 形式: "mm:track:{channel_id}:{message_id}:{invoker_id}"
+pub const TRACK_SELECT_CUSTOM_ID_PREFIX: &str = "mm:track:";
+/// Discord のセレクトメニューで選べる上限。
+const MAX_TRACK_SELECTIONS: u8 = 25;
+
+const FORBIDDEN_MESSAGE: &str =
+    "このメッセージの追跡を変更できるのは、送信者またはメッセージの管理権限を持つメンバーだけです。";
+
+/// 追跡を開始・停止できるのは送信者本人かモデレーター（メッセージの管理権限）。
+pub fn can_manage_tracking(invoker_id: u64, author_id: u64, is_moderator: bool) -> bool {
+    invoker_id == author_id || is_moderator
+}
+
+pub fn track_select_custom_id(channel_id: u64, message_id: u64, invoker_id: u64) -> String {
+    format!("{TRACK_SELECT_CUSTOM_ID_PREFIX}{channel_id}:{message_id}:{invoker_id}")
+}
+
+/// `(channel_id, message_id, invoker_id)` を返す。
+pub fn parse_track_select_custom_id(custom_id: &str) -> Option<(u64, u64, u64)> {
+    let mut parts = custom_id
+        .strip_prefix(TRACK_SELECT_CUSTOM_ID_PREFIX)?
+        .splitn(3, ':')
+        .map(|part| part.parse::<u64>().ok());
+    Some((parts.next()??, parts.next()??, parts.next()??))
+}
+
+/// 「追跡を開始」の最初の応答。対象のユーザー・ロールを選ぶメニューを表示する。
+#[sync_validate_return(validate_plan)]
+pub fn build_start_prompt(
+    channel_id: u64,
+    message_id: u64,
+    invoker_id: u64,
+    can_manage: bool,
+) -> Result<DiscordExecPlan, PlanValidationError> {
+    if !can_manage {
+        return Ok(ephemeral_response_plan(FORBIDDEN_MESSAGE));
+    }
+
+    let select =
+        SelectMenuPayload::mentionables(track_select_custom_id(channel_id, message_id, invoker_id))
+            .placeholder("追跡するユーザー・ロールを選択")
+            .min_values(1)
+            .max_values(MAX_TRACK_SELECTIONS);
+
+    Ok(DiscordExecPlan::new(vec![DiscordExecStep::Response(
+        MessagePayload {
+            content: Some(
+                "このメッセージの既読を追跡する対象を選んでください。ロールを選ぶとそのメンバー全員が対象になります。"
+                    .to_string(),
+            ),
+            components: Some(vec![ActionRowPayload::SelectMenu(select)]),
+            ephemeral: Some(true),
+            ..Default::default()
+        },
+    )]))
+}
+
+pub struct StartTrackingInput {
+    pub guild_id: u64,
+    pub channel_id: u64,
+    pub message_id: u64,
+    pub author_id: u64,
+    pub content: String,
+    pub created_at_unix: i64,
+    /// 選択されたユーザーと、選択されたロールのメンバー（Bot を除く）。
+    pub target_user_ids: Vec<u64>,
+    pub channel_default_priority: Option<MentionPriority>,
+    pub content_storage: ContentStorageMode,
+}
+
+pub struct StartTrackingOutput {
+    /// 選択メニューを置き換える結果メッセージ。
+    pub content: String,
+    /// `Some` の場合は追跡を開始したので、リアクションを付ける。
+    pub priority: Option<MentionPriority>,
+}
+
+/// 選択された対象者でメッセージを記録する。記録済みなら対象者を追加する。
+pub async fn start<R: TrackingRepository>(
+    repo: &R,
+    input: StartTrackingInput,
+) -> anyhow::Result<StartTrackingOutput> {
+    let mut targets = input.target_user_ids;
+    targets.sort_unstable();
+    targets.dedup();
+    if targets.is_empty() {
+        return Ok(StartTrackingOutput {
+            content: "選択した対象に追跡できるメンバーがいません。".to_string(),
+            priority: None,
+        });
+    }
+
+    let priority = classify_priority::execute(&input.content, input.channel_default_priority);
+    let target_count = targets.len();
+    repo.insert_mention(NewMention {
+        guild_id: input.guild_id,
+        channel_id: input.channel_id,
+        message_id: input.message_id,
+        author_id: input.author_id,
+        due_at_unix: resolve_due_at::execute(&input.content, input.created_at_unix),
+        content: input.content,
+        mention_everyone: false,
+        created_at_unix: input.created_at_unix,
+        priority,
+        content_storage: input.content_storage,
+        targets,
+    })
+    .await?;
+
+    Ok(StartTrackingOutput {
+        content: format!("追跡を開始しました（対象 {}人）。", target_count),
+        priority: Some(priority),
+    })
+}
+
+pub struct StopTrackingInput {
+    pub guild_id: u64,
+    pub message_id: u64,
+    pub can_manage: bool,
+    pub now_unix: i64,
+}
+
+pub struct StopTrackingOutput {
+    pub response: DiscordExecPlan,
+    /// `true` の場合は Bot のリアクションを取り除く。
+    pub remove_reactions: bool,
+}
+
+/// メッセージの記録（対象者・既読・解決を含む）を削除する。
+/// 停止したメッセージは起動時の補完でも取り込まない。
+pub async fn stop<R: TrackingRepository>(
+    repo: &R,
+    input: StopTrackingInput,
+) -> anyhow::Result<StopTrackingOutput> {
+    if !input.can_manage {
+        return Ok(StopTrackingOutput {
+            response: ephemeral_response_plan(FORBIDDEN_MESSAGE),
+            remove_reactions: false,
+        });
+    }
+
+    let deleted = repo
+        .stop_tracking_message(input.guild_id, input.message_id, input.now_unix)
+        .await?;
+    let content = if deleted > 0 {
+        "追跡を停止しました。記録した既読・解決状況も削除しました。"
+    } else {
+        "このメッセージは追跡されていません。"
+    };
+    Ok(StopTrackingOutput {
+        response: ephemeral_response_plan(content),
+        remove_reactions: true,
+    })
+}
+
+fn ephemeral_response_plan(content: &str) -> DiscordExecPlan {
+    DiscordExecPlan::new(vec![DiscordExecStep::Response(MessagePayload {
+        content: Some(content.to_string()),
+        ephemeral: Some(true),
+        ..Default::default()
+    })])
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use super::*;
+    use crate::usecase::dto::SelectMenuKindPayload;
+
+    #[derive(Default)]
+    struct FakeRepository {
+        inserted: Mutex<Vec<NewMention>>,
+        stopped: Mutex<Vec<u64>>,
+        existing: bool,
+    }
+
+    impl TrackingRepository for FakeRepository {
+        async fn insert_mention(&self, mention: NewMention) -> anyhow::Result<()> {
+            self.inserted.lock().unwrap().push(mention);
+            Ok(())
+        }
+
+        async fn stop_tracking_message(
+            &self,
+            _guild_id: u64,
+            message_id: u64,
+            _stopped_at_unix: i64,
+        ) -> anyhow::Result<u64> {
+            self.stopped.lock().unwrap().push(message_id);
+            Ok(u64::from(self.existing))
+        }
+    }
+
+    fn start_input(target_user_ids: Vec<u64>) -> StartTrackingInput {
+        StartTrackingInput {
+            guild_id: 1,
+            channel_id: 2,
+            message_id: 3,
+            author_id: 4,
+            content: "[FYI] 金曜までに資料を見ておいてください".into(),
+            created_at_unix: 1_792_544_400,
+            target_user_ids,
+            channel_default_priority: None,
+            content_storage: ContentStorageMode::Full,
+        }
+    }
+
+    #[test]
+    fn only_author_or_moderator_can_manage() {
+        assert!(can_manage_tracking(4, 4, false));
+        assert!(can_manage_tracking(5, 4, true));
+        assert!(!can_manage_tracking(5, 4, false));
+    }
+
+    #[test]
+    fn round_trips_select_custom_id() {
+        let custom_id = track_select_custom_id(2, 3, 4);
+        assert_eq!(custom_id, "mm:track:2:3:4");
+        assert_eq!(parse_track_select_custom_id(&custom_id), Some((2, 3, 4)));
+        assert_eq!(parse_track_select_custom_id("mm:track:2:x:4"), None);
+        assert_eq!(parse_track_select_custom_id("mm:p:0:0:4"), None);
+    }
+
+    #[test]
+    fn prompts_with_mentionable_select() {
+        let plan = build_start_prompt(2, 3, 4, true).expect("expected plan");
+        let [DiscordExecStep::Response(payload)] = plan.steps() else {
+            panic!("expected single response");
+        };
+        let rows = payload.components.as_ref().expect("expected components");
+        let ActionRowPayload::SelectMenu(select) = &rows[0] else {
+            panic!("expected select menu");
+        };
+        assert_eq!(select.custom_id, "mm:track:2:3:4");
+        assert!(matches!(
+            select.kind,
+            SelectMenuKindPayload::Mentionable { .. }
+        ));
+        assert_eq!(select.max_values, Some(MAX_TRACK_SELECTIONS));
+    }
+
+    #[test]
+    fn rejects_prompt_without_permission() {
+        let plan = build_start_prompt(2, 3, 5, false).expect("expected plan");
+        let [DiscordExecStep::Response(payload)] = plan.steps() else {
+            panic!("expected single response");
+        };
+        assert_eq!(payload.content.as_deref(), Some(FORBIDDEN_MESSAGE));
+        assert!(payload.components.is_none());
+    }
+
+    #[tokio::test]
+    async fn records_selected_targets_with_priority_and_due() {
+        let repo = FakeRepository::default();
+        let output = start(&repo, start_input(vec![11, 10, 11]))
+            .await
+            .expect("expected output");
+
+        assert_eq!(output.content, "追跡を開始しました（対象 2人）。");
+        assert_eq!(output.priority, Some(MentionPriority::Fyi));
+        let inserted = repo.inserted.lock().unwrap();
+        assert_eq!(inserted[0].targets, vec![10, 11]);
+        assert!(inserted[0].due_at_unix.is_some());
+    }
+
+    #[tokio::test]
+    async fn does_not_record_without_targets() {
+        let repo = FakeRepository::default();
+        let output = start(&repo, start_input(Vec::new()))
+            .await
+            .expect("expected output");
+
+        assert!(output.priority.is_none());
+        assert!(repo.inserted.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn stops_tracking_and_removes_reactions() {
+        let repo = FakeRepository {
+            existing: true,
+            ..Default::default()
+        };
+        let output = stop(
+            &repo,
+            StopTrackingInput {
+                guild_id: 1,
+                message_id: 3,
+                can_manage: true,
+                now_unix: 100,
+            },
+        )
+        .await
+        .expect("expected output");
+        assert!(output.remove_reactions);
+
+        let output = stop(
+            &repo,
+            StopTrackingInput {
+                guild_id: 1,
+                message_id: 3,
+                can_manage: false,
+                now_unix: 100,
+            },
+        )
+        .await
+        .expect("expected output");
+        assert!(!output.remove_reactions);
+        assert_eq!(*repo.stopped.lock().unwrap(), vec![3]);
+    }
+}
@@ -1,7 +1,31 @@
+use std::collections::HashSet;
+
 use serenity::model::prelude::UserId;
+use validate_macro::async_validate_return;
 
+use crate::domain::model::MentionPriority;
 use crate::domain::policy::read_status_calc;
-use crate::infrastructure::db::StoredMention;
+use crate::usecase::dto::output::discord_exec::{validate_plan, MAX_EMBED_FIELD_VALUE_CHARS};
+use crate::usecase::dto::output::status_color::{COLOR_DONE, COLOR_READ, COLOR_UNREAD};
+use crate::usecase::dto::output::text::{format_due, truncate};
+use crate::usecase::dto::repository::StoredMention;
+use crate::usecase::dto::{
+    DiscordExecPlan, DiscordExecStep, EmbedFieldPayload, EmbedPayload, MessagePayload,
+};
+use crate::usecase::ports::MentionRepository;
+
+const NOT_RECORDED_MESSAGE: &str = "このメッセージは記録されていません。メンションが含まれていないか、Bot起動前に送信された可能性があります。メッセージのアプリメニュー「追跡を開始」から対象を選んで記録できます。";
+const NO_TARGETS_MESSAGE: &str = "このメッセージにはメンション対象者が記録されていません。";
+
+pub struct ViewReadStatusInput {
+    pub message_id: u64,
+    pub now_unix: i64,
+    /// スレッド内の @everyone/@here の場合のみ、現在のスレッド参加者を渡す。
+    /// 参加者以外の対象者は DB からも取り除く。
+    pub thread_member_ids: Option<HashSet<u64>>,
+    /// Discord から取得した現在の本文。本文を保存しない設定のギルドではこれを表示する。
+    pub live_content: String,
+}
 
 pub struct ViewReadStatusOutput {
     pub guild_id: u64,
@@ -9,12 +33,48 @@ pub struct ViewReadStatusOutput {
     pub message_id: u64,
     pub message_content: String,
     pub author_id: u64,
+    pub created_at_unix: i64,
+    pub due_at_unix: Option<i64>,
+    pub priority: MentionPriority,
     pub read_users: Vec<UserId>,
     pub unread_users: Vec<UserId>,
     pub done_users: Vec<UserId>,
 }
 
-pub fn execute(mention: StoredMention) -> Option<ViewReadStatusOutput> {
+#[async_validate_return(validate_plan)]
+pub async fn execute<R: MentionRepository>(
+    repo: &R,
+    input: ViewReadStatusInput,
+) -> anyhow::Result<DiscordExecPlan> {
+    let mut mention = match repo.fetch_mention_by_message_id(input.message_id).await? {
+        Some(mention) => mention,
+        None => return Ok(ephemeral_response_plan(NOT_RECORDED_MESSAGE)),
+    };
+
+    if let Some(member_ids) = &input.thread_member_ids {
+        reconcile_thread_targets(repo, &mut mention, member_ids).await?;
+    }
+
+    if mention.content.is_empty() {
+        mention.content = input.live_content;
+    }
+
+    let output = match build_output(mention) {
+        Some(output) => output,
+        None => return Ok(ephemeral_response_plan(NO_TARGETS_MESSAGE)),
+    };
+
+    let payload = MessagePayload {
+        embeds: Some(vec![build_embed(&output, input.now_unix)]),
+        ephemeral: Some(true),
+        ..Default::default()
+    };
+    Ok(DiscordExecPlan::new(vec![DiscordExecStep::Response(
+        payload,
+    )]))
+}
+
+pub fn build_output(mention: StoredMention) -> Option<ViewReadStatusOutput> {
     if mention.target_user_ids.is_empty() {
         return None;
     }
@@ -48,8 +108,379 @@ pub fn execute(mention: StoredMention) -> Option<ViewReadStatusOutput> {
         message_id: mention.message_id,
         message_content: mention.content,
         author_id: mention.author_id,
+        created_at_unix: mention.created_at_unix,
+        due_at_unix: mention.due_at_unix,
+        priority: mention.priority,
         read_users,
         unread_users,
         done_users,
     })
 }
+
+async fn reconcile_thread_targets<R: MentionRepository>(
+    repo: &R,
+    mention: &mut StoredMention,
+    member_ids: &HashSet<u64>,
+) -> anyhow::Result<()> {
+    let before_len = mention.target_user_ids.len();
+    mention
+        .target_user_ids
+        .retain(|user_id| member_ids.contains(user_id));
+
+    if mention.target_user_ids.len() == before_len {
+        return Ok(());
+    }
+
+    let deleted = repo
+        .delete_targets_except_by_message_id(mention.message_id, &mention.target_user_ids)
+        .await?;
+
+    tracing::info!(
+        "reconciled mention targets for thread everyone/here in view_read_status: message_id={}, removed={}",
+        mention.message_id,
+        deleted
+    );
+
+    Ok(())
+}
+
+fn ephemeral_response_plan(content: &str) -> DiscordExecPlan {
+    DiscordExecPlan::new(vec![DiscordExecStep::Response(MessagePayload {
+        content: Some(content.to_string()),
+        ephemeral: Some(true),
+        ..Default::default()
+    })])
+}
+
+fn build_embed(output: &ViewReadStatusOutput, now_unix: i64) -> EmbedPayload {
+    let message_link = format!(
+        "https://discord.com/channels/{}/{}/{}",
+        output.guild_id, output.channel_id, output.message_id
+    );
+    let mut snippet = truncate(&output.message_content, 100);
+    if snippet.is_empty() {
+        snippet = "（本文なし）".into();
+    }
+    let total = output.read_users.len() + output.unread_users.len();
+    let read_count = output.read_users.len();
+    let percent = (read_count * 100).checked_div(total).unwrap_or(0);
+    let read_summary = format!("{}/{} ({}%)", read_count, total, percent);
+    let read_users_text = format_user_mentions_limited(
+        &output.read_users,
+        available_chars_for_read_users(&read_summary),
+    );
+
+    let mut embed = EmbedPayload::new()
+        .title(title(output.priority))
+        .url(message_link.clone())
+        .color(status_color(output))
+        .timestamp(output.created_at_unix)
+        .description(format!("[メッセージを開く]({})", message_link))
+        .field(EmbedFieldPayload::new("内容", snippet, false));
+    if let Some(due_at) = output.due_at_unix {
+        let all_done = output.done_users.len() >= total;
+        embed = embed.field(EmbedFieldPayload::new(
+            "期限",
+            format_due(due_at, now_unix, all_done),
+            false,
+        ));
+    }
+    embed
+        .field(EmbedFieldPayload::new(
+            "既読",
+            format!("{}\n{}", read_summary, read_users_text),
+            false,
+        ))
+        .field(EmbedFieldPayload::new(
+            "未読",
+            format_user_mentions_limited(&output.unread_users, MAX_EMBED_FIELD_VALUE_CHARS),
+            false,
+        ))
+        .field(EmbedFieldPayload::new(
+            "解決済み",
+            format_user_mentions_limited(&output.done_users, MAX_EMBED_FIELD_VALUE_CHARS),
+            false,
+        ))
+}
+
+fn title(priority: MentionPriority) -> &'static str {
+    match priority {
+        MentionPriority::Normal => "既読状況確認",
+        MentionPriority::Urgent => "既読状況確認（🚨 至急）",
+        MentionPriority::Fyi => "既読状況確認（ℹ️ FYI）",
+    }
+}
+
+/// 未読者がいれば赤、全員が解決済みなら緑、それ以外（全員既読）は黄。
+/// FYI は解決を求めないため、全員既読で緑とする。
+fn status_color(output: &ViewReadStatusOutput) -> u32 {
+    let target_count = output.read_users.len() + output.unread_users.len();
+    if !output.unread_users.is_empty() {
+        COLOR_UNREAD
+    } else if output.done_users.len() >= target_count || !output.priority.requires_action() {
+        COLOR_DONE
+    } else {
+        COLOR_READ
+    }
+}
+
+fn available_chars_for_read_users(read_summary: &str) -> usize {
+    let used = read_summary.chars().count() + 1;
+    MAX_EMBED_FIELD_VALUE_CHARS.saturating_sub(used)
+}
+
+fn format_user_mentions_limited(users: &[UserId], max_chars: usize) -> String {
+    if users.is_empty() {
+        return "なし".into();
+    }
+
+    let mentions = users
+        .iter()
+        .map(|id| format!("<@{}>", id.get()))
+        .collect::<Vec<_>>();
+
+    let mut body = String::new();
+    for (index, mention) in mentions.iter().enumerate() {
+        let separator = if body.is_empty() { "" } else { " " };
+        let remaining_after = mentions.len().saturating_sub(index + 1);
+        let predicted = format!("{}{}{}", body, separator, mention);
+        if remaining_after == 0 {
+            if predicted.chars().count() <= max_chars {
+                body = predicted;
+                return body;
+            }
+            append_omitted_suffix(&mut body, mentions.len().saturating_sub(index), max_chars);
+            return body;
+        }
+
+        let suffix_if_truncated = format!(" …他{}人", remaining_after);
+        if predicted.chars().count() + suffix_if_truncated.chars().count() <= max_chars {
+            body = predicted;
+            continue;
+        }
+
+        append_omitted_suffix(&mut body, mentions.len().saturating_sub(index), max_chars);
+        return body;
+    }
+
+    if body.is_empty() {
+        "なし".into()
+    } else {
+        body
+    }
+}
+
+fn append_omitted_suffix(body: &mut String, omitted_count: usize, max_chars: usize) {
+    if omitted_count == 0 {
+        return;
+    }
+    let suffix = format!(" …他{}人", omitted_count);
+    if body.chars().count() + suffix.chars().count() <= max_chars {
+        body.push_str(&suffix);
+        return;
+    }
+
+    let allowed_body_len = max_chars.saturating_sub(suffix.chars().count());
+    let trimmed = body.chars().take(allowed_body_len).collect::<String>();
+    *body = trimmed;
+    body.push_str(&suffix);
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use super::*;
+
+    struct FakeRepository {
+        mention: Option<StoredMention>,
+        kept: Mutex<Option<Vec<u64>>>,
+    }
+
+    impl FakeRepository {
+        fn new(mention: Option<StoredMention>) -> Self {
+            Self {
+                mention,
+                kept: Mutex::new(None),
+            }
+        }
+    }
+
+    impl MentionRepository for FakeRepository {
+        async fn fetch_mention_by_message_id(
+            &self,
+            _message_id: u64,
+        ) -> anyhow::Result<Option<StoredMention>> {
+            Ok(self.mention.clone())
+        }
+
+        async fn delete_targets_except_by_message_id(
+            &self,
+            _message_id: u64,
+            keep_user_ids: &[u64],
+        ) -> anyhow::Result<u64> {
+            *self.kept.lock().unwrap() = Some(keep_user_ids.to_vec());
+            Ok(1)
+        }
+
+        async fn record_done(
+            &self,
+            _message_id: u64,
+            _user_id: u64,
+            _done_at_unix: i64,
+        ) -> anyhow::Result<bool> {
+            Ok(true)
+        }
+    }
+
+    fn stored_mention(targets: Vec<u64>, reads: Vec<u64>) -> StoredMention {
+        StoredMention {
+            author_id: 1,
+            guild_id: 2,
+            channel_id: 3,
+            message_id: 4,
+            content: "<@10> 確認お願いします".into(),
+            mention_everyone: false,
+            created_at_unix: 0,
+            due_at_unix: None,
+            priority: MentionPriority::Normal,
+            target_user_ids: targets,
+            read_user_ids: reads,
+            done_user_ids: Vec::new(),
+        }
+    }
+
+    fn input(thread_member_ids: Option<HashSet<u64>>) -> ViewReadStatusInput {
+        ViewReadStatusInput {
+            message_id: 4,
+            now_unix: 0,
+            thread_member_ids,
+            live_content: "<@10> 現在の本文".into(),
+        }
+    }
+
+    fn single_response(plan: DiscordExecPlan) -> MessagePayload {
+        match plan.into_steps().into_iter().next() {
+            Some(DiscordExecStep::Response(payload)) => payload,
+            other => panic!("unexpected exec step: {:?}", other),
+        }
+    }
+
+    fn users(count: usize) -> Vec<UserId> {
+        (1..=count as u64).map(UserId::new).collect()
+    }
+
+    #[tokio::test]
+    async fn responds_not_recorded_when_mention_is_missing() {
+        let repo = FakeRepository::new(None);
+        let plan = execute(&repo, input(None)).await.expect("expected plan");
+        let payload = single_response(plan);
+        assert_eq!(payload.content.as_deref(), Some(NOT_RECORDED_MESSAGE));
+        assert_eq!(payload.ephemeral, Some(true));
+    }
+
+    #[tokio::test]
+    async fn responds_no_targets_when_targets_are_empty() {
+        let repo = FakeRepository::new(Some(stored_mention(vec![], vec![])));
+        let plan = execute(&repo, input(None)).await.expect("expected plan");
+        let payload = single_response(plan);
+        assert_eq!(payload.content.as_deref(), Some(NO_TARGETS_MESSAGE));
+    }
+
+    #[tokio::test]
+    async fn builds_read_status_embed() {
+        let repo = FakeRepository::new(Some(stored_mention(vec![10, 11], vec![10])));
+        let plan = execute(&repo, input(None)).await.expect("expected plan");
+        let payload = single_response(plan);
+        let embed = &payload.embeds.expect("expected embeds")[0];
+        assert_eq!(embed.title.as_deref(), Some("既読状況確認"));
+        assert_eq!(embed.fields[1].value, "1/2 (50%)\n<@10>");
+        assert_eq!(embed.fields[2].value, "<@11>");
+        assert_eq!(embed.color, Some(COLOR_UNREAD));
+        assert!(repo.kept.lock().unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn shows_live_content_when_content_is_not_stored() {
+        let mut mention = stored_mention(vec![10], Vec::new());
+        mention.content = String::new();
+        let repo = FakeRepository::new(Some(mention));
+        let plan = execute(&repo, input(None)).await.expect("expected plan");
+        let payload = single_response(plan);
+        let embed = &payload.embeds.expect("expected embeds")[0];
+        assert!(embed.fields[0].value.contains("現在の本文"));
+    }
+
+    #[tokio::test]
+    async fn colors_embed_green_when_everyone_is_done() {
+        let mut mention = stored_mention(vec![10, 11], vec![10, 11]);
+        mention.done_user_ids = vec![10, 11];
+        let repo = FakeRepository::new(Some(mention));
+        let plan = execute(&repo, input(None)).await.expect("expected plan");
+        let embed = &single_response(plan).embeds.expect("expected embeds")[0];
+        assert_eq!(embed.color, Some(COLOR_DONE));
+    }
+
+    #[tokio::test]
+    async fn labels_fyi_and_colors_green_when_everyone_read() {
+        let mut mention = stored_mention(vec![10, 11], vec![10, 11]);
+        mention.priority = MentionPriority::Fyi;
+        let repo = FakeRepository::new(Some(mention));
+        let plan = execute(&repo, input(None)).await.expect("expected plan");
+        let embed = &single_response(plan).embeds.expect("expected embeds")[0];
+        assert_eq!(embed.title.as_deref(), Some("既読状況確認（ℹ️ FYI）"));
+        assert_eq!(embed.color, Some(COLOR_DONE));
+    }
+
+    #[tokio::test]
+    async fn shows_overdue_deadline_while_targets_remain_unresolved() {
+        let mut mention = stored_mention(vec![10, 11], vec![10]);
+        mention.due_at_unix = Some(-60);
+        let repo = FakeRepository::new(Some(mention));
+        let plan = execute(&repo, input(None)).await.expect("expected plan");
+        let embed = &single_response(plan).embeds.expect("expected embeds")[0];
+        assert_eq!(embed.fields[1].name, "期限");
+        assert!(embed.fields[1].value.starts_with("⚠️ 期限切れ"));
+        assert_eq!(embed.fields[2].value, "1/2 (50%)\n<@10>");
+    }
+
+    #[tokio::test]
+    async fn removes_targets_outside_thread_members() {
+        let repo = FakeRepository::new(Some(stored_mention(vec![10, 11, 12], vec![])));
+        let members = HashSet::from([10, 12]);
+        let plan = execute(&repo, input(Some(members)))
+            .await
+            .expect("expected plan");
+        let payload = single_response(plan);
+        let embed = &payload.embeds.expect("expected embeds")[0];
+        assert_eq!(embed.fields[2].value, "<@10> <@12>");
+        assert_eq!(*repo.kept.lock().unwrap(), Some(vec![10, 12]));
+    }
+
+    #[test]
+    fn returns_none_label_for_empty_users() {
+        assert_eq!(format_user_mentions_limited(&[], 1024), "なし");
+    }
+
+    #[test]
+    fn keeps_full_list_when_under_limit() {
+        let text = format_user_mentions_limited(&users(3), 1024);
+        assert_eq!(text, "<@1> <@2> <@3>");
+    }
+
+    #[test]
+    fn truncates_with_omitted_count_when_over_limit() {
+        let text = format_user_mentions_limited(&users(200), 80);
+        assert!(text.contains("他"));
+        assert!(text.chars().count() <= 80);
+    }
+
+    #[test]
+    fn read_field_fits_embed_limit_for_many_users() {
+        let summary = "123/456 (27%)";
+        let users_text =
+            format_user_mentions_limited(&users(500), available_chars_for_read_users(summary));
+        let field = format!("{summary}\n{users_text}");
+        assert!(field.chars().count() <= MAX_EMBED_FIELD_VALUE_CHARS);
+    }
+}